@@ -0,0 +1,118 @@
+// annotation_mod.rs
+
+//! line-anchored annotations collected into the review comment
+//! While reading the source code, the reviewer attaches notes to `file:line` ranges.
+//! The notes are a draft in sled (db_annotation_mod). The draft stays after the save and it is the source of truth:
+//! on every save the section `## Annotations` at the end of the comment is generated again from the whole draft.
+
+use crate::common_structs_mod::*;
+
+/// the heading of the generated section, always at the end of the comment
+const ANNOTATIONS_HEADING: &str = "## Annotations";
+
+/// the comment for the proof with the draft annotations of this crate version
+/// If there was never a draft, the comment is unchanged. An empty draft removes the section.
+pub fn comment_with_annotations(crate_name: &str, crate_version: &str, comment_md: &str) -> anyhow::Result<String> {
+    let crate_name_version = crate::utils_mod::crate_version_join(crate_name, crate_version);
+    match crate::db_sled_mod::db_annotation_mod::read(&crate_name_version)? {
+        None => Ok(comment_md.to_string()),
+        Some(annotations) => Ok(replace_annotations_section(comment_md, &annotations)),
+    }
+}
+
+/// remove the old generated section and append the new one
+pub fn replace_annotations_section(comment_md: &str, annotations: &[AnnotationItemData]) -> String {
+    let mut comment = strip_annotations_section(comment_md).trim_end().to_string();
+    let section = annotations_to_markdown(annotations);
+    if !section.is_empty() {
+        if !comment.is_empty() {
+            comment.push_str("\n\n");
+        }
+        comment.push_str(&section);
+    }
+    comment
+}
+
+/// the comment without the generated section
+fn strip_annotations_section(comment_md: &str) -> &str {
+    if comment_md.starts_with(ANNOTATIONS_HEADING) {
+        return "";
+    }
+    match comment_md.find(&format!("\n{}\n", ANNOTATIONS_HEADING)) {
+        Some(pos) => &comment_md[..pos],
+        None => comment_md.strip_suffix(&format!("\n{}", ANNOTATIONS_HEADING)).unwrap_or(comment_md),
+    }
+}
+
+/// one list item per annotation, sorted by file and line
+/// `- src/lib.rs:10-12 note`
+fn annotations_to_markdown(annotations: &[AnnotationItemData]) -> String {
+    if annotations.is_empty() {
+        return String::new();
+    }
+    let mut sorted: Vec<&AnnotationItemData> = annotations.iter().collect();
+    sorted.sort_by(|a, b| (&a.path, a.line_start).cmp(&(&b.path, b.line_start)));
+    let mut section = format!("{}\n\n", ANNOTATIONS_HEADING);
+    for x in sorted {
+        // more lines of the note must be indented to stay in the list item
+        let note = x.note.trim().replace('\n', "\n  ");
+        section.push_str(&format!("- `{}` {}\n", location(x), note));
+    }
+    section
+}
+
+/// `path:line` or `path:start-end`
+pub fn location(annotation: &AnnotationItemData) -> String {
+    if annotation.line_end > annotation.line_start {
+        format!("{}:{}-{}", annotation.path, annotation.line_start, annotation.line_end)
+    } else {
+        format!("{}:{}", annotation.path, annotation.line_start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annotation(path: &str, line_start: usize, line_end: usize, note: &str) -> AnnotationItemData {
+        AnnotationItemData {
+            path: path.to_string(),
+            line_start,
+            line_end,
+            note: note.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_replace_annotations_section() {
+        let annotations = vec![
+            annotation("src/lib.rs", 40, 40, "spawns a process"),
+            annotation("build.rs", 10, 12, "unsafe here is sound because\nthe pointer is aligned"),
+        ];
+        let comment = replace_annotations_section("looks good", &annotations);
+        assert_eq!(
+            comment,
+            "looks good\n\n## Annotations\n\n- `build.rs:10-12` unsafe here is sound because\n  the pointer is aligned\n- `src/lib.rs:40` spawns a process\n"
+        );
+        // saving again replaces the old section
+        let comment = replace_annotations_section(&comment, &annotations[..1]);
+        assert_eq!(comment, "looks good\n\n## Annotations\n\n- `src/lib.rs:40` spawns a process\n");
+        // an empty draft removes the section
+        assert_eq!(replace_annotations_section(&comment, &[]), "looks good");
+        assert_eq!(replace_annotations_section("", &annotations[..1]), "## Annotations\n\n- `src/lib.rs:40` spawns a process\n");
+    }
+
+    #[test]
+    fn test_save_twice_with_two_drafts() {
+        // the first save with one note
+        let first_draft = vec![annotation("src/lib.rs", 40, 40, "spawns a process")];
+        let comment = replace_annotations_section("looks good", &first_draft);
+        // the draft stays after the save, so the second draft has the old and the new note
+        let second_draft = vec![first_draft[0].clone(), annotation("build.rs", 3, 3, "reads env")];
+        let comment = replace_annotations_section(&comment, &second_draft);
+        assert_eq!(
+            comment,
+            "looks good\n\n## Annotations\n\n- `build.rs:3` reads env\n- `src/lib.rs:40` spawns a process\n"
+        );
+    }
+}
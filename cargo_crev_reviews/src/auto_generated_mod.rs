@@ -5,21 +5,32 @@
 
 use crate::srv_methods_mod::srv_publisher_mod::*;
 use crate::srv_methods_mod::srv_review_mod::*;
+use crate::srv_methods_mod::srv_source_mod::*;
 use crate::srv_methods_mod::srv_utils_mod::*;
 
 pub fn match_request_method_and_call_function(request_method: &str, request_data: serde_json::Value) -> anyhow::Result<String> {
     match request_method {
         // region: generated match_response_method
+        "srv_advisory_edit" => srv_advisory_edit(request_data),
+        "srv_annotation_delete" => srv_annotation_delete(request_data),
+        "srv_annotation_edit" => srv_annotation_edit(request_data),
+        "srv_annotation_list" => srv_annotation_list(request_data),
+        "srv_annotation_save" => srv_annotation_save(request_data),
         "srv_cargo_tree_project" => srv_cargo_tree_project(request_data),
         "srv_config_edit" => srv_config_edit(request_data),
         "srv_config_save" => srv_config_save(request_data),
         "srv_correct_digest" => srv_correct_digest(request_data),
+        "srv_issue_edit" => srv_issue_edit(request_data),
+        "srv_job_cancel" => srv_job_cancel(request_data),
+        "srv_jobs_list" => srv_jobs_list(request_data),
         "srv_list_unclean_crates" => srv_list_unclean_crates(request_data),
+        "srv_proof_problem_list" => srv_proof_problem_list(request_data),
         "srv_publisher_delete" => srv_publisher_delete(request_data),
         "srv_publisher_edit" => srv_publisher_edit(request_data),
         "srv_publisher_list" => srv_publisher_list(request_data),
         "srv_publisher_new" => srv_publisher_new(request_data),
         "srv_publisher_save" => srv_publisher_save(request_data),
+        "srv_read_progress" => srv_read_progress(request_data),
         "srv_review_delete" => srv_review_delete(request_data),
         "srv_review_edit" => srv_review_edit(request_data),
         "srv_review_edit_or_new" => srv_review_edit_or_new(request_data),
@@ -29,7 +40,14 @@ pub fn match_request_method_and_call_function(request_method: &str, request_data
         "srv_review_publish" => srv_review_publish(request_data),
         "srv_review_save" => srv_review_save(request_data),
         "srv_reviews_list" => srv_reviews_list(request_data),
+        "srv_risk_scan" => srv_risk_scan(request_data),
+        "srv_rpc_result" => srv_rpc_result(request_data),
+        "srv_source_file" => srv_source_file(request_data),
+        "srv_source_mark_read" => srv_source_mark_read(request_data),
+        "srv_source_tree" => srv_source_tree(request_data),
         "srv_update_registry_index" => srv_update_registry_index(request_data),
+        "srv_vcs_verify" => srv_vcs_verify(request_data),
+        "srv_version_diff" => srv_version_diff(request_data),
         "srv_version_list" => srv_version_list(request_data),
         // endregion: generated match_response_method
         _ => anyhow::bail!("unknown server method = {}", request_method),
@@ -43,6 +61,33 @@ pub mod cln_methods {
 
     // region: generated cln_methods
 
+    #[named]
+    pub fn cln_advisory_edit_modal<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
+    #[named]
+    pub fn cln_annotation_edit_modal<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
+    #[named]
+    pub fn cln_annotation_list<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
     #[named]
     pub fn cln_cargo_tree_list<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
     where
@@ -61,6 +106,24 @@ pub mod cln_methods {
         Ok(return_srv_response(response_method, response_data, response_html))
     }
 
+    #[named]
+    pub fn cln_issue_edit_modal<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
+    #[named]
+    pub fn cln_jobs_list<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
     #[named]
     pub fn cln_modal_close<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
     where
@@ -88,6 +151,15 @@ pub mod cln_methods {
         Ok(return_srv_response(response_method, response_data, response_html))
     }
 
+    #[named]
+    pub fn cln_proof_problem_list<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
     #[named]
     pub fn cln_publisher_edit_modal<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
     where
@@ -115,6 +187,15 @@ pub mod cln_methods {
         Ok(return_srv_response(response_method, response_data, response_html))
     }
 
+    #[named]
+    pub fn cln_read_progress<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
     #[named]
     pub fn cln_review_edit<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
     where
@@ -151,6 +232,60 @@ pub mod cln_methods {
         Ok(return_srv_response(response_method, response_data, response_html))
     }
 
+    #[named]
+    pub fn cln_risk_scan<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
+    #[named]
+    pub fn cln_rpc_pending<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
+    #[named]
+    pub fn cln_source_file<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
+    #[named]
+    pub fn cln_source_tree<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
+    #[named]
+    pub fn cln_vcs_verify<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
+    #[named]
+    pub fn cln_version_diff<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
+    where
+        T: serde::Serialize,
+    {
+        let response_method = function_name!();
+        Ok(return_srv_response(response_method, response_data, response_html))
+    }
+
     #[named]
     pub fn cln_version_list<T>(response_data: T, response_html: &str) -> anyhow::Result<String>
     where
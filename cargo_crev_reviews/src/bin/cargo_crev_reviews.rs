@@ -6,7 +6,8 @@ use unwrap::unwrap;
 use cargo_crev_reviews::*;
 
 /// start of CLI with this main() function
-/// check if cargo_crev is installed,
+/// headless commands like `list`, `show`, `review` and `delete` run without the browser.
+/// check if the crev id exists,
 /// if the program is run in the directory where Cargo.toml is,
 /// if the host+port TcpListener is free to bind. That means that this is the only one instance of the program running.
 /// User input the passphrase for crev signing.
@@ -14,8 +15,17 @@ use cargo_crev_reviews::*;
 fn main() -> anyhow::Result<()> {
     // I don't need to check for `cargo` or Rust, because cargo_crev_reviews is installed with `cargo install`.
     // It means that cargo and Rust are already installed.
+    // the options --port, --bind, --project, --no-browser and --log-level are removed from the args
+    let args: Vec<String> = env::args().collect();
+    let (options, args) = GlobalOptions::from_args(&args)?;
     // priority order: error!, warn!, info!, debug! and trace!
-    pretty_env_logger::formatted_builder().filter_level(log::LevelFilter::Info).init();
+    pretty_env_logger::formatted_builder().filter_level(options.log_level).init();
+    options.apply()?;
+
+    // headless commands don't need the browser and the web server
+    if is_cli_command(&args) {
+        return match_arguments_and_call_command(&args);
+    }
 
     // env variable is stronger then config menu
     let crev_browser_path = match std::env::var("CREV_BROWSER_PATH") {
@@ -31,13 +41,13 @@ fn main() -> anyhow::Result<()> {
 
     if let Some(host_port_already_busy) = host_port_is_busy() {
         one_instance_of_the_program_already_running(&host_port_already_busy);
-    } else if !home::cargo_home()?.join("bin").join("cargo-crev").exists() {
-        // check if cargo-crev is installed
-        cargo_crev_not_installed();
+    } else if !crev_id_exists() {
+        // the verification is in-process with crev-lib, but the crev id must exist
+        crev_id_not_found();
     } else if !home::cargo_home()?.join("bin").join("cargo-audit").exists() {
         // check if cargo-audit is installed
         cargo_audit_not_installed();
-    } else if !std::path::PathBuf::from(&crev_browser_path).exists() {
+    } else if !options.no_browser && !std::path::PathBuf::from(&crev_browser_path).exists() {
         // check if the browser from config is installed. Default is /usr/bin/xdg-open
         browser_not_installed(&crev_browser_path);
     } else if !env::current_dir()?.join("Cargo.toml").exists() {
@@ -53,7 +63,12 @@ If the default browser does not open from WSL2, you can see my project `https://
         create_persistent_directories();
         unlock_crev_id_interactively()?;
         db_sled_migration_update(env!("CARGO_PKG_VERSION"));
-        open_browser(&crev_browser_path);
+        start_progress_server()?;
+        if options.no_browser {
+            println!("Open this url in the browser: {}", server_url_with_token());
+        } else {
+            open_browser(&crev_browser_path);
+        }
         // this must be the last command, because the server lasts
         start_web_server();
     }
@@ -67,13 +82,10 @@ pub fn open_browser(browser_path: &str) {
     // open default browser in Linux
     // for WSL2 in Win10 I used my project https://crates.io/crates/wsl_open_browser
 
+    let url = server_url_with_token();
+    println!("If the browser does not open, open this url: {}", url);
     let x = std::process::Command::new(browser_path)
-        .arg(&format!(
-            "http://{}:{}/{}/index.html",
-            SERVER_HOST.as_str(),
-            SERVER_PORT.as_str(),
-            SERVER_FIRST_SUBDIRECTORY.as_str()
-        ))
+        .arg(&url)
         .spawn()
         .expect(&format!(
             "Failed to open default browser using `{}`. 
@@ -85,6 +97,12 @@ pub fn open_browser(browser_path: &str) {
     drop(x);
 }
 
+/// the session token in the url is the only way for the page to talk to the server
+/// The progress events come from another port.
+fn server_url_with_token() -> String {
+    format!("{}/index.html?token={}&progress_port={}", server_url(), SESSION_TOKEN.as_str(), progress_port())
+}
+
 /// start the simple web server and match the GET or POST method
 /// this is the only place where the web server is explicitly stated.
 /// Changing this simple function, you can use any other web server library of your choice easily.
@@ -108,8 +126,14 @@ pub fn start_web_server() {
     }
 
     let server = Server::new(|request, response_builder| {
-        let path = request.uri().to_string();
+        // the query has the session token, the files are found by the path only
+        let path = request.uri().path().to_string();
         // log::info!("Request received. {} {}", request.method(), request.uri());
+        let header = |name: http::header::HeaderName| request.headers().get(name).and_then(|x| x.to_str().ok());
+        if let Err(err) = check_host_and_origin(header(http::header::HOST), header(http::header::ORIGIN)) {
+            log::warn!("{}", err);
+            return Ok(response_builder.status(StatusCode::FORBIDDEN).body(err.to_string().into_bytes())?);
+        }
         if !request.uri().to_string().starts_with(&format!("/{}", SERVER_FIRST_SUBDIRECTORY.as_str())) {
             let response = convert_response(response_404_not_found(&path), response_builder);
             return Ok(response);
@@ -144,5 +168,5 @@ pub fn start_web_server() {
     sync_in_background_yanked();
     sync_in_background_verify();
 
-    server.listen(SERVER_HOST.as_str(), SERVER_PORT.as_str());
+    server.listen(&server_host(), &server_port());
 }
@@ -1,30 +1,95 @@
 // cargo_registry_mod.rs
 
-//! functions around the cargo registry  d
-//! ~\.cargo\registry\index\github.com-1ecc6299db9ec823\an\yh\anyhow  
-//! ~\.cargo\registry\src\github.com-1ecc6299db9ec823\anyhow-1.0.37\  
+//! functions around the cargo registry
+//! cargo names the registry folders `<host>-<hash>`, the same in `index`, `cache` and `src`:
+//! ~\.cargo\registry\index\index.crates.io-6f17d22bba15001f\.cache\an\yh\anyhow  (sparse protocol)
+//! ~\.cargo\registry\index\github.com-1ecc6299db9ec823\an\yh\anyhow  (old git index)
+//! ~\.cargo\registry\src\index.crates.io-6f17d22bba15001f\anyhow-1.0.37\
 
 #![allow(dead_code)]
 
 use anyhow::Context;
 use lazy_static::lazy_static;
-use unwrap::unwrap;
+use sha2::Digest;
+use std::sync::{Arc, Mutex};
 
 use crate::utils_mod::*;
 
 lazy_static! {
-    pub static ref CARGO_REGISTRY_SRC_DIR: std::path::PathBuf = crate::HOME_DIR.join(".cargo/registry/src/github.com-1ecc6299db9ec823");
-    pub static ref CARGO_REGISTRY_CACHE_DIR: std::path::PathBuf = crate::HOME_DIR.join(".cargo/registry/cache/github.com-1ecc6299db9ec823");
+    /// `~/.cargo/registry` or `$CARGO_HOME/registry`
+    pub static ref CARGO_REGISTRY_DIR: std::path::PathBuf = home::cargo_home().unwrap_or_else(|_| crate::HOME_DIR.join(".cargo")).join("registry");
+    /// `.crate` files already verified in this session with size and mtime, so a changed file is verified again
+    static ref VERIFIED_CRATE_FILES: Mutex<std::collections::HashSet<(std::path::PathBuf, u64, Option<std::time::SystemTime>)>> =
+        Mutex::new(std::collections::HashSet::new());
+    /// one lock per crate version, so two threads never unpack into the same temp folder
+    static ref CRATE_VERSION_LOCKS: Mutex<std::collections::HashMap<String, Arc<Mutex<()>>>> = Mutex::new(std::collections::HashMap::new());
+}
+
+pub const CRATES_IO_INDEX_URL: &str = "https://github.com/rust-lang/crates.io-index";
+/// crates.io folders: the sparse protocol (default since cargo 1.70) and the old git index
+const CRATES_IO_DIR_PREFIXES: [&str; 2] = ["index.crates.io-", "github.com-"];
+/// where cargo puts crates.io when no registry folder exists yet
+const DEFAULT_CRATES_IO_DIR: &str = "index.crates.io-6f17d22bba15001f";
+/// the format of the cargo index cache files in `index/<registry_dir>/.cache`
+const INDEX_CACHE_VERSION: u8 = 3;
+const INDEX_FORMAT_VERSION: u32 = 2;
+
+/// one version line of the registry index
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct IndexVersion {
+    pub vers: String,
+    /// sha256 of the `.crate` file in hex
+    pub cksum: String,
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+/// names of all registry folders under `~/.cargo/registry/{index,cache,src}`
+pub fn registry_dir_names() -> Vec<String> {
+    let mut names = std::collections::BTreeSet::new();
+    for sub_folder in ["index", "cache", "src"] {
+        if let Ok(read_dir) = std::fs::read_dir(CARGO_REGISTRY_DIR.join(sub_folder)) {
+            for entry in read_dir.flatten() {
+                if entry.path().is_dir() {
+                    names.insert(entry.file_name().to_string_lossy().to_string());
+                }
+            }
+        }
+    }
+    names.into_iter().collect()
+}
+
+/// the crates.io registry folders, the sparse ones first
+pub fn crates_io_dir_names() -> Vec<String> {
+    let mut names: Vec<String> = registry_dir_names()
+        .into_iter()
+        .filter(|x| CRATES_IO_DIR_PREFIXES.iter().any(|prefix| x.starts_with(prefix)))
+        .collect();
+    names.sort_by_key(|x| !x.starts_with(CRATES_IO_DIR_PREFIXES[0]));
+    names
+}
+
+/// the existing file or folder in any crates.io registry folder, else the path where cargo would put it
+fn find_in_crates_io_dirs(sub_folder: &str, file_name: &str) -> std::path::PathBuf {
+    let dir_names = crates_io_dir_names();
+    for dir_name in dir_names.iter() {
+        let path = CARGO_REGISTRY_DIR.join(sub_folder).join(dir_name).join(file_name);
+        if path.exists() {
+            return path;
+        }
+    }
+    let dir_name = dir_names.first().map(|x| x.as_str()).unwrap_or(DEFAULT_CRATES_IO_DIR);
+    CARGO_REGISTRY_DIR.join(sub_folder).join(dir_name).join(file_name)
 }
 
 /// cargo registry src directory for a crate
 pub fn cargo_registry_src_dir_for_crate(crate_name: &str, crate_version: &str) -> std::path::PathBuf {
-    CARGO_REGISTRY_SRC_DIR.join(&crate_version_for_src_folder(crate_name, crate_version))
+    find_in_crates_io_dirs("src", &crate_version_for_src_folder(crate_name, crate_version))
 }
 
 /// cargo registry cache file `.crate` for a crate
 pub fn cargo_registry_cache_file_for_crate(crate_name: &str, crate_version: &str) -> std::path::PathBuf {
-    CARGO_REGISTRY_CACHE_DIR.join(format!("{}.crate", &crate_version_for_src_folder(crate_name, crate_version)))
+    find_in_crates_io_dirs("cache", &format!("{}.crate", &crate_version_for_src_folder(crate_name, crate_version)))
 }
 
 pub fn cargo_crev_reviews_src_dir_for_crate(crate_name: &str, crate_version: &str) -> std::path::PathBuf {
@@ -35,66 +100,250 @@ pub fn cargo_crev_reviews_cache_file_for_crate(crate_name: &str, crate_version:
     crate::CARGO_CREV_REVIEWS_CACHE.join(format!("{}.crate", &crate_version_for_src_folder(crate_name, crate_version)))
 }
 
-fn open_cargo_index() -> anyhow::Result<crates_index::Index> {
-    let index = crates_index::Index::new_cargo_default()?;
-    Ok(index)
+/// the path of a crate in the index: `1/a`, `2/ab`, `3/a/abc`, `an/yh/anyhow`
+pub fn index_relative_path(crate_name: &str) -> String {
+    let name = crate_name.to_lowercase();
+    match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    }
 }
 
-/// yanked
-pub fn yanked_for_version(crate_name: &str, crate_version: &str) -> anyhow::Result<bool> {
-    let crate_info = unwrap!(open_cargo_index())
-        .crate_(crate_name)
-        .context(format!("Cannot find crate name in registry: {}", crate_name))?;
-    for x in crate_info.versions().iter() {
-        if x.version() == crate_version {
-            if x.is_yanked() {
-                return Ok(true);
-            }
-            break;
+/// all versions of a crates.io crate from the sparse cache or the git index, else fetched with the sparse protocol
+fn crates_io_versions(crate_name: &str) -> anyhow::Result<Vec<IndexVersion>> {
+    for dir_name in crates_io_dir_names() {
+        let index_dir = CARGO_REGISTRY_DIR.join("index").join(&dir_name);
+        if let Some(versions) = versions_in_index_dir(&index_dir, CRATES_IO_INDEX_URL, crate_name)? {
+            return Ok(versions);
         }
     }
-    Ok(false)
+    fetch_sparse_crate(crate_name)?.with_context(|| format!("Cannot find crate name in registry: {}", crate_name))
 }
 
-/// list only yanked versions for one crate
-pub fn yanked_for_one_crate(crate_name: &str) -> anyhow::Result<Vec<String>> {
-    let crate_info = unwrap!(open_cargo_index())
-        .crate_(crate_name)
-        .context(format!("Cannot find crate name in registry: {}", crate_name))?;
-    let mut vec = vec![];
-    for x in crate_info.versions().iter() {
-        if x.is_yanked() {
-            vec.push(x.version().to_string())
+/// None if this index folder does not know the crate
+/// A git index has the `.git` folder, the sparse index has only the `.cache` files.
+fn versions_in_index_dir(index_dir: &std::path::Path, index_url: &str, crate_name: &str) -> anyhow::Result<Option<Vec<IndexVersion>>> {
+    if index_dir.join(".git").exists() {
+        let index = crates_index::Index::with_path(index_dir, index_url)
+            .with_context(|| format!("Cannot open the registry index {}", index_dir.to_string_lossy()))?;
+        Ok(index.crate_(crate_name).map(|crate_info| {
+            crate_info
+                .versions()
+                .iter()
+                .map(|x| IndexVersion {
+                    vers: x.version().to_string(),
+                    cksum: to_hex(x.checksum()),
+                    yanked: x.is_yanked(),
+                })
+                .collect()
+        }))
+    } else {
+        let cache_file = index_dir.join(".cache").join(index_relative_path(crate_name));
+        if !cache_file.exists() {
+            return Ok(None);
         }
+        let versions = parse_index_cache(&std::fs::read(&cache_file)?).with_context(|| format!("Cannot parse {}", cache_file.to_string_lossy()))?;
+        Ok(Some(versions))
+    }
+}
+
+/// cargo index cache: `[cache version u8][index format version u32 le][index version]\0` and then pairs `[version]\0[json line]\0`
+/// The cache version 1 has no index format version.
+fn parse_index_cache(data: &[u8]) -> anyhow::Result<Vec<IndexVersion>> {
+    let (cache_version, rest) = data.split_first().context("The index cache file is empty.")?;
+    let rest = if *cache_version >= 2 { rest.get(4..).context("The index cache file is too short.")? } else { rest };
+    let mut parts = rest.split(|x| *x == 0);
+    // etag, last-modified or git sha1 of the cached content
+    let _index_version = parts.next();
+    let mut versions = vec![];
+    while let (Some(_version), Some(json_line)) = (parts.next(), parts.next()) {
+        versions.push(serde_json::from_slice(json_line)?);
+    }
+    Ok(versions)
+}
+
+/// the index file from the sparse protocol in the format of the cargo index cache
+fn index_cache_content(index_version: &str, index_file: &str) -> anyhow::Result<Vec<u8>> {
+    let mut data = vec![INDEX_CACHE_VERSION];
+    data.extend_from_slice(&INDEX_FORMAT_VERSION.to_le_bytes());
+    data.extend_from_slice(index_version.as_bytes());
+    data.push(0);
+    for line in index_file.lines().filter(|x| !x.trim().is_empty()) {
+        let version: IndexVersion = serde_json::from_str(line)?;
+        data.extend_from_slice(version.vers.as_bytes());
+        data.push(0);
+        data.extend_from_slice(line.as_bytes());
+        data.push(0);
     }
-    Ok(vec)
+    Ok(data)
+}
+
+/// fetch the index file of one crate with the sparse protocol: <https://index.crates.io/an/yh/anyhow>
+/// It is stored in the sparse cache of cargo, so cargo uses it too. None if the crate does not exist.
+fn fetch_sparse_crate(crate_name: &str) -> anyhow::Result<Option<Vec<IndexVersion>>> {
+    let relative_path = index_relative_path(crate_name);
+    let url = format!("{}/{}", crate::http_client_mod::sparse_url(), relative_path);
+    log::info!("fetch_sparse_crate: {}", &url);
+    let response = crate::http_client_mod::send_with_retry(&url)?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND || response.status() == reqwest::StatusCode::GONE {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        anyhow::bail!("The endpoint {} returned {}.", url, response.status());
+    }
+    let header = |name: reqwest::header::HeaderName| response.headers().get(&name).and_then(|x| x.to_str().ok()).map(|x| format!("{}: {}", name, x));
+    let index_version = header(reqwest::header::ETAG).or_else(|| header(reqwest::header::LAST_MODIFIED)).unwrap_or_default();
+    let content = index_cache_content(&index_version, &response.text()?)?;
+
+    if let Some(dir_name) = crates_io_dir_names().into_iter().find(|x| x.starts_with(CRATES_IO_DIR_PREFIXES[0])) {
+        let cache_file = CARGO_REGISTRY_DIR.join("index").join(dir_name).join(".cache").join(&relative_path);
+        std::fs::create_dir_all(cache_file.parent().context("cache_file has no parent")?)?;
+        // cargo can read the file in the same moment
+        let temp_file = cache_file.with_extension("cargo_crev_reviews_tmp");
+        std::fs::write(&temp_file, &content)?;
+        std::fs::rename(&temp_file, &cache_file)?;
+    }
+    Ok(Some(parse_index_cache(&content)?))
+}
+
+/// yanked
+pub fn yanked_for_version(crate_name: &str, crate_version: &str) -> anyhow::Result<bool> {
+    Ok(crates_io_versions(crate_name)?.iter().any(|x| x.vers == crate_version && x.yanked))
+}
+
+/// list only yanked versions for one crate
+pub fn yanked_for_one_crate(crate_name: &str) -> anyhow::Result<Vec<String>> {
+    Ok(crates_io_versions(crate_name)?.into_iter().filter(|x| x.yanked).map(|x| x.vers).collect())
 }
 
 /// info of all versions for one crate
 pub fn info_for_one_crate(crate_name: &str) -> anyhow::Result<Vec<(String, bool)>> {
-    let crate_info = unwrap!(open_cargo_index())
-        .crate_(crate_name)
-        .context(format!("Cannot find crate name in registry: {}", crate_name))?;
-    let mut vec = vec![];
-    for x in crate_info.versions().iter() {
-        vec.push((x.version().to_string(), x.is_yanked()));
-    }
-    Ok(vec)
+    Ok(crates_io_versions(crate_name)?.into_iter().map(|x| (x.vers, x.yanked)).collect())
 }
 
 /// max version from registry index cache
 pub fn max_version_from_registry_index(crate_name: &str) -> anyhow::Result<String> {
-    let crate_info = unwrap!(open_cargo_index())
-        .crate_(crate_name)
-        .context(format!("Cannot find crate name in registry: {}", crate_name))?;
     // max version by semver
-    let crate_version = crate_info.highest_version();
-    Ok(crate_version.version().to_string())
+    let crate_version = crates_io_versions(crate_name)?
+        .into_iter()
+        .max_by_key(|x| semver::Version::parse(&x.vers).ok())
+        .with_context(|| format!("The crate {} has no versions in the registry index.", crate_name))?;
+    Ok(crate_version.vers)
+}
+
+/// the sha256 checksum of the `.crate` file from the registry index
+pub fn checksum_from_registry_index(crate_name: &str, crate_version: &str) -> anyhow::Result<[u8; 32]> {
+    checksum_from_versions(&crates_io_versions(crate_name)?, crate_name, crate_version)
+}
+
+fn checksum_from_versions(versions: &[IndexVersion], crate_name: &str, crate_version: &str) -> anyhow::Result<[u8; 32]> {
+    let version = versions
+        .iter()
+        .find(|x| x.vers == crate_version)
+        .with_context(|| format!("Cannot find {} {} in the registry index. Update the registry index.", crate_name, crate_version))?;
+    from_hex(&version.cksum).with_context(|| format!("The registry index has a wrong cksum for {} {}.", crate_name, crate_version))
+}
+
+/// the content of the `.crate` must have the checksum from the registry index
+pub fn verify_checksum(crate_name: &str, crate_version: &str, content: &[u8]) -> anyhow::Result<()> {
+    let checksum = checksum_from_registry_index(crate_name, crate_version)?;
+    if let Err(err) = verify_content_checksum(content, &checksum) {
+        anyhow::bail!("The .crate file of {} {} is not verified. {}", crate_name, crate_version, err);
+    }
+    Ok(())
+}
+
+/// the `.crate` of an alternative registry is verified with the checksum from its own index
+/// The index folder is `~/.cargo/registry/index/<registry_dir>` and the url is from the cargo source id.
+pub fn verify_checksum_alternative_registry(index_dir: &std::path::Path, index_url: &str, crate_name: &str, crate_version: &str, content: &[u8]) -> anyhow::Result<()> {
+    let versions = versions_in_index_dir(index_dir, index_url, crate_name)?
+        .with_context(|| format!("Cannot find crate name {} in the registry index {}", crate_name, index_dir.to_string_lossy()))?;
+    let checksum = checksum_from_versions(&versions, crate_name, crate_version)?;
+    if let Err(err) = verify_content_checksum(content, &checksum) {
+        anyhow::bail!("The .crate file of {} {} from {} is not verified. {}", crate_name, crate_version, index_url, err);
+    }
+    Ok(())
 }
 
-/// fetch/pull the registry index from github
+fn verify_content_checksum(content: &[u8], checksum: &[u8; 32]) -> anyhow::Result<()> {
+    let calculated = sha2::Sha256::digest(content);
+    if calculated.as_slice() != checksum {
+        anyhow::bail!("Checksum mismatch: the registry index has sha256 {}, the file has {}.", to_hex(checksum), to_hex(&calculated));
+    }
+    Ok(())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|x| format!("{:02x}", x)).collect()
+}
+
+fn from_hex(text: &str) -> Option<[u8; 32]> {
+    let mut bytes = [0u8; 32];
+    if text.len() != 64 || !text.is_ascii() {
+        return None;
+    }
+    for (i, x) in bytes.iter_mut().enumerate() {
+        *x = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+/// the lock for the download and unpack of one crate version
+pub fn crate_version_lock(crate_name: &str, crate_version: &str) -> Arc<Mutex<()>> {
+    let mut locks = CRATE_VERSION_LOCKS.lock().unwrap();
+    locks.entry(crate_version_join(crate_name, crate_version)).or_default().clone()
+}
+
+/// the verified `.crate` file from `cargo registry cache` or else from the `cargo_crev_reviews cache`, downloaded only once
+/// A mismatch is a hard error: the crev digest must never be calculated over an unverified archive.
+/// The caller holds the crate_version_lock, so the same file is never downloaded twice at the same time.
+pub fn verified_crate_file(crate_name: &str, crate_version: &str) -> anyhow::Result<std::path::PathBuf> {
+    let crate_file = cargo_registry_cache_file_for_crate(crate_name, crate_version);
+    if crate_file.exists() {
+        return verified_existing_file(crate_name, crate_version, crate_file);
+    }
+    // my own download from a previous call
+    let crate_file = cargo_crev_reviews_cache_file_for_crate(crate_name, crate_version);
+    if crate_file.exists() {
+        return verified_existing_file(crate_name, crate_version, crate_file);
+    }
+    download_crate_from_crate_io(crate_name, crate_version)
+}
+
+/// the file is verified once per session, again if the size or mtime changed
+fn verified_existing_file(crate_name: &str, crate_version: &str, crate_file: std::path::PathBuf) -> anyhow::Result<std::path::PathBuf> {
+    let metadata = std::fs::metadata(&crate_file)?;
+    let key = (crate_file.clone(), metadata.len(), metadata.modified().ok());
+    if !VERIFIED_CRATE_FILES.lock().unwrap().contains(&key) {
+        if let Err(err) = verify_checksum(crate_name, crate_version, &std::fs::read(&crate_file)?) {
+            anyhow::bail!("{} Delete the file {} and the unpacked folder.", err, crate_file.to_string_lossy());
+        }
+        VERIFIED_CRATE_FILES.lock().unwrap().insert(key);
+    }
+    Ok(crate_file)
+}
+
+/// fetch/pull the git index and refresh the sparse cache for the crates in the database
+/// The sparse protocol has no complete index to update, every crate has its own file.
 pub fn update_registry_index() -> anyhow::Result<()> {
-    unwrap!(open_cargo_index()).update()?;
+    let dir_names = crates_io_dir_names();
+    use crate::progress_mod::{progress, UPDATE_REGISTRY_INDEX};
+    for dir_name in dir_names.iter() {
+        let index_dir = CARGO_REGISTRY_DIR.join("index").join(dir_name);
+        if index_dir.join(".git").exists() {
+            progress(UPDATE_REGISTRY_INDEX, 0, 0, &format!("git fetch {}", dir_name));
+            crates_index::Index::with_path(&index_dir, CRATES_IO_INDEX_URL)?.update()?;
+        }
+    }
+    if dir_names.iter().any(|x| x.starts_with(CRATES_IO_DIR_PREFIXES[0])) {
+        let all_crates = crate::db_sled_mod::db_crate_mod::all_crates()?;
+        for (i, x) in all_crates.iter().enumerate() {
+            progress(UPDATE_REGISTRY_INDEX, i + 1, all_crates.len(), &x.crate_name);
+            fetch_sparse_crate(&x.crate_name)?;
+        }
+    }
     Ok(())
 }
 
@@ -106,23 +355,22 @@ struct FileMetadata {
 }
 
 /// download url from crates.io into `cargo_crev_reviews cache` temp folder
-/// URL like: <https://crates.io/api/v1/crates/block-cipher-trait/0.6.0/download>
+/// URL like: <https://crates.io/api/v1/crates/block-cipher-trait/0.6.0/download> or from the template in config
 /// it works also for yanked crates. This downloads are immutable.
+/// The content is verified with the `cksum` from the registry index before it is written into the cache.
 pub fn download_crate_from_crate_io(crate_name: &str, crate_version: &str) -> anyhow::Result<std::path::PathBuf> {
-    let url = format!(
-        "https://crates.io/api/v1/crates/{}/{}/download",
-        percent_encoding::utf8_percent_encode(crate_name, percent_encoding::NON_ALPHANUMERIC),
-        percent_encoding::utf8_percent_encode(crate_version, percent_encoding::NON_ALPHANUMERIC)
-    );
+    let url = crate::http_client_mod::download_url(crate_name, crate_version);
     log::info!("download_crate_from_crate_io: {}", &url);
-    let client = reqwest::blocking::Client::new();
-    let response = client.get(url).send()?;
-    let crate_file = cargo_crev_reviews_cache_file_for_crate(&crate_name, &crate_version);
+    let content = crate::http_client_mod::get(&url)?.bytes()?;
+    verify_checksum(crate_name, crate_version, &content)?;
 
-    let mut file = std::fs::File::create(&crate_file)?;
-    let mut content = std::io::Cursor::new(response.bytes()?);
-    std::io::copy(&mut content, &mut file)?;
-    // TODO: maybe use the `cksum` from registry index to check integrity
+    let crate_file = cargo_crev_reviews_cache_file_for_crate(&crate_name, &crate_version);
+    // the rename is atomic, the cache never contains a half-written file
+    let part_file = crate_file.with_extension("crate.part");
+    std::fs::write(&part_file, &content)?;
+    std::fs::rename(&part_file, &crate_file)?;
+    let metadata = std::fs::metadata(&crate_file)?;
+    VERIFIED_CRATE_FILES.lock().unwrap().insert((crate_file.clone(), metadata.len(), metadata.modified().ok()));
     Ok(crate_file)
 }
 
@@ -144,10 +392,22 @@ pub fn unpack_from_targz_to_folder(crate_name: &str, crate_version: &str, cache_
 /// For cargo_crev_reviews I will download and unpack in a temp folder.
 pub fn list_unclean_crates() -> anyhow::Result<String> {
     let mut ret_list = String::new();
+    for dir_name in registry_dir_names() {
+        let src_dir = CARGO_REGISTRY_DIR.join("src").join(&dir_name);
+        if src_dir.exists() {
+            crate::progress_mod::progress(crate::progress_mod::LIST_UNCLEAN_CRATES, 0, 0, &dir_name);
+            list_unclean_crates_in_registry(&src_dir, &CARGO_REGISTRY_DIR.join("cache").join(&dir_name), &mut ret_list)?;
+        }
+    }
+    // return
+    Ok(ret_list)
+}
 
+/// the unclean crates of one registry folder
+fn list_unclean_crates_in_registry(src_dir: &std::path::Path, cache_dir: &std::path::Path, ret_list: &mut String) -> anyhow::Result<()> {
     // region: fill vectors to not repeat the listings of files
     let mut src_crates = vec![];
-    for entry in walkdir::WalkDir::new(CARGO_REGISTRY_SRC_DIR.as_path())
+    for entry in walkdir::WalkDir::new(src_dir)
         .min_depth(1)
         .max_depth(1)
         .sort_by_file_name()
@@ -156,13 +416,11 @@ pub fn list_unclean_crates() -> anyhow::Result<String> {
         src_crates.push(entry.path().to_owned());
     }
     let mut cache_crates = vec![];
-    for entry in walkdir::WalkDir::new(CARGO_REGISTRY_CACHE_DIR.as_path())
-        .min_depth(1)
-        .max_depth(1)
-        .sort_by_file_name()
-    {
-        let entry = entry?;
-        cache_crates.push(entry.path().to_owned());
+    if cache_dir.exists() {
+        for entry in walkdir::WalkDir::new(cache_dir).min_depth(1).max_depth(1).sort_by_file_name() {
+            let entry = entry?;
+            cache_crates.push(entry.path().to_owned());
+        }
     }
     // from mut to not mut (does it allocate? Probably not, because is move.)
     let src_crates = src_crates;
@@ -170,7 +428,7 @@ pub fn list_unclean_crates() -> anyhow::Result<String> {
     // endregion: fill vectors to not repeat the listings of files
 
     // a folder in src must also be in cache. But a folder in cache does not need to be in src.
-    for src_path in src_crates.iter() {
+    for (i, src_path) in src_crates.iter().enumerate() {
         let mut contains = false;
         let src_crate_name = src_path
             .file_name()
@@ -186,8 +444,9 @@ pub fn list_unclean_crates() -> anyhow::Result<String> {
                 .to_string();
             if cache_crate_name == src_crate_name {
                 log::info!("check if unclean: {}", src_crate_name);
+                crate::progress_mod::progress(crate::progress_mod::LIST_UNCLEAN_CRATES, i + 1, src_crates.len(), &src_crate_name);
                 // check if the content is equal
-                if_unclean_add_to_ret_list(src_path, cache_path, &mut ret_list)?;
+                if_unclean_add_to_ret_list(src_path, cache_path, ret_list)?;
                 contains = true;
                 break;
             }
@@ -197,8 +456,7 @@ pub fn list_unclean_crates() -> anyhow::Result<String> {
             ret_list.push_str(&format!("rm -r {}\n", src_path.to_string_lossy()));
         }
     }
-    // return
-    Ok(ret_list)
+    Ok(())
 }
 
 /// compare if the folder is unclean
@@ -332,3 +590,39 @@ pub fn is_crate_clean(src_folder: &std::path::Path, cache_crate_file: &std::path
     }
     return Ok(true);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_content_checksum() {
+        let mut checksum = [0u8; 32];
+        for (i, x) in checksum.iter_mut().enumerate() {
+            *x = u8::from_str_radix(&"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        assert!(verify_content_checksum(b"abc", &checksum).is_ok());
+        assert!(verify_content_checksum(b"abd", &checksum).is_err());
+        assert_eq!(to_hex(&checksum[..2]), "ba78");
+        assert_eq!(from_hex(&to_hex(&checksum)), Some(checksum));
+    }
+
+    #[test]
+    fn test_index_cache() {
+        assert_eq!(index_relative_path("a"), "1/a");
+        assert_eq!(index_relative_path("cc"), "2/cc");
+        assert_eq!(index_relative_path("Syn"), "3/s/syn");
+        assert_eq!(index_relative_path("anyhow"), "an/yh/anyhow");
+        let index_file = r#"{"name":"x","vers":"0.1.0","deps":[],"cksum":"ab","features":{},"yanked":false}
+{"name":"x","vers":"0.2.0","deps":[],"cksum":"cd","features":{},"yanked":true}
+"#;
+        let content = index_cache_content("etag: W/\"123\"", index_file).unwrap();
+        assert_eq!(&content[..5], &[3, 2, 0, 0, 0]);
+        let versions = parse_index_cache(&content).unwrap();
+        let versions: Vec<(&str, &str, bool)> = versions.iter().map(|x| (x.vers.as_str(), x.cksum.as_str(), x.yanked)).collect();
+        assert_eq!(versions, vec![("0.1.0", "ab", false), ("0.2.0", "cd", true)]);
+        // cache version 1 has no index format version
+        let old = b"\x01abc123\x000.1.0\x00{\"vers\":\"0.1.0\",\"cksum\":\"ab\"}\x00";
+        assert_eq!(parse_index_cache(old).unwrap()[0].vers, "0.1.0");
+    }
+}
@@ -6,108 +6,148 @@ use crate::common_structs_mod::*;
 
 // use anyhow::Context;
 // use function_name::named;
-use std::env;
 // use std::str::FromStr;
 // use std::time::Duration;
-use unwrap::unwrap;
 
-/// cargo_tree
+/// cargo_tree rendered from the dependency graph of `cargo metadata`
 pub fn cargo_tree_project() -> anyhow::Result<CargoTreeListData> {
     //let ns_started = crate::utils_mod::ns_start("cargo_tree_project");
 
+    use crate::progress_mod::{progress, CARGO_TREE};
     let vec_publisher = crate::db_sled_mod::db_publisher_mod::list()?;
+    progress(CARGO_TREE, 0, 0, "cargo audit");
     let cargo_audit = crate::cargo_audit_mod::run_cargo_audit()?;
+    let policy = crate::policy_mod::read_policy()?;
 
-    let output = std::process::Command::new("cargo").arg("tree").output().unwrap();
-    let output = format!("{} {}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    progress(CARGO_TREE, 0, 0, "cargo metadata");
+    let graph = crate::dependency_graph_mod::dependency_graph_project()?;
 
     let mut list_of_cargo_tree = vec![];
-    for line in output.lines() {
-        if !line.trim().starts_with("warning:") && !line.starts_with("package:") && !line.starts_with("workspace:") && !line.starts_with("Blocking ") {
-            //  └── wasm-bindgen v0.2.78 (*)
-            let regex = unwrap!(regex::Regex::new(r#"([a-z0-9-_]+) v([0-9]+.[0-9]+.[0-9]+)"#));
-            match regex.captures(line) {
-                None => list_of_cargo_tree.push(CargoTreeItemData {
-                    cargo_tree_line: line.to_string(),
+    let tree_lines = graph.render_tree_lines();
+    let num_of_lines = tree_lines.len();
+    for (i, tree_line) in tree_lines.into_iter().enumerate() {
+        let node = match tree_line.node_id.as_ref().and_then(|node_id| graph.nodes.get(node_id)) {
+            Some(node) => node,
+            None => {
+                // section lines like [dev-dependencies]
+                list_of_cargo_tree.push(CargoTreeItemData {
+                    cargo_tree_line: tree_line.text,
                     ..Default::default()
-                }),
-                Some(caps) => {
-                    let crate_name = caps[1].to_string();
-                    let crate_version = caps[2].to_string();
-                    let crate_name_version = crate::utils_mod::crate_version_join(&crate_name, &crate_version);
-                    // my rating from my review
-                    let my_rating =
-                        // result, option
-                        match crate::db_sled_mod::db_review_mod::read(&crate_name_version) {
-                            Err(_err) => None,
-                            Ok(my_review_opt) =>{
-                                match my_review_opt{
-                                    None => None,
-                                    Some(my_review) => Some(my_review.rating),
-                                }
-                            }
-                    };
-                    // crate description
-                    let crate_description =
-                        // result, option
-                        match crate::db_sled_mod::db_crate_mod::read(&crate_name) {
-                            Err(_err) => None,
-                            Ok(crate_data_opt) =>{
-                                match crate_data_opt{
-                                    None => None,
-                                    Some(crate_data) => Some(crate_data.description),
-                                }
-                            }
-                        };
-
-                    let (published_by_url,trusted_publisher) =
-                    // result, option
-                    match crate::db_sled_mod::db_verify_mod::read(&crate_name_version) {
-                        Err(_err) => (None,None),
-                        Ok(verify_data_opt) =>{
-                            match verify_data_opt{
-                                None => (None,None),
-                                Some(verify_data) => {
-                                    let published_by_url = verify_data.published_by_url;
-                                    let trusted_publisher = crate::crev_mod::is_trusted_publisher(&vec_publisher, &published_by_url);
-                                    (Some(published_by_url), Some(trusted_publisher))
-                                }
-                            }
-                        }
-                    };
-
-                    let status =
-                    // result, option
-                    match crate::db_sled_mod::db_verify_mod::read(&crate_name_version) {
-                        Err(_err) => None,
-                        Ok(verify_data_opt) =>{
-                            match verify_data_opt{
-                                None => None,
-                                Some(verify_data) => Some(verify_data.status),
-                            }
-                        }
-                    };
-                    let audit_id = crate::cargo_audit_mod::get_audit_id_for_crate_version(&cargo_audit, &crate_name, &crate_version);
+                });
+                continue;
+            }
+        };
+        let features = if node.features.is_empty() { None } else { Some(node.features.join(", ")) };
+        let crev_source = crate::package_source_mod::crev_source(node.source_kind, node.source.as_deref());
+        if node.source_kind != crate::dependency_graph_mod::SourceKind::CratesIo {
+            // workspace members, path, git and alternative registry dependencies don't have the crates.io data
+            // but they can be reviewed with their own source
+            let is_member = graph.roots.contains(&node.id);
+            list_of_cargo_tree.push(CargoTreeItemData {
+                cargo_tree_line: tree_line.text,
+                crate_name_version: if is_member { None } else { Some(crate::utils_mod::crate_version_join(&node.name, &node.version)) },
+                crate_description: node.description.clone(),
+                source_kind: Some(node.source_kind.as_str().to_string()),
+                crev_source: Some(crev_source),
+                features,
+                ..Default::default()
+            });
+            continue;
+        }
+        let crate_name = &node.name;
+        let crate_version = &node.version;
+        let crate_name_version = crate::utils_mod::crate_version_join(crate_name, crate_version);
+        progress(CARGO_TREE, i + 1, num_of_lines, &crate_name_version);
+        // my rating from my review
+        let my_rating =
+            // result, option
+            match crate::db_sled_mod::db_review_mod::read(&crate_name_version) {
+                Err(_err) => None,
+                Ok(my_review_opt) =>{
+                    match my_review_opt{
+                        None => None,
+                        Some(my_review) => Some(my_review.rating),
+                    }
+                }
+        };
+        // crate description from cargo metadata, or from crates.io
+        let crate_description = match &node.description {
+            Some(description) => Some(description.clone()),
+            None => match crate::db_sled_mod::db_crate_mod::read(crate_name) {
+                Err(_err) => None,
+                Ok(crate_data_opt) => crate_data_opt.map(|crate_data| crate_data.description),
+            },
+        };
 
-                    list_of_cargo_tree.push(CargoTreeItemData {
-                        cargo_tree_line: line.to_string(),
-                        crate_name_version: Some(crate_name_version),
-                        my_rating,
-                        crate_description,
-                        published_by_url,
-                        trusted_publisher,
-                        status,
-                        audit_id,
-                    })
+        let (published_by_url,trusted_publisher, status) =
+        // result, option
+        match crate::db_sled_mod::db_verify_mod::read(&crate_name_version) {
+            Err(_err) => (None,None,None),
+            Ok(verify_data_opt) =>{
+                match verify_data_opt{
+                    None => (None,None,None),
+                    Some(verify_data) => {
+                        let published_by_url = verify_data.published_by_url;
+                        let trusted_publisher = crate::crev_mod::is_trusted_publisher(&vec_publisher, &published_by_url);
+                        // the tree shows yanked instead of the crev status
+                        let status = if verify_data.yanked { "yanked".to_string() } else { verify_data.status };
+                        (Some(published_by_url), Some(trusted_publisher), Some(status))
+                    }
                 }
             }
+        };
+
+        let audit_id = crate::cargo_audit_mod::get_audit_id_for_crate_version(&cargo_audit, crate_name, crate_version);
+        let (policy_status, policy_rule) = match &policy {
+            None => (None, None),
+            Some(policy) => {
+                let my_review = crate::policy_mod::my_review_from_cache(crate_name, crate_version);
+                let result = crate::policy_mod::evaluate_crate_version(
+                    policy,
+                    crate_name,
+                    crate_version,
+                    my_review.as_ref(),
+                    trusted_publisher.as_deref().unwrap_or(""),
+                    audit_id.as_deref(),
+                );
+                (Some(result.status()), Some(result.rule))
+            }
+        };
+
+        // the counts of dangerous constructs are cached
+        let risk_scan = crate::db_sled_mod::db_risk_scan_mod::read(&crate_name_version).ok().flatten();
+        // binary blobs and other suspicious files in the package
+        let file_class = crate::db_sled_mod::db_file_class_mod::read(&crate_name_version).ok().flatten();
+        // both from one unpack in the background
+        if risk_scan.is_none() || file_class.is_none() {
+            crate::db_sled_mod::scan_in_background_crate(crate_name.to_string(), crate_version.to_string());
         }
+        let risk_counts = risk_scan.map(|x| x.counts);
+        let file_warning = file_class.and_then(|x| crate::file_class_mod::file_warning(&x));
+
+        list_of_cargo_tree.push(CargoTreeItemData {
+            cargo_tree_line: tree_line.text,
+            crate_name_version: Some(crate_name_version),
+            my_rating,
+            crate_description,
+            published_by_url,
+            trusted_publisher,
+            status,
+            audit_id,
+            policy_status,
+            policy_rule,
+            source_kind: Some(node.source_kind.as_str().to_string()),
+            crev_source: Some(crev_source),
+            features,
+            risk_counts,
+            file_warning,
+        })
     }
 
     //crate::utils_mod::ns_print_ms("cargo_tree_project", ns_started);
 
     Ok(CargoTreeListData {
-        project_dir: env::current_dir()?.to_string_lossy().to_string(),
+        project_dir: graph.workspace_root,
         list_of_cargo_tree,
     })
 }
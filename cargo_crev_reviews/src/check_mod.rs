@@ -0,0 +1,236 @@
+// check_mod.rs
+
+//! headless command `check` for CI pipelines
+//! it collects the same data as the GUI: crev verify, my reviews, yanked, cargo audit and publishers
+//! and fails the process when a dependency does not meet the conditions
+
+use serde::Serialize;
+
+use crate::common_structs_mod::VerifyItemData;
+use crate::crev_mod::*;
+use crate::utils_mod::*;
+
+/// every condition can be allowed with an argument like `--allow-unreviewed`
+#[derive(Debug, Clone)]
+pub struct CheckOptions {
+    pub fail_on_unreviewed: bool,
+    pub fail_on_negative: bool,
+    pub fail_on_yanked: bool,
+    pub fail_on_advisory: bool,
+    pub fail_on_untrusted_publisher: bool,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        CheckOptions {
+            fail_on_unreviewed: true,
+            fail_on_negative: true,
+            fail_on_yanked: true,
+            fail_on_advisory: true,
+            fail_on_untrusted_publisher: true,
+        }
+    }
+}
+
+impl CheckOptions {
+    /// read the `--allow-...` arguments
+    pub fn from_args(args: &[String]) -> Self {
+        let allow = |option: &str| args.iter().any(|x| x == option);
+        CheckOptions {
+            fail_on_unreviewed: !allow("--allow-unreviewed"),
+            fail_on_negative: !allow("--allow-negative"),
+            fail_on_yanked: !allow("--allow-yanked"),
+            fail_on_advisory: !allow("--allow-advisory"),
+            fail_on_untrusted_publisher: !allow("--allow-untrusted-publisher"),
+        }
+    }
+}
+
+/// one dependency that does not meet the conditions
+#[derive(Serialize, Debug, Default)]
+pub struct CheckItem {
+    pub crate_name: String,
+    pub crate_version: String,
+    pub reasons: Vec<String>,
+}
+
+/// the result of the check for the whole project
+#[derive(Serialize, Debug, Default)]
+pub struct CheckListData {
+    pub project_dir: String,
+    pub passed: bool,
+    pub count_of_dependencies: usize,
+    pub list_of_failed: Vec<CheckItem>,
+}
+
+/// collect the data for all dependencies without the server and evaluate the conditions
+pub fn check_project(options: &CheckOptions) -> anyhow::Result<CheckListData> {
+    let verify_list_data = verify_project()?;
+    let vec_publisher = crate::db_sled_mod::db_publisher_mod::list()?;
+    let cargo_audit = crate::cargo_audit_mod::run_cargo_audit()?;
+    // my reviews directly from crev, because the sled cache is synced only in the background
+    let vec_my_reviews = crev_list_my_reviews(&None)?;
+    let policy = crate::policy_mod::read_policy()?;
+
+    let mut list_of_failed = vec![];
+    for item in verify_list_data.list_of_verify.iter() {
+        let crate_name_version = crate_version_join(&item.crate_name, &item.crate_version);
+        // the data from crates.io could be missing on the first run. Download it now and not in the background.
+        if item.published_by_url.is_empty() && !crate::db_sled_mod::db_version_mod::exists(&item.crate_name) {
+            crate::db_sled_mod::download_crate_versions(&item.crate_name)?;
+        }
+        let published_by_url = match crate::db_sled_mod::db_version_mod::read(&crate_name_version)? {
+            Some(version_for_db) => version_for_db.published_by_url.unwrap_or_default(),
+            None => String::new(),
+        };
+        let my_review = vec_my_reviews
+            .iter()
+            // a review of a git or path build of the same version does not cover the crate from crates.io
+            .find(|x| {
+                x.package.name == item.crate_name && x.package.version == item.crate_version && crate::package_source_mod::is_crates_io(&x.package.source)
+            })
+            .map(|x| crate::utils_mod::from_crev_to_item(x));
+        let my_rating = my_review.as_ref().map(|x| x.rating.clone());
+
+        let reasons = if let Some(policy) = &policy {
+            // the project policy in crev-policy.toml replaces the default conditions
+            // evaluated here with my review from crev and not with the policy_status of verify from the sled cache
+            let audit_id = crate::cargo_audit_mod::get_audit_id_for_crate_version(&cargo_audit, &item.crate_name, &item.crate_version);
+            let result = crate::policy_mod::evaluate_crate_version(
+                policy,
+                &item.crate_name,
+                &item.crate_version,
+                my_review.as_ref(),
+                &is_trusted_publisher(&vec_publisher, &published_by_url),
+                audit_id.as_deref(),
+            );
+            if result.passed {
+                vec![]
+            } else {
+                vec![format!("policy: {}", result.rule)]
+            }
+        } else {
+            let audit_id = crate::cargo_audit_mod::get_audit_id_for_crate_version(&cargo_audit, &item.crate_name, &item.crate_version);
+            let trusted_publisher = is_trusted_publisher(&vec_publisher, &published_by_url);
+            default_reasons(options, item, my_rating.as_deref(), audit_id.as_deref(), &published_by_url, &trusted_publisher)
+        };
+
+        if !reasons.is_empty() {
+            list_of_failed.push(CheckItem {
+                crate_name: item.crate_name.clone(),
+                crate_version: item.crate_version.clone(),
+                reasons,
+            });
+        }
+    }
+
+    Ok(CheckListData {
+        project_dir: verify_list_data.project_dir,
+        passed: list_of_failed.is_empty(),
+        count_of_dependencies: verify_list_data.list_of_verify.len(),
+        list_of_failed,
+    })
+}
+
+/// the conditions without a policy file
+/// The crev status and yanked are separate: a yanked version can have a crev pass or warn.
+fn default_reasons(
+    options: &CheckOptions,
+    item: &VerifyItemData,
+    my_rating: Option<&str>,
+    audit_id: Option<&str>,
+    published_by_url: &str,
+    trusted_publisher: &str,
+) -> Vec<String> {
+    let mut reasons = vec![];
+    if options.fail_on_unreviewed && my_rating.is_none() && item.status != "pass" {
+        reasons.push(format!("unreviewed: no review of mine and crev status is `{}`", item.status));
+    }
+    if options.fail_on_negative {
+        if my_rating == Some("negative") {
+            reasons.push("negative: my review rating is negative".to_string());
+        } else if item.status == "warn" {
+            reasons.push("negative: crev verify warns about this version".to_string());
+        }
+    }
+    if options.fail_on_yanked && item.yanked {
+        reasons.push("yanked: this version is yanked on crates.io".to_string());
+    }
+    if options.fail_on_advisory {
+        if let Some(audit_id) = audit_id {
+            reasons.push(format!("advisory: RustSec {}", audit_id));
+        }
+    }
+    if options.fail_on_untrusted_publisher && trusted_publisher.is_empty() {
+        if published_by_url.is_empty() {
+            reasons.push("untrusted publisher: the publisher is unknown".to_string());
+        } else {
+            reasons.push(format!("untrusted publisher: {}", published_by_url));
+        }
+    }
+    reasons
+}
+
+/// print the reasons for every failed crate
+pub fn print_check_list(check_list_data: &CheckListData) {
+    for item in check_list_data.list_of_failed.iter() {
+        println!("{}{} {}{}", *crate::RED, item.crate_name, item.crate_version, *crate::RESET);
+        for reason in item.reasons.iter() {
+            println!("    {}", reason);
+        }
+    }
+    if check_list_data.passed {
+        println!(
+            "{}All {} dependencies passed the check.{}",
+            *crate::GREEN,
+            check_list_data.count_of_dependencies,
+            *crate::RESET
+        );
+    } else {
+        println!(
+            "{}{} of {} dependencies failed the check.{}",
+            *crate::RED,
+            check_list_data.list_of_failed.len(),
+            check_list_data.count_of_dependencies,
+            *crate::RESET
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_reasons() {
+        let options = CheckOptions::default();
+        let allow_yanked = CheckOptions {
+            fail_on_yanked: false,
+            ..Default::default()
+        };
+        let yanked_pass = VerifyItemData {
+            status: "pass".to_string(),
+            yanked: true,
+            ..Default::default()
+        };
+        let reasons = default_reasons(&options, &yanked_pass, None, None, "", "trusted");
+        assert_eq!(reasons, vec!["yanked: this version is yanked on crates.io".to_string()]);
+        assert!(default_reasons(&allow_yanked, &yanked_pass, None, None, "", "trusted").is_empty());
+
+        let yanked_warn = VerifyItemData {
+            status: "warn".to_string(),
+            yanked: true,
+            ..Default::default()
+        };
+        let reasons = default_reasons(&allow_yanked, &yanked_warn, None, None, "", "trusted");
+        assert_eq!(
+            reasons,
+            vec![
+                "unreviewed: no review of mine and crev status is `warn`".to_string(),
+                "negative: crev verify warns about this version".to_string()
+            ]
+        );
+        let reasons = default_reasons(&options, &VerifyItemData::default(), Some("positive"), Some("RUSTSEC-2020-0001"), "", "");
+        assert_eq!(reasons.len(), 2);
+    }
+}
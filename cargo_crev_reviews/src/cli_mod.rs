@@ -0,0 +1,408 @@
+// cli_mod.rs
+
+//! headless commands for the CLI without the browser and the web server
+//! useful for scripting over ssh or in containers
+//! the output is a human table or json with the `--json` argument
+
+use anyhow::Context;
+use std::str::FromStr;
+
+use crate::common_structs_mod::*;
+use crate::crev_mod::*;
+use crate::*;
+
+/// returns true if the first argument is a headless command
+pub fn is_cli_command(args: &[String]) -> bool {
+    match args.get(1).map(|s| s.as_str()) {
+        Some("list") | Some("show") | Some("review") | Some("delete") | Some("diff") | Some("check") | Some("vcs-verify") | Some("help") | Some("--help") => true,
+        _ => false,
+    }
+}
+
+/// options for the web server and for all commands
+/// They are removed from the args before the command is matched.
+#[derive(Debug)]
+pub struct GlobalOptions {
+    pub bind: String,
+    pub port: String,
+    pub project: Option<std::path::PathBuf>,
+    pub no_browser: bool,
+    pub log_level: log::LevelFilter,
+}
+
+impl GlobalOptions {
+    /// the options and the remaining args
+    pub fn from_args(args: &[String]) -> anyhow::Result<(Self, Vec<String>)> {
+        let mut options = GlobalOptions {
+            bind: server_host(),
+            port: server_port(),
+            project: None,
+            no_browser: false,
+            log_level: log::LevelFilter::Info,
+        };
+        let mut remaining = vec![];
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let mut value = || iter.next().cloned().with_context(|| format!("Missing value for the option {}.", arg));
+            match arg.as_str() {
+                "--bind" => {
+                    options.bind = value()?;
+                    options.bind.parse::<std::net::IpAddr>().with_context(|| format!("The option --bind needs an ip address, not {}.", options.bind))?;
+                }
+                "--port" => {
+                    options.port = value()?;
+                    options.port.parse::<u16>().with_context(|| format!("The option --port needs a number, not {}.", options.port))?;
+                }
+                "--project" => options.project = Some(std::path::PathBuf::from(value()?)),
+                "--no-browser" => options.no_browser = true,
+                "--log-level" => {
+                    let level = value()?;
+                    options.log_level = log::LevelFilter::from_str(&level)
+                        .ok()
+                        .with_context(|| format!("The option --log-level needs off, error, warn, info, debug or trace, not {}.", level))?;
+                }
+                _ => remaining.push(arg.clone()),
+            }
+        }
+        Ok((options, remaining))
+    }
+
+    /// the project directory becomes the current directory, because cargo commands run there
+    pub fn apply(&self) -> anyhow::Result<()> {
+        if let Some(project) = &self.project {
+            std::env::set_current_dir(project).with_context(|| format!("Cannot open the project directory {}", project.to_string_lossy()))?;
+        }
+        *SERVER_HOST.lock().unwrap() = self.bind.clone();
+        *SERVER_PORT.lock().unwrap() = self.port.clone();
+        Ok(())
+    }
+}
+
+/// match the first argument and call the headless command
+/// args[0] is the name of the executable
+pub fn match_arguments_and_call_command(args: &[String]) -> anyhow::Result<()> {
+    // the GUI can run at the same time and hold the lock of the sled db
+    if !matches!(args.get(1).map(|s| s.as_str()), Some("help") | Some("--help")) {
+        crate::db_sled_mod::open_db_sled_headless()?;
+    }
+    let json = args.iter().any(|x| x == "--json");
+    match args.get(1).map(|s| s.as_str()) {
+        Some("list") => {
+            open_crev_local()?;
+            command_list(positional_arg(args, 2), json)
+        }
+        Some("show") => {
+            open_crev_local()?;
+            let crate_name = positional_arg(args, 2).context("Missing argument <crate_name>.")?;
+            let crate_version = positional_arg(args, 3).context("Missing argument <crate_version>.")?;
+            let source = option_value(args, "--source").unwrap_or_default();
+            command_show(&crate_name, &crate_version, &source, json)
+        }
+        Some("review") => {
+            let crate_name = positional_arg(args, 2).context("Missing argument <crate_name>.")?;
+            let crate_version = positional_arg(args, 3).context("Missing argument <crate_version>.")?;
+            let rating = option_value(args, "--rating").unwrap_or_else(|| "neutral".to_string());
+            let thoroughness = option_value(args, "--thoroughness").unwrap_or_else(|| "none".to_string());
+            let understanding = option_value(args, "--understanding").unwrap_or_else(|| "none".to_string());
+            let comment_md = match option_value(args, "--comment-file") {
+                Some(file_path) => std::fs::read_to_string(&file_path).with_context(|| format!("Cannot read the comment file {}", &file_path))?,
+                None => String::new(),
+            };
+            let diff_base = option_value(args, "--diff-base");
+            create_temp_directories()?;
+            unlock_crev_id_interactively()?;
+            command_review(&crate_name, &crate_version, &thoroughness, &understanding, &rating, &comment_md, diff_base, json)
+        }
+        Some("delete") => {
+            open_crev_local()?;
+            let crate_name = positional_arg(args, 2).context("Missing argument <crate_name>.")?;
+            let crate_version = positional_arg(args, 3).context("Missing argument <crate_version>.")?;
+            let source = option_value(args, "--source").unwrap_or_else(|| crate::package_source_mod::CRATES_IO_SOURCE.to_string());
+            command_delete(&crate_name, &crate_version, &source, json)
+        }
+        Some("diff") => {
+            let crate_name = positional_arg(args, 2).context("Missing argument <crate_name>.")?;
+            let old_version = positional_arg(args, 3).context("Missing argument <old_version>.")?;
+            let new_version = positional_arg(args, 4).context("Missing argument <new_version>.")?;
+            create_temp_directories()?;
+            command_diff(&crate_name, &old_version, &new_version, args.iter().any(|x| x == "--stat"))
+        }
+        Some("check") => {
+            open_crev_local()?;
+            create_persistent_directories();
+            create_temp_directories()?;
+            command_check(&crate::check_mod::CheckOptions::from_args(args), json)
+        }
+        Some("vcs-verify") => {
+            let crate_name = positional_arg(args, 2).context("Missing argument <crate_name>.")?;
+            let crate_version = positional_arg(args, 3).context("Missing argument <crate_version>.")?;
+            let repo_path = positional_arg(args, 4).context("Missing argument <repo_path>.")?;
+            create_temp_directories()?;
+            command_vcs_verify(&crate_name, &crate_version, &repo_path, json)
+        }
+        _ => {
+            print_help();
+            Ok(())
+        }
+    }
+}
+
+/// print the help for the headless commands
+pub fn print_help() {
+    println!(
+        r#"
+{yel}cargo_crev_reviews{res} without arguments opens the GUI in the browser.
+  {green}cargo_crev_reviews [--port 8182] [--bind 127.0.0.1] [--project <dir>] [--no-browser] [--log-level info]{res}
+
+Headless commands without the browser:
+  {green}cargo_crev_reviews list [crate_name] [--json]{res}
+  {green}cargo_crev_reviews show <crate_name> <crate_version> [--source <url>] [--json]{res}
+  {green}cargo_crev_reviews review <crate_name> <crate_version> --rating positive --thoroughness low --understanding medium --comment-file x.md [--diff-base <old_version>] [--json]{res}
+  {green}cargo_crev_reviews delete <crate_name> <crate_version> [--source <url>] [--json]{res}
+  {green}cargo_crev_reviews diff <crate_name> <old_version> <new_version> [--stat]{res}
+  {green}cargo_crev_reviews check [--allow-unreviewed] [--allow-negative] [--allow-yanked] [--allow-advisory] [--allow-untrusted-publisher] [--json]{res}
+  {green}cargo_crev_reviews vcs-verify <crate_name> <crate_version> <repo_path> [--json]{res}
+
+Rating: negative, neutral, positive, strong.
+Thoroughness and understanding: none, low, medium, high.
+The review with `--diff-base` is a differential review. It covers only the changes after the earlier version.
+Without `--diff-base` the saved review is a full review, also if the previous review of this version was differential.
+The command `review` needs the passphrase. For scripting use the env variable CREV_PASSPHRASE.
+The commands `show` and `delete` use only the review from crates.io or from the `--source` shown by `list --json`.
+The command `check` exits with code 1 if any dependency is unreviewed, negative, yanked, has an advisory or an untrusted publisher.
+If the project has a `crev-policy.toml` file, the command `check` evaluates the policy instead of the `--allow-...` arguments.
+The command `vcs-verify` compares the package with its git commit in the local clone <repo_path>.
+It exits with code 1 if the package was dirty or some files exist only in the package or differ from the repository.
+The options `--project` and `--log-level` work also with the headless commands.
+Every developer on a shared machine can run an own instance on another `--port`.
+"#,
+        yel = *YELLOW,
+        green = *GREEN,
+        res = *RESET
+    );
+}
+
+/// list my reviews, all of them or only for one crate
+fn command_list(crate_name: Option<String>, json: bool) -> anyhow::Result<()> {
+    let filter = crate_name.map(|crate_name| ReviewFilterData {
+        crate_name,
+        crate_version: None,
+        old_crate_version: None,
+        source: String::new(),
+    });
+    let mut vec_proof = crev_list_my_reviews(&filter)?;
+    crev_sort_list_by_name_version(&mut vec_proof);
+    let vec_review: Vec<ReviewItemData> = vec_proof.iter().map(crate::utils_mod::from_crev_to_item).collect();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&vec_review)?);
+    } else {
+        print_table_of_reviews(&vec_review);
+    }
+    Ok(())
+}
+
+/// show one review with the comment
+fn command_show(crate_name: &str, crate_version: &str, source: &str, json: bool) -> anyhow::Result<()> {
+    let filter = ReviewFilterData {
+        crate_name: crate_name.to_string(),
+        crate_version: Some(crate_version.to_string()),
+        old_crate_version: None,
+        source: source.to_string(),
+    };
+    let review = crate::utils_mod::from_crev_to_item(&crev_edit_review(filter)?);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&review)?);
+    } else {
+        print_table_of_reviews(&[review.clone()]);
+        println!("\n{}", review.comment_md);
+    }
+    Ok(())
+}
+
+/// create or replace the review for one crate version
+#[allow(clippy::too_many_arguments)]
+fn command_review(
+    crate_name: &str,
+    crate_version: &str,
+    thoroughness: &str,
+    understanding: &str,
+    rating: &str,
+    comment_md: &str,
+    diff_base: Option<String>,
+    json: bool,
+) -> anyhow::Result<()> {
+    // keep the issues and advisories of the existing review
+    // The diff base is only the argument, without `--diff-base` the review is a full review again.
+    let filter = ReviewFilterData {
+        crate_name: crate_name.to_string(),
+        crate_version: Some(crate_version.to_string()),
+        old_crate_version: None,
+        source: String::new(),
+    };
+    let (issues, advisories, stored_thoroughness) = match crev_edit_review(filter) {
+        Ok(p) => (p.issues, p.advisories, p.review.map(|x| x.thoroughness.to_string())),
+        Err(_) => (vec![], vec![], None),
+    };
+    let diff_base = diff_base.unwrap_or_default();
+    // the same rule as in the GUI, the headless review is always for crates.io
+    crate::source_code_mod::check_thoroughness(crate_name, crate_version, thoroughness, &diff_base, stored_thoroughness.as_deref())?;
+    crev_save_review(
+        crate_name,
+        crate_version,
+        crev_data::Level::from_str(thoroughness)?,
+        crev_data::Level::from_str(understanding)?,
+        rating_parse(rating)?,
+        comment_md,
+        &issues,
+        &advisories,
+        &diff_base,
+        "",
+    )?;
+    print_message(&format!("Review saved for {} {}.", crate_name, crate_version), json)
+}
+
+/// delete the review for one crate version
+fn command_delete(crate_name: &str, crate_version: &str, source: &str, json: bool) -> anyhow::Result<()> {
+    delete_review_proofs(crate_name, crate_version, Some(source))?;
+    print_message(&format!("Review deleted for {} {}.", crate_name, crate_version), json)
+}
+
+/// print the diff between two versions of a crate
+fn command_diff(crate_name: &str, old_version: &str, new_version: &str, stat: bool) -> anyhow::Result<()> {
+    let crate_diff = crate::crate_diff_mod::crate_diff(crate_name, old_version, new_version)?;
+    if stat {
+        print!("{}", crate_diff.summary());
+    } else {
+        print!("{}", crate_diff.to_unified_text());
+    }
+    Ok(())
+}
+
+/// check all dependencies of the project and exit with a non-zero code if some fail
+fn command_check(options: &crate::check_mod::CheckOptions, json: bool) -> anyhow::Result<()> {
+    let check_list_data = crate::check_mod::check_project(options)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&check_list_data)?);
+    } else {
+        crate::check_mod::print_check_list(&check_list_data);
+    }
+    if !check_list_data.passed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// compare the published package with the git commit in a local clone
+fn command_vcs_verify(crate_name: &str, crate_version: &str, repo_path: &str, json: bool) -> anyhow::Result<()> {
+    let data = crate::vcs_verify_mod::vcs_verify(crate_name, crate_version, repo_path)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&data)?);
+    } else {
+        println!("{} {} git commit {} path_in_vcs '{}'", data.crate_name, data.crate_version, data.git_sha1, data.path_in_vcs);
+        if data.dirty {
+            println!("{}The package was published with uncommitted changes.{}", *YELLOW, *RESET);
+        }
+        for file in data.list_of_file.iter() {
+            println!("{:16}  {}", file.status, file.path);
+        }
+        println!("{} of {} files match the repository.", data.files_checked - data.list_of_file.len(), data.files_checked);
+    }
+    if data.dirty || !data.list_of_file.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn print_message(message: &str, json: bool) -> anyhow::Result<()> {
+    if json {
+        let data = RpcMessageData { message: message.to_string() };
+        println!("{}", serde_json::to_string_pretty(&data)?);
+    } else {
+        println!("{}", message);
+    }
+    Ok(())
+}
+
+/// simple table with padded columns
+fn print_table_of_reviews(vec_review: &[ReviewItemData]) {
+    let width_name = vec_review.iter().map(|x| x.crate_name.len()).max().unwrap_or(0).max("crate".len());
+    let width_version = vec_review.iter().map(|x| x.crate_version.len()).max().unwrap_or(0).max("version".len());
+    println!(
+        "{:width_name$}  {:width_version$}  {:8}  {:12}  {:13}  {}",
+        "crate",
+        "version",
+        "rating",
+        "thoroughness",
+        "understanding",
+        "date",
+        width_name = width_name,
+        width_version = width_version
+    );
+    for x in vec_review.iter() {
+        println!(
+            "{:width_name$}  {:width_version$}  {:8}  {:12}  {:13}  {}",
+            x.crate_name,
+            x.crate_version,
+            x.rating,
+            x.thoroughness,
+            x.understanding,
+            x.date.get(..10).unwrap_or(&x.date),
+            width_name = width_name,
+            width_version = width_version
+        );
+    }
+}
+
+/// options that are followed by a value
+const OPTIONS_WITH_VALUE: [&str; 6] = ["--rating", "--thoroughness", "--understanding", "--comment-file", "--diff-base", "--source"];
+
+/// the positional argument, ignoring the options that start with `--` and their values
+fn positional_arg(args: &[String], position: usize) -> Option<String> {
+    let mut positionals = vec![];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+            // skip the value of the option
+            iter.next();
+        } else if arg.starts_with("--") {
+            // flag without value
+        } else {
+            positionals.push(arg.clone());
+        }
+    }
+    positionals.get(position).cloned()
+}
+
+/// the value after an option like `--rating positive`
+fn option_value(args: &[String], option: &str) -> Option<String> {
+    let pos = args.iter().position(|x| x == option)?;
+    args.get(pos + 1).cloned()
+}
+
+/// the headless commands must not empty the temp directories, because the GUI could run in parallel
+fn create_temp_directories() -> anyhow::Result<()> {
+    std::fs::create_dir_all(CARGO_CREV_REVIEWS_CACHE.as_path())?;
+    std::fs::create_dir_all(CARGO_CREV_REVIEWS_SRC.as_path())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_global_options_from_args() {
+        let args: Vec<String> = ["cargo_crev_reviews", "--port", "8190", "list", "--project", "/tmp/x", "--json", "--no-browser"]
+            .iter()
+            .map(|x| x.to_string())
+            .collect();
+        let (options, remaining) = GlobalOptions::from_args(&args).unwrap();
+        assert_eq!(options.port, "8190");
+        assert_eq!(options.project, Some(std::path::PathBuf::from("/tmp/x")));
+        assert!(options.no_browser);
+        assert_eq!(remaining, vec!["cargo_crev_reviews", "list", "--json"]);
+        assert!(is_cli_command(&remaining));
+        let args: Vec<String> = ["cargo_crev_reviews", "--port", "99999"].iter().map(|x| x.to_string()).collect();
+        assert!(GlobalOptions::from_args(&args).is_err());
+    }
+}
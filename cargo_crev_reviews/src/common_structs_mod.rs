@@ -12,6 +12,9 @@ use serde::{Deserialize, Serialize};
 pub struct RpcRequest {
     pub request_method: String,
     pub request_data: serde_json::Value,
+    /// the session token from the url opened by the server
+    #[serde(default)]
+    pub token: String,
 }
 
 /// the response_method will be processed on the client
@@ -31,6 +34,12 @@ pub struct RpcMessageData {
 /// generic empty data for Rpc
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RpcEmptyData {}
+
+/// the long method runs on a worker thread, the client asks again for the result with this id
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RpcPendingData {
+    pub rpc_id: u64,
+}
 // endregion: platform wide structs
 
 // region: review
@@ -40,6 +49,18 @@ pub struct ReviewFilterData {
     pub crate_name: String,
     pub crate_version: Option<String>,
     pub old_crate_version: Option<String>,
+    /// crev source url of git, path or alternative registry packages, empty for crates.io
+    #[serde(default)]
+    pub source: String,
+}
+
+/// the review to delete from one row of a list, the source is empty for crates.io
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ReviewDeleteData {
+    pub crate_name: String,
+    pub crate_version: String,
+    #[serde(default)]
+    pub source: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -51,6 +72,19 @@ pub struct ReviewItemData {
     pub understanding: String,
     pub rating: String,
     pub comment_md: String,
+    #[serde(default)]
+    pub issues: Vec<IssueItemData>,
+    #[serde(default)]
+    pub advisories: Vec<AdvisoryItemData>,
+    /// empty for a full review. A differential review covers only the changes after this version.
+    #[serde(default)]
+    pub diff_base_version: String,
+    /// the highest of my reviewed versions below this version, the editor offers it as diff base
+    #[serde(default)]
+    pub suggested_diff_base_version: String,
+    /// crev source url of git, path or alternative registry packages, empty for crates.io
+    #[serde(default)]
+    pub source: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -59,6 +93,40 @@ pub struct ReviewListData {
     pub list_of_review: Vec<ReviewItemData>,
 }
 
+/// crev issue in a package review
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct IssueItemData {
+    pub id: String,
+    pub severity: String,
+    #[serde(default)]
+    pub comment: String,
+}
+
+/// crev advisory in a package review. The range is all, major or minor.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct AdvisoryItemData {
+    pub ids: Vec<String>,
+    pub severity: String,
+    #[serde(default)]
+    pub range: String,
+    #[serde(default)]
+    pub comment: String,
+}
+
+/// row_number is None for a new issue
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct IssueEditData {
+    pub row_number: Option<usize>,
+    pub issue: IssueItemData,
+}
+
+/// row_number is None for a new advisory
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AdvisoryEditData {
+    pub row_number: Option<usize>,
+    pub advisory: AdvisoryItemData,
+}
+
 // endregion: review
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -71,6 +139,27 @@ pub struct CargoTreeItemData {
     pub trusted_publisher: Option<String>,
     pub status: Option<String>,
     pub audit_id: Option<String>,
+    /// policy from crev-policy.toml: pass, fail or None if the project has no policy
+    #[serde(default)]
+    pub policy_status: Option<String>,
+    /// the policy rule that decided pass or fail
+    #[serde(default)]
+    pub policy_rule: Option<String>,
+    /// crates_io, registry, git or path
+    #[serde(default)]
+    pub source_kind: Option<String>,
+    /// the source url for crev
+    #[serde(default)]
+    pub crev_source: Option<String>,
+    /// enabled features after the resolve
+    #[serde(default)]
+    pub features: Option<String>,
+    /// None until the background risk scan of the source code is finished
+    #[serde(default)]
+    pub risk_counts: Option<RiskCountsData>,
+    /// the list of suspicious files in the package for the warning badge, None if nothing found
+    #[serde(default)]
+    pub file_warning: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -81,14 +170,38 @@ pub struct CargoTreeListData {
 
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct VerifyItemData {
-    /// status: none, pass, warn, yanked
+    /// status: none, pass, warn. Verified natively with the crev proof db and trust set.
     pub status: String,
+    /// the version is yanked on crates.io, independent of the crev status
+    #[serde(default)]
+    pub yanked: bool,
     /// rating if exists, version number if exists for crate
     pub my_review: String,
     pub crate_name: String,
     pub crate_version: String,
     pub published_by_url: String,
     pub trusted_publisher: String,
+    /// policy from crev-policy.toml: pass, fail or empty if the project has no policy
+    #[serde(default)]
+    pub policy_status: String,
+    /// the policy rule that decided pass or fail
+    #[serde(default)]
+    pub policy_rule: String,
+    /// count of reviews in the trusted proof db for this version
+    #[serde(default)]
+    pub reviews_count: usize,
+    /// count of reviews in the trusted proof db for all versions of the crate
+    #[serde(default)]
+    pub reviews_count_all: usize,
+    /// count of open issues reported by trusted reviewers
+    #[serde(default)]
+    pub issues_count: usize,
+    /// owners from crates.io, comma separated
+    #[serde(default)]
+    pub owners: String,
+    /// digest of the source code compared to the reviews: ok, mismatch or empty if not checked
+    #[serde(default)]
+    pub digest_check: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -133,4 +246,306 @@ pub struct PublisherFilterData {
 pub struct ConfigData {
     pub code_editor_path: String,
     pub browser_path: String,
+    /// base url of the crates.io API or a mirror, empty means crates.io
+    #[serde(default)]
+    pub crates_io_api_url: String,
+    /// download url template with `{crate}` and `{version}`, empty means crates.io
+    #[serde(default)]
+    pub crates_io_download_url: String,
+    /// sparse index url, empty means <https://index.crates.io>
+    #[serde(default)]
+    pub crates_io_sparse_url: String,
+}
+
+// region: proof problems
+
+/// a proof in my reviews directory that is corrupt, unsigned or signed by a foreign crev id
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ProofProblemItemData {
+    pub file_path: String,
+    pub line_number: usize,
+    pub problem: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ProofProblemListData {
+    pub reviews_dir: String,
+    pub list_of_problem: Vec<ProofProblemItemData>,
+}
+// endregion: proof problems
+
+// region: version diff
+
+/// one changed file in the summary of the diff
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct FileDiffItemData {
+    pub path: String,
+    /// added, removed or modified
+    pub status: String,
+    pub binary: bool,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+/// one row of the side-by-side diff. The row_kind is file, hunk or line.
+/// For the line the change is equal, delete, insert or replace.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct DiffRowItemData {
+    pub row_kind: String,
+    pub text: String,
+    pub change: String,
+    pub old_line_number: String,
+    pub old_text: String,
+    pub new_line_number: String,
+    pub new_text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct VersionDiffData {
+    pub crate_name: String,
+    pub old_version: String,
+    pub new_version: String,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub list_of_file: Vec<FileDiffItemData>,
+    pub list_of_row: Vec<DiffRowItemData>,
+    /// very big diffs are cut, the rest is only in the summary
+    pub truncated: bool,
+}
+// endregion: version diff
+
+// region: source code
+
+/// request for one file in the clean unpacked crate
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SourceFileFilterData {
+    pub crate_name: String,
+    pub crate_version: String,
+    /// relative to the crate folder
+    pub path: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SourceFileItemData {
+    pub name: String,
+    pub path: String,
+    pub depth: usize,
+    pub is_dir: bool,
+    pub size: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SourceTreeData {
+    pub crate_name: String,
+    pub crate_version: String,
+    pub list_of_file: Vec<SourceFileItemData>,
+}
+
+/// the html of the line is already highlighted and escaped by the server
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SourceLineItemData {
+    pub line_number: usize,
+    pub html: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SourceFileData {
+    pub crate_name: String,
+    pub crate_version: String,
+    pub path: String,
+    pub syntax_name: String,
+    pub binary: bool,
+    pub truncated: bool,
+    pub list_of_line: Vec<SourceLineItemData>,
+}
+// endregion: source code
+
+// region: annotations
+
+/// a note about a `file:line` range of the crate source code
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct AnnotationItemData {
+    /// relative to the crate folder
+    pub path: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub note: String,
+}
+
+/// draft annotations for one crate version, stored in sled until the review is saved
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct AnnotationListData {
+    pub crate_name: String,
+    pub crate_version: String,
+    pub list_of_annotation: Vec<AnnotationItemData>,
+}
+
+/// row_number is None for a new annotation
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct AnnotationEditData {
+    pub crate_name: String,
+    pub crate_version: String,
+    pub row_number: Option<usize>,
+    pub annotation: AnnotationItemData,
+}
+// endregion: annotations
+
+// region: review progress
+
+/// one file marked as read, with the counts at the time of marking
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ReadFileItemData {
+    /// relative to the crate folder
+    pub path: String,
+    pub bytes: u64,
+    pub lines: usize,
+}
+
+/// how much of the crate version the reviewer has read
+/// The percent is for lines of text files. The thoroughness is suggested from the percent.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ReadProgressData {
+    pub crate_name: String,
+    pub crate_version: String,
+    pub files_read: usize,
+    pub files_total: usize,
+    pub lines_read: usize,
+    pub lines_total: usize,
+    pub bytes_read: u64,
+    pub bytes_total: u64,
+    pub percent_read: usize,
+    pub suggested_thoroughness: String,
+    pub list_of_read_path: Vec<String>,
+}
+// endregion: review progress
+
+// region: risk scan
+
+/// count of dangerous constructs in the source code of one crate version
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RiskCountsData {
+    pub unsafe_code: usize,
+    pub build_script: usize,
+    pub proc_macro: usize,
+    pub process: usize,
+    pub network: usize,
+    pub fs_write: usize,
+    pub include_bytes: usize,
+    pub extern_c: usize,
+    pub no_mangle: usize,
+    pub env: usize,
+}
+
+/// one place in the source code, the kind is the name of the field in RiskCountsData
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RiskFindingItemData {
+    pub kind: String,
+    pub path: String,
+    pub line_number: usize,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RiskScanData {
+    pub crate_name: String,
+    pub crate_version: String,
+    pub counts: RiskCountsData,
+    pub list_of_finding: Vec<RiskFindingItemData>,
+}
+// endregion: risk scan
+
+// region: file classes
+
+/// one suspicious file in the crate package: precompiled binary, archive, large opaque data or minified source
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct FileFindingItemData {
+    /// relative to the crate folder
+    pub path: String,
+    pub kind: String,
+    pub size: u64,
+    pub detail: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct FileClassData {
+    pub crate_name: String,
+    pub crate_version: String,
+    pub list_of_finding: Vec<FileFindingItemData>,
+}
+// endregion: file classes
+
+// region: vcs verify
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct VcsVerifyFilterData {
+    pub crate_name: String,
+    pub crate_version: String,
+    /// local clone of the crate repository
+    pub repo_path: String,
+}
+
+/// one file of the package that does not match the repository, the status is `only_in_package` or `differs`
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct VcsFileItemData {
+    /// relative to the crate folder
+    pub path: String,
+    pub status: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct VcsVerifyData {
+    pub crate_name: String,
+    pub crate_version: String,
+    pub repo_path: String,
+    pub git_sha1: String,
+    pub path_in_vcs: String,
+    /// published with uncommitted changes
+    pub dirty: bool,
+    pub files_checked: usize,
+    pub list_of_file: Vec<VcsFileItemData>,
+}
+// endregion: vcs verify
+
+// region: progress
+
+/// one event of a long-running operation, sent as server-sent event
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ProgressEventData {
+    pub operation: String,
+    /// the current crate or step
+    pub current: String,
+    pub n: usize,
+    pub m: usize,
+    pub error: String,
+    pub finished: bool,
+}
+// endregion: progress
+
+// region: jobs
+
+/// one background job, the state is `queued`, `running`, `finished`, `failed` or `cancelled`
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct JobItemData {
+    pub job_id: u64,
+    pub kind: String,
+    /// the crate or the other argument of the job
+    pub description: String,
+    pub state: String,
+    pub queued_at: String,
+    pub started_at: String,
+    pub ended_at: String,
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct JobListData {
+    pub list_of_job: Vec<JobItemData>,
+    /// the last failures persisted in db_sled, also from the previous runs
+    pub list_of_failure: Vec<JobItemData>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct JobFilterData {
+    pub job_id: u64,
 }
+// endregion: jobs
@@ -0,0 +1,382 @@
+// crate_diff_mod.rs
+
+//! diff between two versions of a crate
+//! Both .crate archives are unpacked into clean folders and compared file by file and line by line.
+//! A review with crev `diff_base` covers only this delta.
+
+use similar::ChangeTag;
+
+use crate::common_structs_mod::*;
+
+/// the file `.cargo-ok` is added by cargo after unpacking
+const IGNORED_FILES: [&str; 1] = [".cargo-ok"];
+/// lines of context around the changes
+const CONTEXT_RADIUS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileDiffStatus {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl FileDiffStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileDiffStatus::Added => "added",
+            FileDiffStatus::Removed => "removed",
+            FileDiffStatus::Modified => "modified",
+        }
+    }
+}
+
+/// one line of a hunk. Line numbers start with 1.
+#[derive(Debug, Clone)]
+pub struct DiffLine {
+    pub tag: ChangeTag,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    /// without the line ending
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffHunk {
+    /// like `@@ -1,4 +1,5 @@`
+    pub header: String,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    /// relative to the crate folder
+    pub path: String,
+    pub status: FileDiffStatus,
+    /// binary files are compared only by content, without hunks
+    pub binary: bool,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CrateDiff {
+    pub crate_name: String,
+    pub old_version: String,
+    pub new_version: String,
+    /// only the changed files, sorted by path
+    pub files: Vec<FileDiff>,
+}
+
+/// unpack both versions into clean folders and compare them
+pub fn crate_diff(crate_name: &str, old_version: &str, new_version: &str) -> anyhow::Result<CrateDiff> {
+    let files = crate::crev_mod::with_two_clean_src_folders(crate_name, old_version, new_version, diff_folders)?;
+    Ok(CrateDiff {
+        crate_name: crate_name.to_string(),
+        old_version: old_version.to_string(),
+        new_version: new_version.to_string(),
+        files,
+    })
+}
+
+/// compare all files in two folders
+pub fn diff_folders(old_folder: &std::path::Path, new_folder: &std::path::Path) -> anyhow::Result<Vec<FileDiff>> {
+    let old_files = list_relative_files(old_folder)?;
+    let new_files = list_relative_files(new_folder)?;
+    let mut all_files: Vec<&String> = old_files.iter().chain(new_files.iter()).collect();
+    all_files.sort();
+    all_files.dedup();
+
+    let mut files = vec![];
+    for path in all_files.into_iter() {
+        let old_content = if old_files.contains(path) { Some(std::fs::read(old_folder.join(path))?) } else { None };
+        let new_content = if new_files.contains(path) { Some(std::fs::read(new_folder.join(path))?) } else { None };
+        if let Some(file_diff) = diff_file(path, old_content.as_deref(), new_content.as_deref()) {
+            files.push(file_diff);
+        }
+    }
+    Ok(files)
+}
+
+/// None if the content is equal
+fn diff_file(path: &str, old_content: Option<&[u8]>, new_content: Option<&[u8]>) -> Option<FileDiff> {
+    let status = match (old_content, new_content) {
+        (None, None) => return None,
+        (None, Some(_)) => FileDiffStatus::Added,
+        (Some(_), None) => FileDiffStatus::Removed,
+        (Some(old), Some(new)) if old == new => return None,
+        (Some(_), Some(_)) => FileDiffStatus::Modified,
+    };
+    let old_text = std::str::from_utf8(old_content.unwrap_or_default());
+    let new_text = std::str::from_utf8(new_content.unwrap_or_default());
+    let (old_text, new_text) = match (old_text, new_text) {
+        (Ok(old_text), Ok(new_text)) => (old_text, new_text),
+        _ => {
+            return Some(FileDiff {
+                path: path.to_string(),
+                status,
+                binary: true,
+                lines_added: 0,
+                lines_removed: 0,
+                hunks: vec![],
+            })
+        }
+    };
+
+    let text_diff = similar::TextDiff::from_lines(old_text, new_text);
+    let mut file_diff = FileDiff {
+        path: path.to_string(),
+        status,
+        binary: false,
+        lines_added: 0,
+        lines_removed: 0,
+        hunks: vec![],
+    };
+    for group in text_diff.grouped_ops(CONTEXT_RADIUS).iter() {
+        let mut hunk = DiffHunk {
+            header: similar::udiff::UnifiedHunkHeader::new(group).to_string(),
+            lines: vec![],
+        };
+        for op in group.iter() {
+            for change in text_diff.iter_changes(op) {
+                match change.tag() {
+                    ChangeTag::Insert => file_diff.lines_added += 1,
+                    ChangeTag::Delete => file_diff.lines_removed += 1,
+                    ChangeTag::Equal => {}
+                }
+                hunk.lines.push(DiffLine {
+                    tag: change.tag(),
+                    old_line: change.old_index().map(|x| x + 1),
+                    new_line: change.new_index().map(|x| x + 1),
+                    text: change.value().trim_end_matches(['\n', '\r']).to_string(),
+                });
+            }
+        }
+        file_diff.hunks.push(hunk);
+    }
+    Some(file_diff)
+}
+
+/// pair the deleted lines with the following inserted lines
+fn side_by_side_rows(lines: &[DiffLine]) -> Vec<DiffRowItemData> {
+    let mut rows = vec![];
+    let mut deleted: Vec<&DiffLine> = vec![];
+    let mut inserted: Vec<&DiffLine> = vec![];
+    for line in lines.iter() {
+        match line.tag {
+            ChangeTag::Delete => {
+                if !inserted.is_empty() {
+                    flush_changed_rows(&mut deleted, &mut inserted, &mut rows);
+                }
+                deleted.push(line);
+            }
+            ChangeTag::Insert => inserted.push(line),
+            ChangeTag::Equal => {
+                flush_changed_rows(&mut deleted, &mut inserted, &mut rows);
+                rows.push(DiffRowItemData {
+                    row_kind: "line".to_string(),
+                    text: String::new(),
+                    change: "equal".to_string(),
+                    old_line_number: line_number_to_string(line.old_line),
+                    old_text: line.text.clone(),
+                    new_line_number: line_number_to_string(line.new_line),
+                    new_text: line.text.clone(),
+                });
+            }
+        }
+    }
+    flush_changed_rows(&mut deleted, &mut inserted, &mut rows);
+    rows
+}
+
+fn flush_changed_rows(deleted: &mut Vec<&DiffLine>, inserted: &mut Vec<&DiffLine>, rows: &mut Vec<DiffRowItemData>) {
+    for i in 0..deleted.len().max(inserted.len()) {
+        let old = deleted.get(i);
+        let new = inserted.get(i);
+        let change = match (old, new) {
+            (Some(_), Some(_)) => "replace",
+            (Some(_), None) => "delete",
+            _ => "insert",
+        };
+        rows.push(DiffRowItemData {
+            row_kind: "line".to_string(),
+            text: String::new(),
+            change: change.to_string(),
+            old_line_number: line_number_to_string(old.and_then(|x| x.old_line)),
+            old_text: old.map(|x| x.text.clone()).unwrap_or_default(),
+            new_line_number: line_number_to_string(new.and_then(|x| x.new_line)),
+            new_text: new.map(|x| x.text.clone()).unwrap_or_default(),
+        });
+    }
+    deleted.clear();
+    inserted.clear();
+}
+
+fn line_number_to_string(line_number: Option<usize>) -> String {
+    line_number.map(|x| x.to_string()).unwrap_or_default()
+}
+
+/// the highest of the reviewed versions that is below the crate version, the suggested diff base
+pub fn highest_version_below<'a>(reviewed_versions: impl Iterator<Item = &'a str>, crate_version: &str) -> Option<String> {
+    let crate_version = semver::Version::parse(crate_version).ok()?;
+    reviewed_versions
+        .filter_map(|x| semver::Version::parse(x).ok())
+        .filter(|x| *x < crate_version)
+        .max()
+        .map(|x| x.to_string())
+}
+
+/// relative paths with `/` separator, sorted
+fn list_relative_files(folder: &std::path::Path) -> anyhow::Result<Vec<String>> {
+    let mut files = vec![];
+    for entry in walkdir::WalkDir::new(folder).sort_by_file_name() {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(folder)?;
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if IGNORED_FILES.contains(&relative.as_str()) {
+            continue;
+        }
+        files.push(relative);
+    }
+    Ok(files)
+}
+
+impl CrateDiff {
+    /// one line per changed file like `modified src/lib.rs +3 -1`
+    pub fn summary(&self) -> String {
+        let mut text = String::new();
+        for file in self.files.iter() {
+            if file.binary {
+                text.push_str(&format!("{:8} {} (binary)\n", file.status.as_str(), file.path));
+            } else {
+                text.push_str(&format!("{:8} {} +{} -{}\n", file.status.as_str(), file.path, file.lines_added, file.lines_removed));
+            }
+        }
+        text
+    }
+
+    /// data for the side-by-side view in the GUI
+    /// The deleted and inserted lines of a change are paired in the same row.
+    pub fn to_version_diff_data(&self, max_rows: usize) -> VersionDiffData {
+        let mut data = VersionDiffData {
+            crate_name: self.crate_name.clone(),
+            old_version: self.old_version.clone(),
+            new_version: self.new_version.clone(),
+            ..Default::default()
+        };
+        for file in self.files.iter() {
+            data.lines_added += file.lines_added;
+            data.lines_removed += file.lines_removed;
+            data.list_of_file.push(FileDiffItemData {
+                path: file.path.clone(),
+                status: file.status.as_str().to_string(),
+                binary: file.binary,
+                lines_added: file.lines_added,
+                lines_removed: file.lines_removed,
+            });
+            if data.truncated {
+                continue;
+            }
+            data.list_of_row.push(DiffRowItemData {
+                row_kind: "file".to_string(),
+                text: format!("{} {}{}", file.status.as_str(), file.path, if file.binary { " (binary)" } else { "" }),
+                ..Default::default()
+            });
+            for hunk in file.hunks.iter() {
+                data.list_of_row.push(DiffRowItemData {
+                    row_kind: "hunk".to_string(),
+                    text: hunk.header.clone(),
+                    ..Default::default()
+                });
+                data.list_of_row.extend(side_by_side_rows(&hunk.lines));
+            }
+            if data.list_of_row.len() > max_rows {
+                data.list_of_row.truncate(max_rows);
+                data.truncated = true;
+            }
+        }
+        data
+    }
+
+    /// the diff in the unified format for the terminal
+    pub fn to_unified_text(&self) -> String {
+        let mut text = String::new();
+        for file in self.files.iter() {
+            let old_path = if file.status == FileDiffStatus::Added { "/dev/null".to_string() } else { format!("a/{}", file.path) };
+            let new_path = if file.status == FileDiffStatus::Removed { "/dev/null".to_string() } else { format!("b/{}", file.path) };
+            text.push_str(&format!("--- {}\n+++ {}\n", old_path, new_path));
+            if file.binary {
+                text.push_str("Binary files differ\n");
+            }
+            for hunk in file.hunks.iter() {
+                text.push_str(&hunk.header);
+                text.push('\n');
+                for line in hunk.lines.iter() {
+                    let sign = match line.tag {
+                        ChangeTag::Insert => '+',
+                        ChangeTag::Delete => '-',
+                        ChangeTag::Equal => ' ',
+                    };
+                    text.push(sign);
+                    text.push_str(&line.text);
+                    text.push('\n');
+                }
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highest_version_below() {
+        let reviewed = ["0.9.0", "1.0.10", "1.0.2", "1.2.0"];
+        assert_eq!(highest_version_below(reviewed.iter().copied(), "1.1.0").unwrap(), "1.0.10");
+        // reviewing an older version suggests no newer base
+        assert_eq!(highest_version_below(reviewed.iter().copied(), "0.9.0"), None);
+        assert_eq!(highest_version_below(reviewed.iter().copied(), "2.0.0").unwrap(), "1.2.0");
+    }
+
+    #[test]
+    fn test_diff_folders() {
+        let old_folder = tempfile::tempdir().unwrap();
+        let new_folder = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(old_folder.path().join("src")).unwrap();
+        std::fs::create_dir_all(new_folder.path().join("src")).unwrap();
+        std::fs::write(old_folder.path().join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        std::fs::write(new_folder.path().join("src/lib.rs"), "fn a() {}\nfn c() {}\n").unwrap();
+        std::fs::write(old_folder.path().join("README.md"), "readme\n").unwrap();
+        std::fs::write(new_folder.path().join("README.md"), "readme\n").unwrap();
+        std::fs::write(old_folder.path().join("old.rs"), "old\n").unwrap();
+        std::fs::write(new_folder.path().join("build.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(new_folder.path().join(".cargo-ok"), "").unwrap();
+
+        let files = diff_folders(old_folder.path(), new_folder.path()).unwrap();
+        let summary: Vec<(&str, FileDiffStatus, usize, usize)> = files.iter().map(|x| (x.path.as_str(), x.status, x.lines_added, x.lines_removed)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("build.rs", FileDiffStatus::Added, 1, 0),
+                ("old.rs", FileDiffStatus::Removed, 0, 1),
+                ("src/lib.rs", FileDiffStatus::Modified, 1, 1),
+            ]
+        );
+        let hunk = &files[2].hunks[0];
+        assert_eq!(hunk.header, "@@ -1,2 +1,2 @@");
+        assert_eq!(hunk.lines[1].text, "fn b() {}");
+        assert_eq!(hunk.lines[1].old_line, Some(2));
+        assert_eq!(hunk.lines[2].new_line, Some(2));
+
+        let rows = side_by_side_rows(&hunk.lines);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].change, "replace");
+        assert_eq!(rows[1].old_text, "fn b() {}");
+        assert_eq!(rows[1].new_text, "fn c() {}");
+    }
+}
@@ -35,11 +35,25 @@ pub struct CratesIoPublishedBy {
     pub url: String,
 }
 
-/// GET from crates.io
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CratesIoOwnersResponse {
+    pub users: Vec<CratesIoOwner>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CratesIoOwner {
+    pub login: String,
+    pub kind: Option<String>,
+    pub url: Option<String>,
+}
+
+/// GET from crates.io, None if the crate is not on crates.io
 /// It is used only to store into db_version.
-pub fn crate_response(crate_name: &str) -> anyhow::Result<CratesIoCrateResponse> {
-    let response_text = crate_responses_get(crate_name)?;
-    crate_response_deserialize(response_text)
+pub fn crate_response(crate_name: &str) -> anyhow::Result<Option<CratesIoCrateResponse>> {
+    match crate_responses_get(crate_name)? {
+        Some(response_text) => Ok(Some(crate_response_deserialize(response_text)?)),
+        None => Ok(None),
+    }
 }
 
 fn crate_response_deserialize(response_text: String) -> anyhow::Result<CratesIoCrateResponse> {
@@ -47,17 +61,22 @@ fn crate_response_deserialize(response_text: String) -> anyhow::Result<CratesIoC
     Ok(crates_io_crate_response)
 }
 
-fn crate_responses_get(crate_name: &str) -> Result<String, anyhow::Error> {
+fn crate_responses_get(crate_name: &str) -> Result<Option<String>, anyhow::Error> {
     // trailing slash is forbidden. See sample in file crate_io_versions_for_crate.json
-    let url = format!("https://crates.io/api/v1/crates/{}", &crate_name);
-    log::debug!("get url: {}", &url);
-    let client = reqwest::blocking::Client::new();
-    let res = client
-        .get(url)
-        .header("User-Agent", "cargo_crev_reviews (github.com/bestia-dev/cargo_crev_reviews_workspace)")
-        .send()?;
-    let response_text = res.text()?;
-    Ok(response_text)
+    let url = format!("{}/crates/{}", crate::http_client_mod::api_url(), &crate_name);
+    match crate::http_client_mod::get_if_found(&url)? {
+        Some(response) => Ok(Some(response.text()?)),
+        None => Ok(None),
+    }
+}
+
+/// GET owners from crates.io
+/// It is used only to store into db_crate.
+pub fn owners_response(crate_name: &str) -> anyhow::Result<CratesIoOwnersResponse> {
+    let url = format!("{}/crates/{}/owners", crate::http_client_mod::api_url(), &crate_name);
+    let response_text = crate::http_client_mod::get(&url)?.text()?;
+    let crates_io_owners_response: CratesIoOwnersResponse = serde_json::from_str(&response_text)?;
+    Ok(crates_io_owners_response)
 }
 
 #[cfg(test)]
@@ -66,7 +85,7 @@ mod tests {
 
     #[test]
     fn test_crate_responses_get() {
-        let response_text = crate_responses_get("unwrap").unwrap();
+        let response_text = crate_responses_get("unwrap").unwrap().unwrap();
         if !response_text.starts_with(r#"{"categories":[{""#) {
             panic!("wrong start");
         }
@@ -4,6 +4,7 @@
 
 // crev-lib="0.22.0"
 // crev-data="0.22.0"
+// crev-wot="0.22.0"
 // anyhow="1.0.43"
 // serde = { version = "1.0", features = ["derive"] }
 // serde_json = "1.0.66"
@@ -17,16 +18,14 @@
 
 use anyhow::Context;
 use crev_data::{
-    proof::{CommonOps, ContentExt},
+    proof::ContentExt,
     Level, Rating,
 };
 use crev_lib::ProofStore;
-use dev_bestia_string_utils::*;
 use lazy_static::lazy_static;
 use serde::Deserialize;
-use std::{env, sync::Mutex};
+use std::sync::Mutex;
 use std::{ops::Range, str::FromStr, vec};
-use unwrap::unwrap;
 
 use crate::common_structs_mod::*;
 use crate::stdio_input_password_mod;
@@ -36,10 +35,21 @@ lazy_static! {
     /// mutable static, because it is hard to pass variables around with async closures
     static ref CREV_UNLOCKED: Mutex<Option<crev_data::id::UnlockedId>>=Mutex::new(None);
     static ref CREV_LOCAL: Mutex<Option<crev_lib::Local>>=Mutex::new(None);
+    /// one writer at a time in the proofs directory: delete, insert, commit and git pull
+    /// The long rpc methods run on worker threads, so two tabs can save reviews at the same time.
+    static ref PROOF_WRITE: Mutex<()>=Mutex::new(());
+}
+
+/// a panic in another worker thread must not lock out the crev id for the rest of the session
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
 }
 
 #[derive(Deserialize, Clone, Default, Debug)]
 pub struct PackageSegment {
+    /// `https://crates.io` or the url of the git repository, path or alternative registry
+    #[serde(default)]
+    pub source: String,
     pub name: String,
     pub version: String,
     pub digest: String,
@@ -59,6 +69,13 @@ pub struct ProofCrevForReview {
     pub package: PackageSegment,
     pub review: Option<ReviewSegment>,
     pub comment: Option<String>,
+    #[serde(default)]
+    pub issues: Vec<IssueItemData>,
+    #[serde(default)]
+    pub advisories: Vec<AdvisoryItemData>,
+    /// a differential review covers only the changes after this package
+    #[serde(default, rename = "diff-base")]
+    pub diff_base: Option<PackageSegment>,
 }
 
 // region: copied from cargo-crev  (maybe should add this to crev-lib?)
@@ -66,17 +83,23 @@ pub const VCS_INFO_JSON_FILE: &str = ".cargo_vcs_info.json";
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct VcsInfoJson {
-    git: VcsInfoJsonGit,
+    pub git: VcsInfoJsonGit,
+    /// the folder of the package inside the repository, empty for the repository root
+    #[serde(default)]
+    pub path_in_vcs: String,
 }
 
+/// a struct and not an enum, because cargo adds `dirty` next to `sha1`
 #[derive(Debug, Clone, Deserialize)]
-pub enum VcsInfoJsonGit {
-    #[serde(rename = "sha1")]
-    Sha1(String),
+pub struct VcsInfoJsonGit {
+    pub sha1: String,
+    /// published with `--allow-dirty` from uncommitted changes
+    #[serde(default)]
+    pub dirty: bool,
 }
 
 impl VcsInfoJson {
-    fn read_from_crate_dir(pkg_dir: &std::path::Path) -> anyhow::Result<Option<Self>> {
+    pub fn read_from_crate_dir(pkg_dir: &std::path::Path) -> anyhow::Result<Option<Self>> {
         let path = pkg_dir.join(VCS_INFO_JSON_FILE);
 
         if path.exists() {
@@ -88,8 +111,7 @@ impl VcsInfoJson {
         }
     }
     fn get_git_revision(&self) -> Option<String> {
-        let VcsInfoJsonGit::Sha1(ref s) = self.git;
-        Some(s.to_string())
+        Some(self.git.sha1.to_string())
     }
 }
 
@@ -106,73 +128,56 @@ pub fn unlock_crev_id_interactively() -> anyhow::Result<()> {
     println!("Unlocked.");
 
     // write to static mut
-    *CREV_UNLOCKED.lock().unwrap() = Some(crev_unlocked);
-    *CREV_LOCAL.lock().unwrap() = Some(crev_local);
+    *lock(&CREV_UNLOCKED) = Some(crev_unlocked);
+    *lock(&CREV_LOCAL) = Some(crev_local);
 
     // return
     Ok(())
 }
 
+/// open the crev local without unlocking the crev_id
+/// it is enough for reading and deleting proofs, but not for signing new proofs
+pub fn open_crev_local() -> anyhow::Result<()> {
+    let crev_local = crev_lib::local::Local::auto_open()?;
+    *lock(&CREV_LOCAL) = Some(crev_local);
+    Ok(())
+}
+
 /// list my reviews
+/// only valid proofs signed with my crev id. The problems are ignored here, they are listed in srv_proof_problems.
 pub fn crev_list_my_reviews(filter: &Option<ReviewFilterData>) -> anyhow::Result<Vec<ProofCrevForReview>> {
     let mut vec_proof: Vec<ProofCrevForReview> = vec![];
     // open every *.proof.crev file in my crev reviews directory
-    match proof_crev_files_paths() {
+    match crate::proof_store_mod::read_my_proof_store() {
         Err(err) => {
             // just write it and return an empty vector
             log::error!("There are no personal reviews yet: {}", err);
         }
-        Ok(paths) => {
-            for path in paths.iter() {
-                let file_content = std::fs::read_to_string(path)?;
-                let mut pos_cursor = 0;
-                loop {
-                    let range = find_range_including_delimiters(&file_content, &mut pos_cursor, "----- BEGIN CREV PROOF -----", "----- END CREV PROOF -----");
-                    match range {
-                        Some(mut range) => {
-                            // if there is some white space after the segment, include it in the range.
-                            let pos_2 = find_pos_before_delimiter(&file_content, pos_cursor, "----- BEGIN CREV PROOF -----");
-                            range.end = match pos_2 {
-                                Some(pos_2) => pos_2,
-                                None => file_content.len(),
-                            };
-
-                            let proof_text = unwrap!(file_content.get(range.clone()));
-                            // This must not panic because it is internal to the previous range.
-                            let range_yaml = unwrap!(find_range_between_delimiters(
-                                proof_text,
-                                &mut 0,
-                                "----- BEGIN CREV PROOF -----",
-                                "----- SIGN CREV PROOF -----",
-                            ));
-                            // if this panics it's a bug in the code and not an exception to handle
-                            let yaml = unwrap!(proof_text.get(range_yaml));
-                            let proof_crev: ProofCrevForReview = unwrap!(serde_yaml::from_str(yaml));
-                            // push it to vector, if it filters
-                            match filter {
-                                // no filter, push all for list
-                                None => vec_proof.push(proof_crev),
-                                Some(filter) => {
-                                    // always filtered at least by crate_name
-                                    if filter.crate_name == proof_crev.package.name {
-                                        match &filter.crate_version {
-                                            None => {
-                                                // all the versions of one crate
-                                                vec_proof.push(proof_crev);
-                                            }
-                                            Some(version) => {
-                                                if version == proof_crev.package.version.as_str() {
-                                                    // exact match
-                                                    vec_proof.push(proof_crev);
-                                                    break;
-                                                }
-                                            }
-                                        }
+        Ok(content) => {
+            for proof_in_file in content.proofs.into_iter() {
+                let proof_crev = proof_in_file.proof_crev;
+                // push it to vector, if it filters
+                match filter {
+                    // no filter, push all for list
+                    None => vec_proof.push(proof_crev),
+                    Some(filter) => {
+                        // always filtered at least by crate_name
+                        if filter.crate_name == proof_crev.package.name {
+                            match &filter.crate_version {
+                                None => {
+                                    // all the versions of one crate
+                                    vec_proof.push(proof_crev);
+                                }
+                                Some(version) => {
+                                    // the same version from crates.io and from git are different reviews
+                                    if version == proof_crev.package.version.as_str() && same_source(&proof_crev.package.source, &filter.source) {
+                                        // exact match
+                                        vec_proof.push(proof_crev);
+                                        break;
                                     }
                                 }
                             }
                         }
-                        None => break,
                     }
                 }
             }
@@ -210,14 +215,18 @@ pub fn crev_edit_or_new_review(filter: ReviewFilterData) -> anyhow::Result<Proof
         crate_name: filter.crate_name.clone(),
         crate_version: None,
         old_crate_version: None,
+        source: String::new(),
     };
 
     let mut vec_of_reviews = crev_list_my_reviews(&Some(new_filter))?;
     if vec_of_reviews.is_empty() {
         anyhow::bail!("Crate reviews for {} not found in my reviews!", filter.crate_name.as_str());
     }
-    // search for the exact version
-    match vec_of_reviews.iter().find(|&x| &x.package.version == filter.crate_version.as_ref().unwrap()) {
+    // search for the exact version from the same source
+    match vec_of_reviews
+        .iter()
+        .find(|&x| &x.package.version == filter.crate_version.as_ref().unwrap() && same_source(&x.package.source, &filter.source))
+    {
         None => {
             // use the text from the last review. Probably in the new review it will be the same.
             crev_sort_list_by_name_version(&mut vec_of_reviews);
@@ -227,7 +236,10 @@ pub fn crev_edit_or_new_review(filter: ReviewFilterData) -> anyhow::Result<Proof
                 None => {}
                 Some(comment) => review.comment = Some(format!("// Just suggesting a copy from your last review:\n\n\n{}", comment)),
             }
+            // a differential review is an explicit choice in the review editor
+            review.diff_base = None;
             review.package.version = filter.crate_version.context("none version")?.clone();
+            review.package.source = filter.source.clone();
 
             // return
             Ok(review)
@@ -244,6 +256,7 @@ pub fn crev_new_version(filter: ReviewFilterData) -> anyhow::Result<ProofCrevFor
         crate_name: filter.crate_name.clone(),
         crate_version: None,
         old_crate_version: None,
+        source: String::new(),
     };
     let vec_of_reviews = crev_list_my_reviews(&Some(new_filter))?;
     if vec_of_reviews.is_empty() {
@@ -255,13 +268,10 @@ pub fn crev_new_version(filter: ReviewFilterData) -> anyhow::Result<ProofCrevFor
         if version == max_version.as_str() {
             anyhow::bail!("Max version {} is already reviewed!", &max_version);
         }
-        let path_dir = crate::cargo_registry_mod::cargo_registry_src_dir_for_crate(&filter.crate_name, &max_version);
-        if !path_dir.exists() {
-            anyhow::bail!("Max version {} src is not cached on your system. It means you don't have a dependency on it in your projects. You cannot review a crate version that you don't use.", &max_version);
-        }
         for x in vec_of_reviews.iter() {
             if x.package.version.as_str() == version {
                 let mut review = x.clone();
+                review.diff_base = None;
                 review.package.version = max_version;
                 return Ok(review);
             }
@@ -272,7 +282,31 @@ pub fn crev_new_version(filter: ReviewFilterData) -> anyhow::Result<ProofCrevFor
     anyhow::bail!("Cannot add new version for {}!", &filter.crate_name);
 }
 
+/// the highest of my crates.io reviews below this version, empty if there is none
+/// The review editor offers it as the diff base, but does not fill it in.
+pub fn suggested_diff_base_version(crate_name: &str, crate_version: &str) -> anyhow::Result<String> {
+    let vec_of_reviews = my_crates_io_reviews(crate_name)?;
+    let versions = vec_of_reviews.iter().map(|x| x.package.version.as_str());
+    Ok(crate::crate_diff_mod::highest_version_below(versions, crate_version).unwrap_or_default())
+}
+
+fn my_crates_io_reviews(crate_name: &str) -> anyhow::Result<Vec<ProofCrevForReview>> {
+    let filter = ReviewFilterData {
+        crate_name: crate_name.to_string(),
+        crate_version: None,
+        old_crate_version: None,
+        source: String::new(),
+    };
+    let vec_of_reviews = crev_list_my_reviews(&Some(filter))?;
+    Ok(vec_of_reviews
+        .into_iter()
+        .filter(|x| x.package.name == crate_name && x.review.is_some() && crate::package_source_mod::is_crates_io(&x.package.source))
+        .collect())
+}
+
 /// create save review proof
+/// The source is the crev source url, empty for crates.io.
+#[allow(clippy::too_many_arguments)]
 pub fn crev_save_review(
     crate_name: &str,
     crate_version: &str,
@@ -280,6 +314,10 @@ pub fn crev_save_review(
     understanding: crev_data::Level,
     rating: crev_data::Rating,
     comment_md: &str,
+    issues: &[IssueItemData],
+    advisories: &[AdvisoryItemData],
+    diff_base_version: &str,
+    source: &str,
 ) -> anyhow::Result<()> {
     let review = crev_data::proof::Review {
         thoroughness,
@@ -287,73 +325,166 @@ pub fn crev_save_review(
         rating,
     };
 
-    let digest_clean = calculate_crate_digest(crate_name, crate_version)?;
-    let src_folder = crate::cargo_registry_mod::cargo_registry_src_dir_for_crate(crate_name, crate_version);
-    if !src_folder.exists() {
-        anyhow::bail!("The crate {}-{} does not exist in the local cargo registry cache. You must use the crate as dependency in your projects, if you want to review it. This way cargo will download the source code for the crate that you review. ", crate_name, crate_version);
-    }
-    let vcs = VcsInfoJson::read_from_crate_dir(&src_folder)?;
-
-    // new reviews only for crates that come from crates.io
-    let crate_version_version = crev_data::Version::from_str(crate_version)?;
-    let package_id = crev_data::proof::PackageVersionId::new("https://crates.io".to_string(), crate_name.to_string(), crate_version_version);
-
-    let package_info = crev_data::proof::PackageInfo {
-        id: package_id,
-        digest: digest_clean.into_vec(),
-        digest_type: crev_data::proof::default_digest_type(),
-        revision: vcs_info_to_revision_string(vcs),
-        revision_type: crev_data::proof::default_revision_type(),
+    let package_info = if crate::package_source_mod::is_crates_io(source) {
+        // any published version can be reviewed, also if it is not a dependency in my projects.
+        // The digest and the revision come from the checksum-verified .crate, downloaded if needed.
+        let (digest_clean, revision) = with_clean_src_folder(crate_name, crate_version, digest_and_revision)?;
+        crev_package_info(crate::package_source_mod::CRATES_IO_SOURCE, crate_name, crate_version, digest_clean, revision)?
+    } else {
+        // git, path and alternative registry dependencies of the current project
+        if !diff_base_version.is_empty() {
+            anyhow::bail!("A differential review is possible only for crates from crates.io.");
+        }
+        let package_source = crate::package_source_mod::package_source_in_project(crate_name, crate_version, source)?;
+        crev_package_info(&package_source.crev_source, crate_name, crate_version, package_source.digest, package_source.revision)?
     };
-
-    let proof =
-        CREV_UNLOCKED
-            .lock()
-            .unwrap()
-            .as_ref()
-            .unwrap()
-            .as_public_id()
-            .create_package_review_proof(package_info.clone(), review, comment_md.to_string())?;
+    // the draft annotations from the source browser are rendered into the comment
+    let comment_md = crate::annotation_mod::comment_with_annotations(crate_name, crate_version, comment_md)?;
+
+    let mut proof = lock(&CREV_UNLOCKED)
+        .as_ref()
+        .unwrap()
+        .as_public_id()
+        .create_package_review_proof(package_info.clone(), review, comment_md)?;
+    // issues and advisories have the same yaml format as in the proof
+    proof.issues = serde_yaml::from_value(serde_yaml::to_value(issues)?)?;
+    let advisories: Vec<AdvisoryItemData> = advisories
+        .iter()
+        .map(|x| AdvisoryItemData {
+            range: if x.range.is_empty() { "all".to_string() } else { x.range.clone() },
+            ..x.clone()
+        })
+        .collect();
+    proof.advisories = serde_yaml::from_value(serde_yaml::to_value(&advisories)?)?;
+    // differential review: the earlier reviewed package and its digest
+    if !diff_base_version.is_empty() {
+        if semver::Version::parse(diff_base_version)? >= semver::Version::parse(crate_version)? {
+            anyhow::bail!("The diff base {} must be an earlier version than {}.", diff_base_version, crate_version);
+        }
+        // the differential review builds on the earlier review of mine
+        if !my_crates_io_reviews(crate_name)?.iter().any(|x| x.package.version == diff_base_version) {
+            anyhow::bail!("The diff base {} {} has no review of mine. Review it first or save a full review.", crate_name, diff_base_version);
+        }
+        let (base_digest, base_revision) = with_clean_src_folder(crate_name, diff_base_version, digest_and_revision)?;
+        proof.diff_base = Some(crev_package_info(
+            crate::package_source_mod::CRATES_IO_SOURCE,
+            crate_name,
+            diff_base_version,
+            base_digest,
+            base_revision,
+        )?);
+    }
 
     // for `sign_by()` to work there must be `use crev_data::proof::ContentExt;`
-    let proof = proof.sign_by(&CREV_UNLOCKED.lock().unwrap().as_ref().unwrap())?;
+    let proof = proof.sign_by(&lock(&CREV_UNLOCKED).as_ref().unwrap())?;
 
+    let _proof_write = lock(&PROOF_WRITE);
     // if exists an old proof with same crate+version, delete it and then save the new one
-    delete_review_proofs(crate_name, crate_version)?;
+    delete_review_proofs_locked(crate_name, crate_version, Some(source))?;
 
     // it needs `use crev_lib::ProofStore;`
-    CREV_LOCAL.lock().unwrap().as_ref().unwrap().insert(&proof)?;
+    lock(&CREV_LOCAL).as_ref().unwrap().insert(&proof)?;
 
     let commit_msg = format!("Add review for {} v{}", crate_name, crate_version);
-    CREV_LOCAL.lock().unwrap().as_ref().unwrap().proof_dir_commit(&commit_msg)?;
+    lock(&CREV_LOCAL).as_ref().unwrap().proof_dir_commit(&commit_msg)?;
+    // the draft annotations stay in the db, the next save generates the section again from all of them
 
     // return
     Ok(())
 }
 
+/// package info for the proof with the source url for crev
+fn crev_package_info(source: &str, crate_name: &str, crate_version: &str, digest: crev_data::Digest, revision: String) -> anyhow::Result<crev_data::proof::PackageInfo> {
+    let crate_version_version = crev_data::Version::from_str(crate_version)?;
+    let package_id = crev_data::proof::PackageVersionId::new(source.to_string(), crate_name.to_string(), crate_version_version);
+
+    Ok(crev_data::proof::PackageInfo {
+        id: package_id,
+        digest: digest.into_vec(),
+        digest_type: crev_data::proof::default_digest_type(),
+        revision,
+        revision_type: crev_data::proof::default_revision_type(),
+    })
+}
+
+/// call the function with the clean src folder while the crate version is locked
+/// The download and the unpack of the same crate version never run in parallel.
+/// No other thread can unpack into the same folder until the function returns.
+/// Every read of the src folder must be inside the function, the folder can be unpacked again after the lock is released.
+pub fn with_clean_src_folder<T, F>(crate_name: &str, crate_version: &str, function: F) -> anyhow::Result<T>
+where
+    F: FnOnce(&std::path::Path) -> anyhow::Result<T>,
+{
+    let crate_version_lock = crate::cargo_registry_mod::crate_version_lock(crate_name, crate_version);
+    let _guard = lock(&crate_version_lock);
+    let src_folder = clean_src_folder_locked(crate_name, crate_version)?;
+    function(&src_folder)
+}
+
+/// call the function with the clean src folders of two versions of the crate while both are locked
+/// The locks are always taken in the same order, so two diffs in the opposite direction cannot deadlock.
+pub fn with_two_clean_src_folders<T, F>(crate_name: &str, version_1: &str, version_2: &str, function: F) -> anyhow::Result<T>
+where
+    F: FnOnce(&std::path::Path, &std::path::Path) -> anyhow::Result<T>,
+{
+    if version_1 == version_2 {
+        return with_clean_src_folder(crate_name, version_1, |src_folder| function(src_folder, src_folder));
+    }
+    let (first_version, second_version) = if version_1 < version_2 {
+        (version_1, version_2)
+    } else {
+        (version_2, version_1)
+    };
+    let first_lock = crate::cargo_registry_mod::crate_version_lock(crate_name, first_version);
+    let _first_guard = lock(&first_lock);
+    let second_lock = crate::cargo_registry_mod::crate_version_lock(crate_name, second_version);
+    let _second_guard = lock(&second_lock);
+    let src_folder_1 = clean_src_folder_locked(crate_name, version_1)?;
+    let src_folder_2 = clean_src_folder_locked(crate_name, version_2)?;
+    function(&src_folder_1, &src_folder_2)
+}
+
+/// call the function with the clean temp folder, never with the `cargo registry src`
+/// The code editor opens this folder, because its intellisense server alters the files.
+pub fn with_clean_temp_src_folder<T, F>(crate_name: &str, crate_version: &str, function: F) -> anyhow::Result<T>
+where
+    F: FnOnce(&std::path::Path) -> anyhow::Result<T>,
+{
+    let crate_version_lock = crate::cargo_registry_mod::crate_version_lock(crate_name, crate_version);
+    let _guard = lock(&crate_version_lock);
+    let cache_crate_file = crate::cargo_registry_mod::verified_crate_file(crate_name, crate_version)?;
+    let src_folder = clean_temp_src_folder_locked(crate_name, crate_version, &cache_crate_file)?;
+    function(&src_folder)
+}
+
 /// If the .crate targz file is not in `cargo registry cache`, download it to temp dir.
+/// The .crate file is always verified with the checksum from the registry index.
 /// Check that the crate src folder exist in `cargo registry src` and is clean.
 /// If not, unpack targz in temp folder and return that PathBuf
-pub fn get_clean_src_folder(crate_name: &str, crate_version: &str) -> anyhow::Result<std::path::PathBuf> {
-    let mut cache_crate_file = crate::cargo_registry_mod::cargo_registry_cache_file_for_crate(&crate_name, &crate_version);
-    if !cache_crate_file.exists() {
-        // download in temp file
-        cache_crate_file = crate::cargo_registry_mod::download_crate_from_crate_io(&crate_name, &crate_version)?;
-    }
-
-    let mut src_folder = crate::cargo_registry_mod::cargo_registry_src_dir_for_crate(&crate_name, &crate_version);
-    let mut use_temp_dir = false;
-    if !src_folder.exists() {
-        use_temp_dir = true;
-    } else if !crate::cargo_registry_mod::is_crate_clean(&src_folder, &cache_crate_file)? {
+/// The caller holds the crate_version_lock.
+fn clean_src_folder_locked(crate_name: &str, crate_version: &str) -> anyhow::Result<std::path::PathBuf> {
+    // from the cargo registry cache or downloaded into a temp file, always verified with the checksum from the registry index
+    let cache_crate_file = crate::cargo_registry_mod::verified_crate_file(crate_name, crate_version)?;
+
+    let src_folder = crate::cargo_registry_mod::cargo_registry_src_dir_for_crate(&crate_name, &crate_version);
+    if src_folder.exists() {
+        if crate::cargo_registry_mod::is_crate_clean(&src_folder, &cache_crate_file)? {
+            return Ok(src_folder);
+        }
         log::error!("Remove unclean crate: rm -r {:#?}", &src_folder);
-        use_temp_dir = true;
     }
+    clean_temp_src_folder_locked(crate_name, crate_version, &cache_crate_file)
+}
 
-    if use_temp_dir == true {
-        src_folder = crate::cargo_registry_mod::cargo_crev_reviews_src_dir_for_crate(&crate_name, &crate_version);
-        crate::cargo_registry_mod::unpack_from_targz_to_folder(&crate_name, &crate_version, cache_crate_file.parent().unwrap(), src_folder.parent().unwrap())
-            .unwrap();
+/// the clean temp folder from a previous unpack is reused, a half-unpacked or altered one is unpacked again
+/// The caller holds the crate_version_lock.
+fn clean_temp_src_folder_locked(crate_name: &str, crate_version: &str, cache_crate_file: &std::path::Path) -> anyhow::Result<std::path::PathBuf> {
+    let src_folder = crate::cargo_registry_mod::cargo_crev_reviews_src_dir_for_crate(&crate_name, &crate_version);
+    if src_folder.exists() && !crate::cargo_registry_mod::is_crate_clean(&src_folder, cache_crate_file)? {
+        std::fs::remove_dir_all(&src_folder)?;
+    }
+    if !src_folder.exists() {
+        crate::cargo_registry_mod::unpack_from_targz_to_folder(&crate_name, &crate_version, cache_crate_file.parent().unwrap(), src_folder.parent().unwrap())?;
     }
     Ok(src_folder)
 }
@@ -361,12 +492,23 @@ pub fn get_clean_src_folder(crate_name: &str, crate_version: &str) -> anyhow::Re
 /// if the `cargo registry src` does not exist or is unclean, download and unpack into temp folder
 /// and calculate digest there.
 pub fn calculate_crate_digest(crate_name: &str, crate_version: &str) -> anyhow::Result<crev_data::Digest> {
-    let src_folder = get_clean_src_folder(crate_name, crate_version)?;
+    with_clean_src_folder(crate_name, crate_version, digest_for_src_folder)
+}
 
+/// the digest and the vcs revision of the unpacked crate folder for the proof
+fn digest_and_revision(src_folder: &std::path::Path) -> anyhow::Result<(crev_data::Digest, String)> {
+    Ok((
+        digest_for_src_folder(src_folder)?,
+        vcs_info_to_revision_string(VcsInfoJson::read_from_crate_dir(src_folder)?),
+    ))
+}
+
+/// the crev digest of the unpacked crate folder
+fn digest_for_src_folder(src_folder: &std::path::Path) -> anyhow::Result<crev_data::Digest> {
     // Ignore only the file .cargo_ok, that is added after unpacking the targz
     let mut ignore_list = std::collections::HashSet::default();
     ignore_list.insert(std::path::PathBuf::from(".cargo-ok"));
-    let digest = crev_lib::get_recursive_digest_for_dir(&src_folder, &ignore_list)?;
+    let digest = crev_lib::get_recursive_digest_for_dir(src_folder, &ignore_list)?;
     Ok(digest)
 }
 
@@ -388,27 +530,19 @@ pub fn rating_to_string(rating: &Rating) -> String {
     }
 }
 
-// verify the signature of a proof
-pub fn _verify_proof(yaml: &str) -> anyhow::Result<()> {
-    let proofs = crev_data::proof::Proof::parse_from(yaml.as_bytes())?;
-    let proof = &proofs[0];
-    log::info!("signature: {}", proof.signature());
-    log::info!("id: {}", proof.author_public_id().id);
-    log::info!("body:\n{}", proof.body());
-    proof.verify()?;
-
-    log::info!("Proof verified ok.");
-    // return
-    Ok(())
+/// my crev id from the opened crev local
+pub fn my_crev_id() -> anyhow::Result<crev_data::Id> {
+    let crev_id = lock(&CREV_LOCAL).as_ref().context("The crev local is not opened.")?.read_current_id()?;
+    Ok(crev_id)
 }
 
 pub fn reviews_dir() -> anyhow::Result<String> {
-    let path = CREV_LOCAL.lock().unwrap().as_ref().unwrap().get_proofs_dir_path()?;
-    let crev_id = CREV_LOCAL.lock().unwrap().as_ref().unwrap().read_current_id()?;
+    let path = lock(&CREV_LOCAL).as_ref().unwrap().get_proofs_dir_path()?;
+    let crev_id = lock(&CREV_LOCAL).as_ref().unwrap().read_current_id()?;
     Ok(format!("{}/{}/reviews", path.to_str().unwrap(), crev_id))
 }
 
-fn proof_crev_files_paths() -> anyhow::Result<Vec<String>> {
+pub fn proof_crev_files_paths() -> anyhow::Result<Vec<String>> {
     let reviews_dir = reviews_dir()?;
     let mut v = Vec::new();
     for entry in std::fs::read_dir(&reviews_dir)? {
@@ -423,50 +557,38 @@ fn proof_crev_files_paths() -> anyhow::Result<Vec<String>> {
 }
 
 /// remove old proofs, so the new review proof will be unique
-pub fn delete_review_proofs(crate_name: &str, crate_version: &str) -> anyhow::Result<()> {
-    // open every *.proof.crev file in my crev reviews directory
-    for path in proof_crev_files_paths()?.iter() {
-        let mut file_content = std::fs::read_to_string(path)?;
-        let mut pos_cursor = 0;
-        let mut vec_range: Vec<Range<usize>> = vec![];
-        loop {
-            let range = find_range_including_delimiters(&file_content, &mut pos_cursor, "----- BEGIN CREV PROOF -----", "----- END CREV PROOF -----");
-            match range {
-                Some(mut range) => {
-                    // if there is some white space after the segment, include it in the range.
-                    let pos_2 = find_pos_before_delimiter(&file_content, pos_cursor, "----- BEGIN CREV PROOF -----");
-                    range.end = match pos_2 {
-                        Some(pos_2) => pos_2,
-                        None => file_content.len(),
-                    };
-
-                    let proof_text = unwrap!(file_content.get(range.clone()));
-                    // This must not panic because it is internal to the previous range.
-                    let range_yaml = unwrap!(find_range_between_delimiters(
-                        proof_text,
-                        &mut 0,
-                        "----- BEGIN CREV PROOF -----",
-                        "----- SIGN CREV PROOF -----",
-                    ));
-                    // if this panics it's a bug in the code and not an exception to handle
-                    let yaml = unwrap!(proof_text.get(range_yaml));
-                    let proof_crev: ProofCrevForReview = unwrap!(serde_yaml::from_str(yaml));
-                    // I will remove only if it has the review segment.
-                    if proof_crev.review.is_some() && proof_crev.package.name == crate_name && proof_crev.package.version == crate_version {
-                        vec_range.push(range.clone());
-                    }
-                }
-                None => break,
-            }
-        }
+/// proofs with problems are never removed automatically
+/// With Some(source) only the reviews from this source are deleted, with None from all sources.
+pub fn delete_review_proofs(crate_name: &str, crate_version: &str, source: Option<&str>) -> anyhow::Result<()> {
+    let _proof_write = lock(&PROOF_WRITE);
+    delete_review_proofs_locked(crate_name, crate_version, source)
+}
+
+/// the caller holds PROOF_WRITE
+fn delete_review_proofs_locked(crate_name: &str, crate_version: &str, source: Option<&str>) -> anyhow::Result<()> {
+    let content = crate::proof_store_mod::read_my_proof_store()?;
+    let mut vec_path: Vec<&str> = content.proofs.iter().map(|x| x.file_path.as_str()).collect();
+    vec_path.dedup();
+    for path in vec_path.into_iter() {
+        // I will remove only if it has the review segment.
+        let mut vec_range: Vec<Range<usize>> = content
+            .proofs
+            .iter()
+            .filter(|x| x.file_path == path)
+            .filter(|x| x.proof_crev.review.is_some() && x.proof_crev.package.name == crate_name && x.proof_crev.package.version == crate_version)
+            .filter(|x| source.map_or(true, |source| same_source(&x.proof_crev.package.source, source)))
+            .map(|x| x.range.clone())
+            .collect();
         if !vec_range.is_empty() {
+            let mut file_content = std::fs::read_to_string(path)?;
             // remove all ranges from the bottom up
+            vec_range.sort_by_key(|x| x.start);
             while let Some(range) = vec_range.pop() {
                 file_content.drain(range);
             }
             if file_content.trim().is_empty() {
                 // delete the file
-                unwrap!(std::fs::remove_file(path));
+                std::fs::remove_file(path)?;
             } else {
                 std::fs::write(path, file_content)?;
             }
@@ -475,50 +597,159 @@ pub fn delete_review_proofs(crate_name: &str, crate_version: &str) -> anyhow::Re
     Ok(())
 }
 
+/// the empty source is crates.io
+fn same_source(a: &str, b: &str) -> bool {
+    use crate::package_source_mod::is_crates_io;
+    (is_crates_io(a) && is_crates_io(b)) || a == b
+}
+
+/// the source of path packages before PATH_SOURCE
+fn is_local_folder_source(source: &str) -> bool {
+    source.starts_with("file://") && source != crate::package_source_mod::PATH_SOURCE
+}
+
+/// publish my proofs with `cargo crev publish`
 pub fn crev_publish() -> anyhow::Result<String> {
-    let output = std::process::Command::new("cargo").arg("crev").arg("publish").output()?;
-    let ret_val = format!("{} {}", String::from_utf8(output.stdout)?, String::from_utf8(output.stderr)?);
-    Ok(ret_val)
+    // git pull inside cargo crev publish rewrites the proof files
+    let _proof_write = lock(&PROOF_WRITE);
+    // older reviews of path packages have the local folder as source, it must not be published
+    let content = crate::proof_store_mod::read_my_proof_store()?;
+    if let Some(x) = content.proofs.iter().find(|x| x.proof_crev.review.is_some() && is_local_folder_source(&x.proof_crev.package.source)) {
+        anyhow::bail!(
+            "The review of {} {} has the local folder {} as source. Delete this review and review the path package again before publishing.",
+            x.proof_crev.package.name,
+            x.proof_crev.package.version,
+            x.proof_crev.package.source
+        );
+    }
+    let output = std::process::Command::new("cargo")
+        .arg("crev")
+        .arg("publish")
+        .output()
+        .context("Cannot run `cargo crev publish`. Install cargo-crev to publish the reviews.")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        anyhow::bail!("cargo crev publish failed: {}", stderr.trim());
+    }
+    Ok(format!("{} {}", stdout, stderr))
 }
 
+/// verify all crates.io dependencies of the project in-process with the crev proof db and my trust set
+/// The dependencies come from `cargo metadata`. The digest of the source code is calculated like `cargo crev verify` does.
 /// verify_project should return some data quickly, but in the background start to fill the db_version
 /// for all these crates. So the next time we have more complete data
 pub fn verify_project() -> anyhow::Result<VerifyListData> {
-    let output = std::process::Command::new("cargo").arg("crev").arg("verify").output().unwrap();
-    let output = format!("{} {}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    let graph = crate::dependency_graph_mod::dependency_graph_project()?;
     let vec_publisher_item = crate::db_sled_mod::db_publisher_mod::list()?;
+    let policy = crate::policy_mod::read_policy()?;
+    // cargo audit is needed only for the policy
+    let cargo_audit = match &policy {
+        Some(_) => Some(crate::cargo_audit_mod::run_cargo_audit()?),
+        None => None,
+    };
+
+    // the proof db contains all fetched proofs, the trust set is calculated from my crev id
+    let (db, trust_set) = {
+        let crev_local = lock(&CREV_LOCAL);
+        let crev_local = crev_local.as_ref().context("The crev local is not opened.")?;
+        let db = crev_local.load_db()?;
+        let my_id = crev_local.read_current_id()?;
+        let trust_set = db.calculate_trust_set(&my_id, &crev_wot::TrustDistanceParams::default());
+        (db, trust_set)
+    };
+    let requirements = crev_lib::VerificationRequirements::default();
+    let source = "https://crates.io";
 
     let mut list_of_verify = vec![];
-    for line in output.lines() {
-        if line.starts_with("none ") || line.starts_with("pass ") || line.starts_with("warn ") {
-            let s: Vec<&str> = line.split_whitespace().collect();
-            let mut status = s[0].to_string();
-            let crate_name = s[1].to_string();
-            let crate_version = s[2].to_string();
-            let crate_name_version = crate_version_join(&crate_name, &crate_version);
-
-            let published_by_url = published_by_url(&crate_name, &crate_version)?;
-            let trusted_publisher = is_trusted_publisher(&vec_publisher_item, &published_by_url);
-            if crate::db_sled_mod::db_yanked_mod::exists(&crate_name_version) {
-                status = "yanked".to_string();
+    let mut vec_node: Vec<&crate::dependency_graph_mod::DependencyNode> = graph
+        .nodes
+        .values()
+        .filter(|node| node.source_kind == crate::dependency_graph_mod::SourceKind::CratesIo)
+        .collect();
+    vec_node.sort_by(|a, b| a.id.cmp(&b.id));
+    for node in vec_node.into_iter() {
+        let crate_name = node.name.clone();
+        let crate_version = node.version.clone();
+        let crate_name_version = crate_version_join(&crate_name, &crate_version);
+        let version = crev_data::Version::parse(&crate_version)?;
+
+        // the digest of the clean source code
+        let (status, digest_check) = match calculate_crate_digest(&crate_name, &crate_version) {
+            Err(err) => {
+                log::error!("Cannot calculate digest for {}: {}", &crate_name_version, err);
+                ("none".to_string(), String::new())
             }
+            Ok(digest) => {
+                let status = crev_lib::verify_package_digest(&digest, &trust_set, &requirements, &db).to_string();
+                let mut reviewed_digests = db.get_package_reviews_for_package(source, Some(&crate_name), Some(&version)).map(|x| x.package.digest.clone());
+                let digest_check = match reviewed_digests.next() {
+                    None => String::new(),
+                    Some(first) => {
+                        if first == digest.as_slice() && reviewed_digests.all(|x| x == digest.as_slice()) {
+                            "ok".to_string()
+                        } else {
+                            "mismatch".to_string()
+                        }
+                    }
+                };
+                (status, digest_check)
+            }
+        };
+        let reviews_count = db.get_package_review_count(source, Some(&crate_name), Some(&version));
+        let reviews_count_all = db.get_package_review_count(source, Some(&crate_name), None);
+        let issues_count = db
+            .get_open_issues_for_version(source, &crate_name, &version, &trust_set, crev_data::TrustLevel::Medium)
+            .len();
+        let owners = match crate::db_sled_mod::db_crate_mod::read(&crate_name)? {
+            Some(crate_for_db) => crate_for_db.owners.join(", "),
+            None => String::new(),
+        };
 
-            let my_review = rating_or_version(&crate_name, &crate_version)?;
+        let published_by_url = published_by_url(&crate_name, &crate_version)?;
+        let trusted_publisher = is_trusted_publisher(&vec_publisher_item, &published_by_url);
+        let yanked = crate::db_sled_mod::db_yanked_mod::exists(&crate_name_version);
+
+        let my_review = rating_or_version(&crate_name, &crate_version)?;
+
+        let (policy_status, policy_rule) = match (&policy, &cargo_audit) {
+            (Some(policy), Some(cargo_audit)) => {
+                let audit_id = crate::cargo_audit_mod::get_audit_id_for_crate_version(cargo_audit, &crate_name, &crate_version);
+                let my_review = crate::policy_mod::my_review_from_cache(&crate_name, &crate_version);
+                let result = crate::policy_mod::evaluate_crate_version(
+                    policy,
+                    &crate_name,
+                    &crate_version,
+                    my_review.as_ref(),
+                    &trusted_publisher,
+                    audit_id.as_deref(),
+                );
+                (result.status(), result.rule)
+            }
+            _ => (String::new(), String::new()),
+        };
 
-            list_of_verify.push(VerifyItemData {
-                status,
-                my_review,
-                crate_name,
-                crate_version,
-                published_by_url,
-                trusted_publisher,
-            })
-        }
+        list_of_verify.push(VerifyItemData {
+            status,
+            yanked,
+            my_review,
+            crate_name,
+            crate_version,
+            published_by_url,
+            trusted_publisher,
+            policy_status,
+            policy_rule,
+            reviews_count,
+            reviews_count_all,
+            issues_count,
+            owners,
+            digest_check,
+        })
     }
     verify_sort_list_by_name_version(&mut list_of_verify);
 
     Ok(VerifyListData {
-        project_dir: env::current_dir()?.to_string_lossy().to_string(),
+        project_dir: graph.workspace_root,
         list_of_verify,
     })
 }
@@ -581,6 +812,7 @@ pub fn crev_crate_versions(crate_name: &str) -> anyhow::Result<Vec<VersionItemDa
         crate_name: crate_name.to_string(),
         crate_version: None,
         old_crate_version: None,
+        source: String::new(),
     };
     // my reviews from crev
     let vec_of_reviews = crev_list_my_reviews(&Some(new_filter))?;
@@ -604,7 +836,8 @@ pub fn crev_crate_versions(crate_name: &str) -> anyhow::Result<Vec<VersionItemDa
         // my_review
         let mut my_review = None;
         for review in vec_of_reviews.iter() {
-            if review.package.name == crate_name && review.package.version.as_str() == &io_crate_version {
+            // the versions are from crates.io
+            if review.package.name == crate_name && review.package.version.as_str() == &io_crate_version && crate::package_source_mod::is_crates_io(&review.package.source) {
                 my_review = Some(ReviewItemData {
                     crate_name: review.package.name.clone(),
                     crate_version: review.package.version.clone(),
@@ -613,6 +846,11 @@ pub fn crev_crate_versions(crate_name: &str) -> anyhow::Result<Vec<VersionItemDa
                     understanding: review.review.as_ref().context("review")?.understanding.to_string(),
                     rating: rating_to_string(&review.review.as_ref().context("review")?.rating),
                     comment_md: review.comment.as_ref().unwrap_or(&String::new()).clone(),
+                    issues: review.issues.clone(),
+                    advisories: review.advisories.clone(),
+                    diff_base_version: review.diff_base.as_ref().map(|x| x.version.clone()).unwrap_or_default(),
+                    suggested_diff_base_version: String::new(),
+                    source: String::new(),
                 });
                 break;
             }
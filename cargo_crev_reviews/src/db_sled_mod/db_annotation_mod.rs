@@ -0,0 +1,40 @@
+// db_annotation_mod.rs
+
+//! draft annotations for `file:line` ranges of the crate source code
+//! one list of annotations per crate_name_version
+//! crev_save_review renders them into the comment of the review, the draft stays for the next save
+
+#![allow(dead_code)]
+
+use lazy_static::lazy_static;
+use unwrap::unwrap;
+
+use crate::common_structs_mod::AnnotationItemData;
+
+lazy_static! {
+    static ref DB_ANNOTATION: sled::Tree = crate::db_sled_mod::DB_SLED.open_tree(b"annotations").unwrap();
+}
+
+/// insert
+pub fn insert(crate_name_version: &str, value: &Vec<AnnotationItemData>) -> anyhow::Result<()> {
+    let value = serde_json::to_vec(value)?;
+    DB_ANNOTATION.insert(crate_name_version, value)?;
+    Ok(())
+}
+
+/// None if there was never a draft for this version
+pub fn read(crate_name_version: &str) -> anyhow::Result<Option<Vec<AnnotationItemData>>> {
+    let data = DB_ANNOTATION.get(crate_name_version)?;
+    match data {
+        Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn delete(crate_name_version: &str) {
+    unwrap!(DB_ANNOTATION.remove(crate_name_version));
+}
+
+pub fn exists(crate_name_version: &str) -> bool {
+    unwrap!(DB_ANNOTATION.contains_key(crate_name_version))
+}
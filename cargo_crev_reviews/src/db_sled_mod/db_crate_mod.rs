@@ -15,6 +15,9 @@ use unwrap::unwrap;
 pub struct CrateForDb {
     pub crate_name: String,
     pub description: String,
+    /// logins of the owners on crates.io
+    #[serde(default)]
+    pub owners: Vec<String>,
 }
 
 lazy_static! {
@@ -0,0 +1,39 @@
+// db_file_class_mod.rs
+
+//! suspicious files found by the file classifier after unpacking
+//! the package of a crate version never changes, so it is classified only once
+
+#![allow(dead_code)]
+
+use lazy_static::lazy_static;
+use unwrap::unwrap;
+
+use crate::common_structs_mod::FileClassData;
+
+lazy_static! {
+    static ref DB_FILE_CLASS: sled::Tree = crate::db_sled_mod::DB_SLED.open_tree(b"file_class").unwrap();
+}
+
+/// insert
+pub fn insert(crate_name_version: &str, value: &FileClassData) -> anyhow::Result<()> {
+    let value = serde_json::to_vec(value)?;
+    DB_FILE_CLASS.insert(crate_name_version, value)?;
+    Ok(())
+}
+
+/// None if the crate version was never classified
+pub fn read(crate_name_version: &str) -> anyhow::Result<Option<FileClassData>> {
+    let data = DB_FILE_CLASS.get(crate_name_version)?;
+    match data {
+        Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn delete(crate_name_version: &str) {
+    unwrap!(DB_FILE_CLASS.remove(crate_name_version));
+}
+
+pub fn exists(crate_name_version: &str) -> bool {
+    unwrap!(DB_FILE_CLASS.contains_key(crate_name_version))
+}
@@ -0,0 +1,43 @@
+// db_job_failure_mod.rs
+
+//! the last failed background jobs
+//! the key is the job_id in big endian, so the iteration is from the oldest to the newest
+//! only the last MAX_FAILURES are kept, the older are removed on insert
+
+#![allow(dead_code)]
+
+use lazy_static::lazy_static;
+use unwrap::unwrap;
+
+use crate::common_structs_mod::JobItemData;
+
+const MAX_FAILURES: usize = 100;
+
+lazy_static! {
+    static ref DB_JOB_FAILURE: sled::Tree = crate::db_sled_mod::DB_SLED.open_tree(b"job_failures").unwrap();
+}
+
+/// insert and remove the oldest above MAX_FAILURES
+pub fn insert(value: &JobItemData) -> anyhow::Result<()> {
+    let json = serde_json::to_vec(value)?;
+    DB_JOB_FAILURE.insert(value.job_id.to_be_bytes(), json)?;
+    while DB_JOB_FAILURE.len() > MAX_FAILURES {
+        DB_JOB_FAILURE.pop_min()?;
+    }
+    Ok(())
+}
+
+/// the newest first
+pub fn list() -> anyhow::Result<Vec<JobItemData>> {
+    let mut vec = vec![];
+    for x in DB_JOB_FAILURE.iter().rev() {
+        let (_key, value) = x?;
+        let v: JobItemData = serde_json::from_slice(&value)?;
+        vec.push(v);
+    }
+    Ok(vec)
+}
+
+pub fn clear() {
+    unwrap!(DB_JOB_FAILURE.clear());
+}
@@ -46,6 +46,9 @@ pub fn get_config() -> anyhow::Result<ConfigData> {
             // defaults for config
             code_editor_path: "/usr/bin/code".to_string(),
             browser_path: "/usr/bin/xdg-open".to_string(),
+            crates_io_api_url: crate::http_client_mod::DEFAULT_API_URL.to_string(),
+            crates_io_download_url: crate::http_client_mod::DEFAULT_DOWNLOAD_URL.to_string(),
+            crates_io_sparse_url: crate::http_client_mod::DEFAULT_SPARSE_URL.to_string(),
         },
     };
     // return
@@ -0,0 +1,54 @@
+// db_read_file_mod.rs
+
+//! files of the crate version that the reviewer marked as read
+//! one list of files per crate_name_version
+//! the percent of code read suggests the thoroughness of the review
+
+#![allow(dead_code)]
+
+use lazy_static::lazy_static;
+use unwrap::unwrap;
+
+use crate::common_structs_mod::ReadFileItemData;
+
+lazy_static! {
+    static ref DB_READ_FILE: sled::Tree = crate::db_sled_mod::DB_SLED.open_tree(b"read_files").unwrap();
+}
+
+/// insert
+pub fn insert(crate_name_version: &str, value: &Vec<ReadFileItemData>) -> anyhow::Result<()> {
+    let value = serde_json::to_vec(value)?;
+    DB_READ_FILE.insert(crate_name_version, value)?;
+    Ok(())
+}
+
+/// read-modify-write in one atomic step, so two toggles at the same time don't lose one of them
+/// The function can be called more times, if another thread changed the value in between.
+pub fn update<F>(crate_name_version: &str, mut function: F) -> anyhow::Result<()>
+where
+    F: FnMut(&mut Vec<ReadFileItemData>),
+{
+    DB_READ_FILE.update_and_fetch(crate_name_version, |old| {
+        // a corrupt value is replaced with a new list
+        let mut list: Vec<ReadFileItemData> = old.and_then(|x| serde_json::from_slice(x).ok()).unwrap_or_default();
+        function(&mut list);
+        Some(unwrap!(serde_json::to_vec(&list)))
+    })?;
+    Ok(())
+}
+
+pub fn read(crate_name_version: &str) -> anyhow::Result<Option<Vec<ReadFileItemData>>> {
+    let data = DB_READ_FILE.get(crate_name_version)?;
+    match data {
+        Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn delete(crate_name_version: &str) {
+    unwrap!(DB_READ_FILE.remove(crate_name_version));
+}
+
+pub fn exists(crate_name_version: &str) -> bool {
+    unwrap!(DB_READ_FILE.contains_key(crate_name_version))
+}
@@ -0,0 +1,39 @@
+// db_risk_scan_mod.rs
+
+//! cached result of the risk scanner
+//! the source code of a crate version never changes, so the scan is done only once
+//! it scans in the background for the cargo tree
+
+#![allow(dead_code)]
+
+use lazy_static::lazy_static;
+use unwrap::unwrap;
+
+use crate::common_structs_mod::RiskScanData;
+
+lazy_static! {
+    static ref DB_RISK_SCAN: sled::Tree = crate::db_sled_mod::DB_SLED.open_tree(b"risk_scan").unwrap();
+}
+
+/// insert
+pub fn insert(crate_name_version: &str, value: &RiskScanData) -> anyhow::Result<()> {
+    let value = serde_json::to_vec(value)?;
+    DB_RISK_SCAN.insert(crate_name_version, value)?;
+    Ok(())
+}
+
+pub fn read(crate_name_version: &str) -> anyhow::Result<Option<RiskScanData>> {
+    let data = DB_RISK_SCAN.get(crate_name_version)?;
+    match data {
+        Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn delete(crate_name_version: &str) {
+    unwrap!(DB_RISK_SCAN.remove(crate_name_version));
+}
+
+pub fn exists(crate_name_version: &str) -> bool {
+    unwrap!(DB_RISK_SCAN.contains_key(crate_name_version))
+}
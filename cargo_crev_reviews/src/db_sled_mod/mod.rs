@@ -6,10 +6,15 @@
 
 #![allow(dead_code)]
 
+pub mod db_annotation_mod;
 pub mod db_crate_mod;
+pub mod db_file_class_mod;
+pub mod db_job_failure_mod;
 pub mod db_metadata_mod;
 pub mod db_publisher_mod;
+pub mod db_read_file_mod;
 pub mod db_review_mod;
+pub mod db_risk_scan_mod;
 pub mod db_verify_mod;
 pub mod db_version_mod;
 pub mod db_yanked_mod;
@@ -22,122 +27,166 @@ use crate::{db_sled_mod::db_yanked_mod::YankedForDb, utils_mod::crate_version_jo
 lazy_static! {
     /// "sled" db stays open all the time of the program running.
     /// this program on start checks if there is an instance already running, so to guarantee only one process access the db files.
-    pub static ref DB_SLED:sled::Db = unwrap!(sled::open(crate::CARGO_CREV_REVIEWS_SLED_DB.as_path()));
-    /// 3 threads to download in parallel
-    static ref POOL:rayon::ThreadPool = rayon::ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+    pub static ref DB_SLED:sled::Db = open_db_sled();
+    /// the headless commands open the db before the first access to DB_SLED, to return an error instead of a panic
+    static ref DB_SLED_HEADLESS: std::sync::Mutex<Option<sled::Db>> = std::sync::Mutex::new(None);
 }
 
-/// to avoid double or triple call to crates.io for the same crate
+/// the headless commands cannot work while the GUI holds the lock of the db files
+pub fn open_db_sled_headless() -> anyhow::Result<()> {
+    let db = sled::open(crate::CARGO_CREV_REVIEWS_SLED_DB.as_path()).map_err(|err| {
+        anyhow::anyhow!(
+            "The db {} is used by the running GUI ({}). Close the GUI or use the GUI.",
+            crate::CARGO_CREV_REVIEWS_SLED_DB.to_string_lossy(),
+            err
+        )
+    })?;
+    *unwrap!(DB_SLED_HEADLESS.lock()) = Some(db);
+    Ok(())
+}
+
+fn open_db_sled() -> sled::Db {
+    if let Some(db) = unwrap!(DB_SLED_HEADLESS.lock()).take() {
+        return db;
+    }
+    match sled::open(crate::CARGO_CREV_REVIEWS_SLED_DB.as_path()) {
+        Ok(db) => db,
+        Err(err) => panic!("Cannot open the db {}: {}", crate::CARGO_CREV_REVIEWS_SLED_DB.to_string_lossy(), err),
+    }
+}
+
+/// the job registry avoids double or triple call to crates.io for the same crate
 pub fn download_in_background_crate_versions(crate_name: String) {
-    POOL.spawn(move || {
-        let ns_started = crate::utils_mod::ns_start(&format!("download_in_background_crate_versions {}", &crate_name));
-        // cargo_crev_reviews_wasm is not on crates.io
-        match crate::crates_io_mod::crate_response(&crate_name) {
-            Err(_err) => log::info!("crate {} is not on crates.io.", &crate_name),
-            Ok(crates_io) => {
-                let c = crate::db_sled_mod::db_crate_mod::CrateForDb {
-                    crate_name: crate_name.clone(),
-                    description: crates_io.crate_segment.description.clone(),
+    let description = crate_name.clone();
+    crate::jobs_mod::spawn_job(crate::jobs_mod::DOWNLOAD_CRATE_VERSIONS, &description, move || download_crate_versions(&crate_name));
+}
+
+/// GET the crate data from crates.io and store it in db_crate, db_version and db_yanked
+/// The headless commands need the data immediately and don't wait for the background.
+pub fn download_crate_versions(crate_name: &str) -> anyhow::Result<()> {
+    // cargo_crev_reviews_wasm is not on crates.io, the other errors fail the job
+    match crate::crates_io_mod::crate_response(&crate_name)? {
+        None => log::info!("crate {} is not on crates.io.", &crate_name),
+        Some(crates_io) => {
+            let owners = match crate::crates_io_mod::owners_response(&crate_name) {
+                Ok(owners) => owners.users.into_iter().map(|x| x.login).collect(),
+                Err(_err) => vec![],
+            };
+            let c = crate::db_sled_mod::db_crate_mod::CrateForDb {
+                crate_name: crate_name.to_string(),
+                description: crates_io.crate_segment.description.clone(),
+                owners,
+            };
+            crate::db_sled_mod::db_crate_mod::insert(&crate_name, &c)?;
+
+            for crate_io_version in crates_io.versions.iter() {
+                // region: VersionForDb
+                let published_by_url = match &crate_io_version.published_by {
+                    Some(published_by) => Some(published_by.url.clone()),
+                    None => None,
                 };
-                unwrap!(crate::db_sled_mod::db_crate_mod::insert(&crate_name, &c));
-
-                for crate_io_version in crates_io.versions.iter() {
-                    // region: VersionForDb
-                    let published_by_url = match &crate_io_version.published_by {
-                        Some(published_by) => Some(published_by.url.clone()),
-                        None => None,
-                    };
-                    let crate_name_version = crate::utils_mod::crate_version_join(&crate_name, &crate_io_version.num);
-                    let v = crate::db_sled_mod::db_version_mod::VersionForDb {
-                        crate_name_version: crate_name_version.clone(),
-                        published_by_url,
-                        published_date: crate_io_version.created_at.clone(),
-                    };
-                    unwrap!(crate::db_sled_mod::db_version_mod::insert(v.crate_name_version.as_str(), &v));
-                    // region: VersionForDb
-                    // store only yanked versions
-                    // This is ok, but not enough, because yanked can change, while all other data is immutable
-                    // Therefore I need also a background_sync sometimes.
-                    if crate_io_version.yanked {
-                        unwrap!(crate::db_sled_mod::db_yanked_mod::insert(
-                            &crate_name_version,
-                            &YankedForDb {
-                                crate_name_version: crate_name_version.clone(),
-                            }
-                        ));
-                    } else {
-                        if crate::db_sled_mod::db_yanked_mod::exists(&crate_name_version) {
-                            crate::db_sled_mod::db_yanked_mod::delete(&crate_name_version);
-                        }
+                let crate_name_version = crate::utils_mod::crate_version_join(&crate_name, &crate_io_version.num);
+                let v = crate::db_sled_mod::db_version_mod::VersionForDb {
+                    crate_name_version: crate_name_version.clone(),
+                    published_by_url,
+                    published_date: crate_io_version.created_at.clone(),
+                };
+                crate::db_sled_mod::db_version_mod::insert(v.crate_name_version.as_str(), &v)?;
+                // region: VersionForDb
+                // store only yanked versions
+                // This is ok, but not enough, because yanked can change, while all other data is immutable
+                // Therefore I need also a background_sync sometimes.
+                if crate_io_version.yanked {
+                    crate::db_sled_mod::db_yanked_mod::insert(
+                        &crate_name_version,
+                        &YankedForDb {
+                            crate_name_version: crate_name_version.clone(),
+                        },
+                    )?;
+                } else {
+                    if crate::db_sled_mod::db_yanked_mod::exists(&crate_name_version) {
+                        crate::db_sled_mod::db_yanked_mod::delete(&crate_name_version);
                     }
-                    // endregion: YankedForDb
                 }
+                // endregion: YankedForDb
             }
         }
-        crate::utils_mod::ns_print_ms("download_in_background_crate_versions", ns_started);
-    });
+    }
+    Ok(())
 }
 
 /// from cargo registry read all versions that are in db_version
 /// only the yanked store in db_yanked
 pub fn sync_in_background_yanked() {
-    POOL.spawn(move || {
-        let ns_started = crate::utils_mod::ns_start("sync_in_background_yanked");
-        for x in unwrap!(crate::db_sled_mod::db_crate_mod::all_crates()).iter() {
+    crate::jobs_mod::spawn_job(crate::jobs_mod::SYNC_YANKED, "", || {
+        for x in crate::db_sled_mod::db_crate_mod::all_crates()?.iter() {
             // println!("registry crate: {}", &x.crate_name);
-            for (crate_version, yanked) in unwrap!(crate::cargo_registry_mod::info_for_one_crate(&x.crate_name)).iter() {
+            for (crate_version, yanked) in crate::cargo_registry_mod::info_for_one_crate(&x.crate_name)?.iter() {
                 let crate_name_version = crate_version_join(&x.crate_name, crate_version);
                 if crate::db_sled_mod::db_yanked_mod::exists(&crate_name_version) && *yanked == false {
                     crate::db_sled_mod::db_yanked_mod::delete(&crate_name_version);
                 } else if !crate::db_sled_mod::db_yanked_mod::exists(&crate_name_version) && *yanked == true {
-                    unwrap!(crate::db_sled_mod::db_yanked_mod::insert(
+                    crate::db_sled_mod::db_yanked_mod::insert(
                         &crate_name_version,
                         &YankedForDb {
                             crate_name_version: crate_name_version.clone(),
-                        }
-                    ));
+                        },
+                    )?;
                 }
             }
         }
-        crate::utils_mod::ns_print_ms("sync_in_background_yanked", ns_started);
+        Ok(())
     });
 }
 
 /// working with crev data looks slow.
 /// I will sync in background with fast sled database and work from there.
 pub fn sync_in_background_reviews() {
-    POOL.spawn(move || {
-        let ns_started = crate::utils_mod::ns_start("sync_in_background_reviews");
-        let vec = unwrap!(crate::crev_mod::crev_list_my_reviews(&None));
+    crate::jobs_mod::spawn_job(crate::jobs_mod::SYNC_REVIEWS, "", || {
+        let vec = crate::crev_mod::crev_list_my_reviews(&None)?;
         for x in vec.iter() {
             let item = crate::utils_mod::from_crev_to_item(x);
             let crate_name_version = &crate_version_join(&item.crate_name, &item.crate_version);
-            if crate::db_sled_mod::db_review_mod::exists(crate_name_version) {
-                // check the date
-                let y = unwrap!(unwrap!(crate::db_sled_mod::db_review_mod::read(crate_name_version)));
-                if y.date != item.date {
-                    unwrap!(crate::db_sled_mod::db_review_mod::insert(crate_name_version, &item));
-                }
-            } else {
-                unwrap!(crate::db_sled_mod::db_review_mod::insert(crate_name_version, &item));
+            // check the date
+            match crate::db_sled_mod::db_review_mod::read(crate_name_version)? {
+                Some(y) if y.date == item.date => {}
+                _ => crate::db_sled_mod::db_review_mod::insert(crate_name_version, &item)?,
             }
         }
-        crate::utils_mod::ns_print_ms("sync_in_background_reviews", ns_started);
+        Ok(())
     });
 }
 
 /// working with crev verify looks slow.
 /// I will sync in background with fast sled database and work from there.
 pub fn sync_in_background_verify() {
-    POOL.spawn(move || {
-        let ns_started = crate::utils_mod::ns_start("sync_in_background_verify");
-        let verify_list_data = unwrap!(crate::crev_mod::verify_project());
+    crate::jobs_mod::spawn_job(crate::jobs_mod::SYNC_VERIFY, "", || {
+        let verify_list_data = crate::crev_mod::verify_project()?;
         for item in verify_list_data.list_of_verify.iter() {
             let crate_name_version = &crate_version_join(&item.crate_name, &item.crate_version);
             // always insert/update
-            unwrap!(crate::db_sled_mod::db_verify_mod::insert(crate_name_version, &item));
+            crate::db_sled_mod::db_verify_mod::insert(crate_name_version, &item)?;
         }
-        crate::utils_mod::ns_print_ms("sync_in_background_verify", ns_started);
+        Ok(())
+    });
+}
+
+/// the risk scan and the file classification can need to download and unpack the crate, so they run in the background
+/// Both read the same unpacked folder while the crate version is locked, a half-unpacked folder must never be cached.
+pub fn scan_in_background_crate(crate_name: String, crate_version: String) {
+    let description = crate_version_join(&crate_name, &crate_version);
+    crate::jobs_mod::spawn_job(crate::jobs_mod::SCAN_CRATE, &description, move || {
+        crate::crev_mod::with_clean_src_folder(&crate_name, &crate_version, |src_folder| {
+            let crate_name_version = crate_version_join(&crate_name, &crate_version);
+            if !db_risk_scan_mod::exists(&crate_name_version) {
+                crate::risk_scan_mod::scan_and_store(&crate_name, &crate_version, src_folder)?;
+            }
+            // the files are classified only here, the result stays in the db
+            if !db_file_class_mod::exists(&crate_name_version) {
+                crate::file_class_mod::classify_and_store(&crate_name, &crate_version, src_folder)?;
+            }
+            Ok(())
+        })
     });
 }
 
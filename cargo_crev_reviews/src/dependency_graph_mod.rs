@@ -0,0 +1,352 @@
+// dependency_graph_mod.rs
+
+//! dependency graph of the project from `cargo metadata --format-version 1`
+//! The text output of `cargo tree` is meant for humans and it is hard to parse.
+//! `cargo metadata` has the packages with the source id and the resolved nodes with edges, dependency kinds and features.
+//! The lines of the cargo tree page are rendered from this graph.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// region: structs for deserializing cargo metadata
+
+#[derive(Deserialize, Debug)]
+pub struct CargoMetadata {
+    pub packages: Vec<MetadataPackage>,
+    pub workspace_members: Vec<String>,
+    pub resolve: Option<MetadataResolve>,
+    pub workspace_root: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MetadataPackage {
+    pub name: String,
+    pub version: String,
+    pub id: String,
+    pub description: Option<String>,
+    /// None for path dependencies and workspace members
+    pub source: Option<String>,
+    /// the Cargo.toml of the package in the registry src, git checkout or path folder
+    #[serde(default)]
+    pub manifest_path: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MetadataResolve {
+    pub nodes: Vec<MetadataNode>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MetadataNode {
+    pub id: String,
+    #[serde(default)]
+    pub deps: Vec<MetadataNodeDep>,
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MetadataNodeDep {
+    pub pkg: String,
+    #[serde(default)]
+    pub dep_kinds: Vec<MetadataDepKind>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MetadataDepKind {
+    /// null for normal, "dev" or "build"
+    pub kind: Option<String>,
+}
+
+// endregion: structs for deserializing cargo metadata
+
+// region: dependency graph
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DependencyKind {
+    Normal,
+    Build,
+    Dev,
+}
+
+impl DependencyKind {
+    fn from_metadata(kind: &Option<String>) -> Self {
+        match kind.as_deref() {
+            Some("build") => DependencyKind::Build,
+            Some("dev") => DependencyKind::Dev,
+            _ => DependencyKind::Normal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    CratesIo,
+    Registry,
+    Git,
+    Path,
+}
+
+impl SourceKind {
+    /// the source id from cargo: registry+url, sparse+url, git+url or None for path
+    pub fn from_source_id(source: Option<&str>) -> Self {
+        match source {
+            None => SourceKind::Path,
+            Some("registry+https://github.com/rust-lang/crates.io-index") | Some("sparse+https://index.crates.io/") => SourceKind::CratesIo,
+            Some(source) if source.starts_with("git+") => SourceKind::Git,
+            Some(source) if source.starts_with("path+") => SourceKind::Path,
+            Some(_) => SourceKind::Registry,
+        }
+    }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SourceKind::CratesIo => "crates_io",
+            SourceKind::Registry => "registry",
+            SourceKind::Git => "git",
+            SourceKind::Path => "path",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DependencyNode {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub source: Option<String>,
+    pub source_kind: SourceKind,
+    pub manifest_path: String,
+    /// enabled features after the resolve
+    pub features: Vec<String>,
+    pub edges: Vec<DependencyEdge>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DependencyEdge {
+    /// package id of the dependency
+    pub to: String,
+    pub kinds: Vec<DependencyKind>,
+}
+
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    pub workspace_root: String,
+    /// package ids of the workspace members
+    pub roots: Vec<String>,
+    pub nodes: HashMap<String, DependencyNode>,
+}
+
+/// one rendered line of the tree. Section lines like `[dev-dependencies]` don't have a node.
+#[derive(Debug, Clone)]
+pub struct DependencyTreeLine {
+    pub text: String,
+    pub node_id: Option<String>,
+}
+
+// endregion: dependency graph
+
+/// run `cargo metadata` in the current directory and build the graph
+/// Only the dependencies for the host platform are used, like `cargo tree` does.
+pub fn dependency_graph_project() -> anyhow::Result<DependencyGraph> {
+    let mut command = std::process::Command::new("cargo");
+    command.arg("metadata").arg("--format-version").arg("1");
+    if let Some(host) = host_target() {
+        command.arg("--filter-platform").arg(host);
+    }
+    let output = command.output()?;
+    if !output.status.success() {
+        anyhow::bail!("cargo metadata failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    dependency_graph_from_json(&String::from_utf8(output.stdout)?)
+}
+
+/// the host target triple from `rustc -vV`
+fn host_target() -> Option<String> {
+    let output = std::process::Command::new("rustc").arg("-vV").output().ok()?;
+    let output = String::from_utf8(output.stdout).ok()?;
+    output.lines().find_map(|line| line.strip_prefix("host: ").map(|host| host.trim().to_string()))
+}
+
+pub fn dependency_graph_from_json(json: &str) -> anyhow::Result<DependencyGraph> {
+    let metadata: CargoMetadata = serde_json::from_str(json)?;
+    let resolve = match metadata.resolve {
+        Some(resolve) => resolve,
+        None => anyhow::bail!("cargo metadata has no resolve segment."),
+    };
+    let mut nodes = HashMap::new();
+    for package in metadata.packages.into_iter() {
+        nodes.insert(
+            package.id.clone(),
+            DependencyNode {
+                source_kind: SourceKind::from_source_id(package.source.as_deref()),
+                id: package.id,
+                name: package.name,
+                version: package.version,
+                description: package.description,
+                source: package.source,
+                manifest_path: package.manifest_path,
+                features: vec![],
+                edges: vec![],
+            },
+        );
+    }
+    for resolve_node in resolve.nodes.into_iter() {
+        if let Some(node) = nodes.get_mut(&resolve_node.id) {
+            node.features = resolve_node.features;
+            node.edges = resolve_node
+                .deps
+                .into_iter()
+                .map(|dep| DependencyEdge {
+                    to: dep.pkg,
+                    kinds: dep.dep_kinds.iter().map(|x| DependencyKind::from_metadata(&x.kind)).collect(),
+                })
+                .collect();
+        }
+    }
+    Ok(DependencyGraph {
+        workspace_root: metadata.workspace_root,
+        roots: metadata.workspace_members,
+        nodes,
+    })
+}
+
+impl DependencyGraph {
+    /// render the lines like `cargo tree` does:
+    /// normal dependencies first, then build and dev dependencies in their sections.
+    /// A node that was already expanded is marked with (*)
+    pub fn render_tree_lines(&self) -> Vec<DependencyTreeLine> {
+        let mut lines = vec![];
+        let mut expanded = std::collections::HashSet::new();
+        for (i, root) in self.roots.iter().enumerate() {
+            if i > 0 {
+                lines.push(DependencyTreeLine {
+                    text: String::new(),
+                    node_id: None,
+                });
+            }
+            if let Some(node) = self.nodes.get(root) {
+                lines.push(DependencyTreeLine {
+                    text: self.node_text(node),
+                    node_id: Some(node.id.clone()),
+                });
+                expanded.insert(node.id.clone());
+                self.render_children(node, "", &mut expanded, &mut lines);
+            }
+        }
+        lines
+    }
+
+    fn render_children(&self, node: &DependencyNode, prefix: &str, expanded: &mut std::collections::HashSet<String>, lines: &mut Vec<DependencyTreeLine>) {
+        for kind in [DependencyKind::Normal, DependencyKind::Build, DependencyKind::Dev] {
+            let mut children: Vec<&DependencyNode> = node
+                .edges
+                .iter()
+                .filter(|edge| edge.kinds.contains(&kind))
+                .filter_map(|edge| self.nodes.get(&edge.to))
+                .collect();
+            if children.is_empty() {
+                continue;
+            }
+            // sort like cargo tree: by name and then by version
+            children.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| compare_versions(&a.version, &b.version)));
+            match kind {
+                DependencyKind::Normal => {}
+                DependencyKind::Build => lines.push(DependencyTreeLine {
+                    text: format!("{}[build-dependencies]", prefix),
+                    node_id: None,
+                }),
+                DependencyKind::Dev => lines.push(DependencyTreeLine {
+                    text: format!("{}[dev-dependencies]", prefix),
+                    node_id: None,
+                }),
+            }
+            let count = children.len();
+            for (i, child) in children.into_iter().enumerate() {
+                let is_last = i + 1 == count;
+                let branch = if is_last { "└── " } else { "├── " };
+                let already_expanded = expanded.contains(&child.id);
+                let has_children = !child.edges.is_empty();
+                let mut text = format!("{}{}{}", prefix, branch, self.node_text(child));
+                if already_expanded && has_children {
+                    text.push_str(" (*)");
+                }
+                lines.push(DependencyTreeLine {
+                    text,
+                    node_id: Some(child.id.clone()),
+                });
+                if !already_expanded {
+                    expanded.insert(child.id.clone());
+                    let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+                    self.render_children(child, &child_prefix, expanded, lines);
+                }
+            }
+        }
+    }
+
+    /// name, version and the source if it is not crates.io
+    fn node_text(&self, node: &DependencyNode) -> String {
+        match node.source_kind {
+            SourceKind::CratesIo => format!("{} v{}", node.name, node.version),
+            SourceKind::Path => {
+                // the package id has 2 formats: `name version (path+file:///dir)` and `path+file:///dir#name@version`
+                let path = std::path::Path::new(&node.manifest_path)
+                    .parent()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                format!("{} v{} ({})", node.name, node.version, path)
+            }
+            SourceKind::Git | SourceKind::Registry => {
+                let source = node.source.as_deref().unwrap_or("");
+                let source = source.split_once('+').map(|(_, url)| url).unwrap_or(source);
+                format!("{} v{} ({})", node.name, node.version, source)
+            }
+        }
+    }
+}
+
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    match (semver::Version::parse(a), semver::Version::parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependency_graph_from_json() {
+        let sample_text = std::fs::read_to_string("samples/cargo_metadata.json").unwrap();
+        let graph = dependency_graph_from_json(&sample_text).unwrap();
+        let root = &graph.nodes[&graph.roots[0]];
+        assert_eq!(root.source_kind, SourceKind::Path);
+        assert_eq!(root.edges.len(), 5);
+
+        let lines: Vec<String> = graph.render_tree_lines().into_iter().map(|x| x.text).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "demo v0.1.0 (/home/rustdevuser/rustprojects/demo)",
+                "├── Inflector v0.11.4",
+                "│   └── semver-parser v0.10.0-alpha.1+build.5",
+                "├── anyhow v1.0.57",
+                "└── unwrap v1.2.1 (https://github.com/canndrew/unwrap?rev=1a2b3c4#1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b)",
+                "    └── anyhow v1.0.57",
+                "[build-dependencies]",
+                "└── cc v1.0.73",
+                "[dev-dependencies]",
+                "└── tempfile v3.3.0",
+            ]
+        );
+
+        // the package id format since cargo 1.77
+        let sample_text = sample_text.replace(
+            "demo 0.1.0 (path+file:///home/rustdevuser/rustprojects/demo)",
+            "path+file:///home/rustdevuser/rustprojects/demo#0.1.0",
+        );
+        let graph = dependency_graph_from_json(&sample_text).unwrap();
+        assert_eq!(graph.render_tree_lines()[0].text, "demo v0.1.0 (/home/rustdevuser/rustprojects/demo)");
+    }
+}
@@ -0,0 +1,159 @@
+// file_class_mod.rs
+
+//! classify every file of the crate package in the background scan of the crate version
+//! Binary blobs in crates are a known supply-chain vector: the source code looks clean, but the build uses a precompiled file.
+//! Flag precompiled binaries (by header and extension), archives, large opaque data files and minified or obfuscated sources.
+//! The findings are stored in sled per crate version and the cargo tree shows a warning badge.
+
+use crate::common_structs_mod::*;
+
+/// binary data files bigger than this are opaque for the reviewer
+const LARGE_DATA_SIZE: u64 = 100 * 1024;
+/// source lines longer than this are typical for minified or generated code
+const MAX_SOURCE_LINE_LEN: usize = 1_000;
+/// a string literal of base64 or hex characters longer than this can hide anything
+const MIN_ENCODED_BLOB_LEN: usize = 500;
+
+/// extensions of precompiled libraries and executables
+const PRECOMPILED_EXTENSIONS: [&str; 11] = ["so", "a", "dll", "dylib", "lib", "exe", "o", "obj", "rlib", "wasm", "pdb"];
+/// extensions of text sources that are checked for minified code
+const SOURCE_EXTENSIONS: [&str; 12] = ["rs", "c", "h", "cc", "cpp", "hpp", "js", "mjs", "ts", "py", "css", "S"];
+
+/// classify the unpacked crate and store the findings, also an empty list, so it is not classified again
+pub fn classify_and_store(crate_name: &str, crate_version: &str, src_folder: &std::path::Path) -> anyhow::Result<FileClassData> {
+    let data = FileClassData {
+        crate_name: crate_name.to_string(),
+        crate_version: crate_version.to_string(),
+        list_of_finding: classify_folder(src_folder)?,
+    };
+    let crate_name_version = crate::utils_mod::crate_version_join(crate_name, crate_version);
+    crate::db_sled_mod::db_file_class_mod::insert(&crate_name_version, &data)?;
+    Ok(data)
+}
+
+/// the warning for the badge in the cargo tree, one line per suspicious file
+pub fn file_warning(data: &FileClassData) -> Option<String> {
+    if data.list_of_finding.is_empty() {
+        None
+    } else {
+        Some(data.list_of_finding.iter().map(|x| format!("{}: {} {}", x.path, x.kind, x.detail)).collect::<Vec<String>>().join("\n"))
+    }
+}
+
+/// all suspicious files in the folder
+pub fn classify_folder(src_folder: &std::path::Path) -> anyhow::Result<Vec<FileFindingItemData>> {
+    let mut list_of_finding = vec![];
+    for entry in walkdir::WalkDir::new(src_folder).min_depth(1).sort_by_file_name() {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path().strip_prefix(src_folder)?.to_string_lossy().replace('\\', "/");
+        if path == ".cargo-ok" {
+            continue;
+        }
+        let content = std::fs::read(entry.path())?;
+        if let Some((kind, detail)) = classify_file(&path, &content) {
+            list_of_finding.push(FileFindingItemData {
+                path,
+                kind: kind.to_string(),
+                size: content.len() as u64,
+                detail,
+            });
+        }
+    }
+    Ok(list_of_finding)
+}
+
+/// None for normal files, otherwise the kind and a short explanation
+pub fn classify_file(path: &str, content: &[u8]) -> Option<(&'static str, String)> {
+    if let Some(format) = binary_format(content) {
+        return Some(("precompiled", format!("{} header", format)));
+    }
+    let extension = std::path::Path::new(path).extension().map(|x| x.to_string_lossy().to_string()).unwrap_or_default();
+    if PRECOMPILED_EXTENSIONS.contains(&extension.as_str()) {
+        return Some(("precompiled", format!(".{} file", extension)));
+    }
+    if let Some(format) = archive_format(content) {
+        return Some(("archive", format!("{} data", format)));
+    }
+    let text = match std::str::from_utf8(content) {
+        Ok(text) if !text.contains('\0') => text,
+        _ => {
+            if content.len() as u64 > LARGE_DATA_SIZE {
+                return Some(("opaque_data", format!("{} KB of binary data", content.len() / 1024)));
+            }
+            return None;
+        }
+    };
+    if SOURCE_EXTENSIONS.contains(&extension.as_str()) {
+        let max_line_len = text.lines().map(|x| x.len()).max().unwrap_or(0);
+        if max_line_len > MAX_SOURCE_LINE_LEN {
+            return Some(("minified", format!("line of {} chars", max_line_len)));
+        }
+        let blob_len = longest_encoded_run(text);
+        if blob_len >= MIN_ENCODED_BLOB_LEN {
+            return Some(("obfuscated", format!("encoded string of {} chars", blob_len)));
+        }
+    }
+    None
+}
+
+/// magic bytes of executables and object files
+fn binary_format(content: &[u8]) -> Option<&'static str> {
+    match content {
+        [0x7f, b'E', b'L', b'F', ..] => Some("ELF"),
+        [b'M', b'Z', ..] if content.len() > 0x40 => Some("PE"),
+        [0xfe, 0xed, 0xfa, 0xce | 0xcf, ..] | [0xce | 0xcf, 0xfa, 0xed, 0xfe, ..] => Some("Mach-O"),
+        [0xca, 0xfe, 0xba, 0xbe, ..] => Some("Mach-O fat or java class"),
+        [0x00, b'a', b's', b'm', ..] => Some("WebAssembly"),
+        _ if content.starts_with(b"!<arch>\n") => Some("static library"),
+        _ => None,
+    }
+}
+
+/// magic bytes of compressed archives
+fn archive_format(content: &[u8]) -> Option<&'static str> {
+    match content {
+        [b'P', b'K', 0x03, 0x04, ..] => Some("zip"),
+        [0x1f, 0x8b, ..] => Some("gzip"),
+        [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => Some("xz"),
+        [b'7', b'z', 0xbc, 0xaf, 0x27, 0x1c, ..] => Some("7z"),
+        [b'B', b'Z', b'h', ..] => Some("bzip2"),
+        [0x28, 0xb5, 0x2f, 0xfd, ..] => Some("zstd"),
+        _ => None,
+    }
+}
+
+/// the longest run of base64 characters, also hex strings are inside this alphabet
+fn longest_encoded_run(text: &str) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() || c == '+' || c == '/' || c == '=' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_file() {
+        assert_eq!(classify_file("src/lib.rs", b"fn main() {}\n"), None);
+        assert_eq!(classify_file("lib/x.bin", b"\x7fELF\x02\x01\x01").unwrap().0, "precompiled");
+        assert_eq!(classify_file("lib/x.dll", b"short").unwrap().0, "precompiled");
+        assert_eq!(classify_file("x.tar.gz", b"\x1f\x8b\x08\x00").unwrap().0, "archive");
+        assert_eq!(classify_file("logo.png", &[0x89, b'P', b'N', b'G', 0]), None);
+        assert_eq!(classify_file("data.bin", &vec![0u8; 200 * 1024]).unwrap().0, "opaque_data");
+        assert_eq!(classify_file("src/min.js", "a;".repeat(1000).as_bytes()).unwrap().0, "minified");
+        let blob = format!("const X: &str = \"{}\";\n", "QUJD".repeat(200));
+        assert_eq!(classify_file("src/lib.rs", blob.as_bytes()).unwrap().0, "obfuscated");
+    }
+}
@@ -0,0 +1,128 @@
+// http_client_mod.rs
+
+//! one http client for crates.io, its mirrors and the sparse index
+//! The API base url, the download url template and the sparse index url are in ConfigData, so a corporate mirror
+//! or a local fake server can stand in for crates.io.
+//! Connection errors, timeouts, 429 and 5xx responses are retried with exponential backoff.
+
+use anyhow::Context;
+use lazy_static::lazy_static;
+
+pub const DEFAULT_API_URL: &str = "https://crates.io/api/v1";
+pub const DEFAULT_DOWNLOAD_URL: &str = "https://crates.io/api/v1/crates/{crate}/{version}/download";
+pub const DEFAULT_SPARSE_URL: &str = "https://index.crates.io";
+/// the unreserved characters of RFC 3986 stay as they are, crate names have `-` and `_`, versions have `.`
+const URL_SEGMENT: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+/// crates.io asks for a user agent that identifies the application
+const USER_AGENT: &str = "cargo_crev_reviews (github.com/bestia-dev/cargo_crev_reviews_workspace)";
+const CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+const MAX_ATTEMPTS: u32 = 4;
+const FIRST_BACKOFF: std::time::Duration = std::time::Duration::from_millis(500);
+
+lazy_static! {
+    /// the client keeps the connections alive between requests
+    static ref CLIENT: reqwest::blocking::Client = reqwest::blocking::Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(TIMEOUT)
+        .build()
+        .unwrap();
+}
+
+/// the API base url from config without the trailing slash
+pub fn api_url() -> String {
+    let config = crate::db_sled_mod::db_metadata_mod::get_config().unwrap_or_default();
+    let api_url = if config.crates_io_api_url.trim().is_empty() { DEFAULT_API_URL } else { config.crates_io_api_url.trim() };
+    api_url.trim_end_matches('/').to_string()
+}
+
+/// the sparse index url from config without the trailing slash
+pub fn sparse_url() -> String {
+    let config = crate::db_sled_mod::db_metadata_mod::get_config().unwrap_or_default();
+    let sparse_url = if config.crates_io_sparse_url.trim().is_empty() { DEFAULT_SPARSE_URL } else { config.crates_io_sparse_url.trim() };
+    sparse_url.trim_end_matches('/').to_string()
+}
+
+/// the download url for the `.crate` file from the template in config
+pub fn download_url(crate_name: &str, crate_version: &str) -> String {
+    let config = crate::db_sled_mod::db_metadata_mod::get_config().unwrap_or_default();
+    let template = if config.crates_io_download_url.trim().is_empty() { DEFAULT_DOWNLOAD_URL } else { config.crates_io_download_url.trim() };
+    download_url_from_template(template, crate_name, crate_version)
+}
+
+fn download_url_from_template(template: &str, crate_name: &str, crate_version: &str) -> String {
+    template
+        .replace("{crate}", &percent_encoding::utf8_percent_encode(crate_name, URL_SEGMENT).to_string())
+        .replace("{version}", &percent_encoding::utf8_percent_encode(crate_version, URL_SEGMENT).to_string())
+}
+
+/// GET with retries, a success or an error with a clear message
+pub fn get(url: &str) -> anyhow::Result<reqwest::blocking::Response> {
+    get_if_found(url)?.with_context(|| format!("The endpoint {} returned {}.", url, reqwest::StatusCode::NOT_FOUND))
+}
+
+/// GET with retries, None only for 404, all the other failures are errors
+pub fn get_if_found(url: &str) -> anyhow::Result<Option<reqwest::blocking::Response>> {
+    let response = send_with_retry(url)?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        anyhow::bail!("The endpoint {} returned {}.", url, response.status());
+    }
+    Ok(Some(response))
+}
+
+/// GET with retries, returns also the responses like 404 that are not worth to retry
+pub fn send_with_retry(url: &str) -> anyhow::Result<reqwest::blocking::Response> {
+    let mut backoff = FIRST_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        log::debug!("get url: {} attempt {}", url, attempt);
+        let last_error = match CLIENT.get(url).send() {
+            Ok(response) if !is_retryable(response.status()) => return Ok(response),
+            Ok(response) => format!("returned {}", response.status()),
+            Err(err) if err.is_timeout() => "timed out".to_string(),
+            Err(err) if err.is_connect() => "is unreachable".to_string(),
+            Err(err) => anyhow::bail!("The request to {} failed: {}", url, err),
+        };
+        if attempt == MAX_ATTEMPTS {
+            anyhow::bail!(
+                "The endpoint {} {} after {} attempts. Check the network and the crates.io urls in Config.",
+                url,
+                last_error,
+                MAX_ATTEMPTS
+            );
+        }
+        log::warn!("{} {}, retry in {} ms", url, last_error, backoff.as_millis());
+        std::thread::sleep(backoff);
+        backoff *= 2;
+        attempt += 1;
+    }
+}
+
+/// too many requests and server errors can pass
+fn is_retryable(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_download_url_from_template() {
+        assert_eq!(
+            download_url_from_template(DEFAULT_DOWNLOAD_URL, "block-cipher-trait", "0.6.0"),
+            "https://crates.io/api/v1/crates/block-cipher-trait/0.6.0/download"
+        );
+        assert_eq!(
+            download_url_from_template("http://127.0.0.1:9000/files/{crate}-{version}.crate", "unwrap", "1.2.1"),
+            "http://127.0.0.1:9000/files/unwrap-1.2.1.crate"
+        );
+        assert_eq!(download_url_from_template("{crate}/{version}", "a_b", "1.0.0+build/x"), "a_b/1.0.0%2Bbuild%2Fx");
+        assert!(is_retryable(reqwest::StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_retryable(reqwest::StatusCode::NOT_FOUND));
+    }
+}
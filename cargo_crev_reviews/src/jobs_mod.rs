@@ -0,0 +1,201 @@
+// jobs_mod.rs
+
+//! background jobs on a small thread pool with a registry of their state
+//! Every job is registered with its kind, times, state and error, so the jobs page shows why some data is missing.
+//! A queued job can be cancelled before it starts. The failures are persisted in db_sled.
+
+use chrono::prelude::*;
+use lazy_static::lazy_static;
+use std::sync::Mutex;
+
+use crate::common_structs_mod::JobItemData;
+
+pub const DOWNLOAD_CRATE_VERSIONS: &str = "download_crate_versions";
+pub const SYNC_REVIEWS: &str = "sync_reviews";
+pub const SYNC_YANKED: &str = "sync_yanked";
+pub const SYNC_VERIFY: &str = "sync_verify";
+pub const SCAN_CRATE: &str = "scan_crate";
+
+const QUEUED: &str = "queued";
+const RUNNING: &str = "running";
+const FINISHED: &str = "finished";
+const FAILED: &str = "failed";
+const CANCELLED: &str = "cancelled";
+
+/// the oldest ended jobs are removed from the registry above this count
+const MAX_JOBS: usize = 500;
+
+lazy_static! {
+    /// 3 threads to download in parallel
+    static ref POOL: rayon::ThreadPool = rayon::ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+    /// the jobs of this run in the order of queuing
+    static ref JOBS: Mutex<Vec<JobItemData>> = Mutex::new(vec![]);
+}
+
+/// register the job and queue it on the thread pool
+/// The same job that is already queued or running is not queued again. This avoids double calls to crates.io for the same crate.
+pub fn spawn_job<F>(kind: &str, description: &str, job: F) -> u64
+where
+    F: FnOnce() -> anyhow::Result<()> + Send + 'static,
+{
+    let mut jobs = JOBS.lock().unwrap();
+    if let Some(x) = jobs.iter().find(|x| x.kind == kind && x.description == description && (x.state == QUEUED || x.state == RUNNING)) {
+        return x.job_id;
+    }
+    // the sled id is unique also across runs, so it is a good key for the persisted failures
+    let job_id = crate::db_sled_mod::DB_SLED.generate_id().unwrap_or_else(|_| jobs.last().map(|x| x.job_id + 1).unwrap_or(0));
+    push_job(
+        &mut jobs,
+        JobItemData {
+            job_id,
+            kind: kind.to_string(),
+            description: description.to_string(),
+            state: QUEUED.to_string(),
+            queued_at: now(),
+            ..Default::default()
+        },
+    );
+    drop(jobs);
+    POOL.spawn(move || run_job(job_id, job));
+    job_id
+}
+
+/// only a queued job can be cancelled, the running job cannot be interrupted
+pub fn cancel_job(job_id: u64) -> anyhow::Result<()> {
+    cancel(&mut JOBS.lock().unwrap(), job_id, &now())
+}
+
+/// the newest first
+pub fn list_jobs() -> Vec<JobItemData> {
+    JOBS.lock().unwrap().iter().rev().cloned().collect()
+}
+
+fn run_job<F>(job_id: u64, job: F)
+where
+    F: FnOnce() -> anyhow::Result<()>,
+{
+    if !start(&mut JOBS.lock().unwrap(), job_id, &now()) {
+        // cancelled while queued
+        return;
+    }
+    let ns_started = crate::utils_mod::ns_start("");
+    // a panic inside the job is recorded as its error and does not kill the worker
+    let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(job)) {
+        Ok(result) => result.map_err(|err| format!("{:#}", err)),
+        Err(panic) => Err(panic_message(panic.as_ref())),
+    };
+    let ended = end(&mut JOBS.lock().unwrap(), job_id, result, &now());
+    if let Some(item) = ended {
+        crate::utils_mod::ns_print_ms(&format!("{} {}", &item.kind, &item.description), ns_started);
+        if item.state == FAILED {
+            log::error!("job {} {} failed: {}", &item.kind, &item.description, &item.error);
+            if let Err(err) = crate::db_sled_mod::db_job_failure_mod::insert(&item) {
+                log::error!("cannot persist the job failure: {}", err);
+            }
+        }
+    }
+}
+
+fn now() -> String {
+    Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// unwrap! panics with a String, panic!("literal") with a &str
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    match (panic.downcast_ref::<String>(), panic.downcast_ref::<&str>()) {
+        (Some(x), _) => format!("panic: {}", x),
+        (None, Some(x)) => format!("panic: {}", x),
+        (None, None) => "panic".to_string(),
+    }
+}
+
+/// add the job and remove the oldest ended jobs above MAX_JOBS
+fn push_job(jobs: &mut Vec<JobItemData>, item: JobItemData) {
+    jobs.push(item);
+    while jobs.len() > MAX_JOBS {
+        match jobs.iter().position(|x| x.state != QUEUED && x.state != RUNNING) {
+            Some(pos) => {
+                jobs.remove(pos);
+            }
+            None => break,
+        }
+    }
+}
+
+/// from queued to running, false if the job was cancelled
+fn start(jobs: &mut [JobItemData], job_id: u64, now: &str) -> bool {
+    match jobs.iter_mut().find(|x| x.job_id == job_id && x.state == QUEUED) {
+        Some(x) => {
+            x.state = RUNNING.to_string();
+            x.started_at = now.to_string();
+            true
+        }
+        None => false,
+    }
+}
+
+/// from running to finished or failed
+fn end(jobs: &mut [JobItemData], job_id: u64, result: Result<(), String>, now: &str) -> Option<JobItemData> {
+    let x = jobs.iter_mut().find(|x| x.job_id == job_id && x.state == RUNNING)?;
+    match result {
+        Ok(()) => x.state = FINISHED.to_string(),
+        Err(err) => {
+            x.state = FAILED.to_string();
+            x.error = err;
+        }
+    }
+    x.ended_at = now.to_string();
+    Some(x.clone())
+}
+
+fn cancel(jobs: &mut [JobItemData], job_id: u64, now: &str) -> anyhow::Result<()> {
+    let x = match jobs.iter_mut().find(|x| x.job_id == job_id) {
+        Some(x) => x,
+        None => anyhow::bail!("The job {} does not exist anymore.", job_id),
+    };
+    if x.state != QUEUED {
+        anyhow::bail!("The job {} is {} and cannot be cancelled.", job_id, x.state);
+    }
+    x.state = CANCELLED.to_string();
+    x.ended_at = now.to_string();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(job_id: u64) -> JobItemData {
+        JobItemData {
+            job_id,
+            kind: SYNC_REVIEWS.to_string(),
+            state: QUEUED.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_job_states() {
+        let mut jobs = vec![queued(1), queued(2), queued(3)];
+        assert!(start(&mut jobs, 1, "t1"));
+        assert!(cancel(&mut jobs, 1, "t2").is_err());
+        assert!(cancel(&mut jobs, 2, "t2").is_ok());
+        assert!(!start(&mut jobs, 2, "t3"));
+        assert!(cancel(&mut jobs, 9, "t2").is_err());
+        let ended = end(&mut jobs, 1, Err("panic: no network".to_string()), "t4").unwrap();
+        assert_eq!(ended.state, FAILED);
+        assert_eq!(ended.error, "panic: no network");
+        assert!(end(&mut jobs, 2, Ok(()), "t4").is_none());
+        assert!(start(&mut jobs, 3, "t5"));
+        assert_eq!(end(&mut jobs, 3, Ok(()), "t6").unwrap().state, FINISHED);
+
+        let mut jobs: Vec<JobItemData> = (0..MAX_JOBS as u64).map(queued).collect();
+        jobs[10].state = FINISHED.to_string();
+        push_job(&mut jobs, queued(MAX_JOBS as u64));
+        assert_eq!(jobs.len(), MAX_JOBS);
+        assert!(jobs.iter().all(|x| x.job_id != 10));
+        push_job(&mut jobs, queued(MAX_JOBS as u64 + 1));
+        assert_eq!(jobs.len(), MAX_JOBS + 1);
+        assert_eq!(panic_message(&"boom"), "panic: boom");
+    }
+}
@@ -444,24 +444,43 @@
 use lazy_static::lazy_static;
 use std::sync::Mutex;
 
+mod annotation_mod;
 mod auto_generated_files_mod;
 mod auto_generated_mod;
 mod cargo_audit_mod;
 mod cargo_registry_mod;
 mod cargo_tree_mod;
+mod check_mod;
+mod cli_mod;
 mod common_structs_mod;
+mod crate_diff_mod;
 mod crates_io_mod;
 mod crev_mod;
 mod db_sled_mod;
+mod dependency_graph_mod;
+mod file_class_mod;
 mod html_mod;
+mod http_client_mod;
+mod jobs_mod;
+mod package_source_mod;
+mod policy_mod;
+mod progress_mod;
+mod proof_store_mod;
 mod response_get_mod;
 mod response_post_mod;
+mod risk_scan_mod;
+mod rpc_worker_mod;
+mod source_code_mod;
 mod srv_methods_mod;
 mod stdio_input_password_mod;
 mod utils_mod;
+mod vcs_verify_mod;
 
 // region: functions and structs accessible to /bin/cargo_crev_reviews.
 
+pub use cli_mod::is_cli_command;
+pub use cli_mod::GlobalOptions;
+pub use cli_mod::match_arguments_and_call_command;
 pub use crev_mod::unlock_crev_id_interactively;
 pub use db_sled_mod::db_metadata_mod::get_config;
 pub use db_sled_mod::db_sled_migration_update;
@@ -472,6 +491,9 @@ pub use response_get_mod::parse_get_uri_and_response_file;
 pub use response_get_mod::response_404_not_found;
 pub use response_get_mod::ResponseWithBytes;
 pub use response_get_mod::Status;
+pub use progress_mod::progress_port;
+pub use progress_mod::start_progress_server;
+pub use response_post_mod::check_host_and_origin;
 pub use response_post_mod::parse_post_data_and_match_method;
 pub use response_post_mod::response_err_message;
 pub use response_post_mod::response_modal_message;
@@ -480,19 +502,14 @@ pub use response_post_mod::response_modal_message;
 // region: public static variables
 
 lazy_static! {
-    /// 127.0.0.1
-    pub static ref SERVER_HOST: String=String::from("127.0.0.1");
-    /// 8182
-    pub static ref SERVER_PORT: String=String::from("8182");
+    /// listen address, default 127.0.0.1, the CLI option `--bind` changes it
+    pub static ref SERVER_HOST: Mutex<String>=Mutex::new(String::from("127.0.0.1"));
+    /// default 8182, the CLI option `--port` changes it
+    pub static ref SERVER_PORT: Mutex<String>=Mutex::new(String::from("8182"));
     // first subdirectory /cargo_crev_reviews/
     pub static ref SERVER_FIRST_SUBDIRECTORY: String=String::from("cargo_crev_reviews");
-}
-
-lazy_static! {
-    /// mutable static, because it is hard to pass variables around with async closures
-    static ref CREV_UNLOCKED: Mutex<Option<crev_data::id::UnlockedId>>=Mutex::new(None);
-    /// crev_liv Local struct
-    static ref LOCAL: Mutex<Option<crev_lib::Local>>=Mutex::new(None);
+    /// random token of this session. The opened url has it and every rpc must send it back.
+    pub static ref SESSION_TOKEN: String=response_post_mod::new_session_token();
 }
 
 lazy_static! {
@@ -523,12 +540,36 @@ lazy_static! {
 
 // endregion: public static variables
 
+pub fn server_host() -> String {
+    SERVER_HOST.lock().unwrap().clone()
+}
+
+pub fn server_port() -> String {
+    SERVER_PORT.lock().unwrap().clone()
+}
+
+/// the host as the browser sends it in the Host header
+/// The unspecified address `0.0.0.0` listens on all interfaces and the browser uses 127.0.0.1.
+pub fn browser_host() -> String {
+    let host = server_host();
+    match host.parse::<std::net::IpAddr>() {
+        Ok(ip) if ip.is_unspecified() => String::from("127.0.0.1"),
+        Ok(std::net::IpAddr::V6(ip)) => format!("[{}]", ip),
+        _ => host,
+    }
+}
+
+/// the url of the GUI
+pub fn server_url() -> String {
+    format!("http://{}:{}/{}", browser_host(), server_port(), SERVER_FIRST_SUBDIRECTORY.as_str())
+}
 /// check that this is the only instance of this server
 /// return None if the host+port is free
 /// if the server host+port is not free, returns the String for the error message.
 pub fn host_port_is_busy() -> Option<String> {
-    let url = format!("{}:{}", SERVER_HOST.as_str(), SERVER_PORT.as_str());
-    let listener = std::net::TcpListener::bind(&url);
+    let (host, port) = (server_host(), server_port());
+    let url = format!("{}:{}", host, port);
+    let listener = std::net::TcpListener::bind((host.as_str(), port.parse::<u16>().unwrap_or_default()));
     match listener {
         Ok(listener) => {
             drop(listener);
@@ -566,6 +607,7 @@ There is no Cargo.toml in the current directory: {dir}{res}
 
 Cargo_crev_reviews works best when started inside a Rust project 
 in the directory where the Cargo.toml file is.
+Or use the option `--project <dir>`.
 "#,
         dir = std::env::current_dir()?.to_string_lossy(),
         yel = *YELLOW,
@@ -575,15 +617,24 @@ in the directory where the Cargo.toml file is.
     Ok(())
 }
 
-/// warning cargo-crev not installed
-pub fn cargo_crev_not_installed() {
+/// the crev id is needed for verification and signing
+pub fn crev_id_exists() -> bool {
+    match crev_lib::local::Local::auto_open() {
+        Ok(crev_local) => crev_local.read_current_id().is_ok(),
+        Err(_err) => false,
+    }
+}
+
+/// warning crev id not found
+pub fn crev_id_not_found() {
     println!(
         r#"
 {yel}WELCOME to cargo_crev_reviews from Bestia.dev!{res}
 
-{red}Error: cargo-crev is not installed!{res}
+{red}Error: crev id not found!{res}
 
 Cargo_crev_reviews is a GUI wrapper around cargo-crev (from dpc).
+Cargo_crev_reviews verifies the dependencies itself, but cargo-crev is needed once to create your CrevId.
 Install and configure cargo-crev in 5 easy steps.
 Open a new Linux terminal and use this incantations:  
 1. Install cargo-crev:
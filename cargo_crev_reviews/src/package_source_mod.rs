@@ -0,0 +1,157 @@
+// package_source_mod.rs
+
+//! reviews for packages that don't come from crates.io: alternative registries, git repositories and path dependencies
+//! The crev `PackageVersionId` has the source url: `https://crates.io`, the index url of the alternative registry,
+//! the url of the git repository or PATH_SOURCE for every path dependency, so the local folder is never published.
+//! Distinct path packages with the same name and version share one review.
+//! The digest is calculated over the checksum-verified `.crate` of the alternative registry, or over the git checkout or the path folder.
+//! The package is found by name and version in the dependency graph of the current project.
+
+use anyhow::Context;
+
+use crate::dependency_graph_mod::{DependencyNode, SourceKind};
+
+pub const CRATES_IO_SOURCE: &str = "https://crates.io";
+/// the same source for all path packages, the local folder must not end up in a published proof
+pub const PATH_SOURCE: &str = "file:///local";
+/// files and folders in git checkouts and path folders that are not part of the package
+const IGNORED_IN_FOLDER: [&str; 3] = [".cargo-ok", ".git", "target"];
+
+/// the source of one package ready for the review proof
+#[derive(Debug, Clone)]
+pub struct PackageSource {
+    pub crev_source: String,
+    /// git commit of the checkout or from `.cargo_vcs_info.json`, else empty
+    pub revision: String,
+    /// calculated while the unpacked folder is locked
+    pub digest: crev_data::Digest,
+}
+
+/// in ReviewItemData the empty source means crates.io
+pub fn is_crates_io(crev_source: &str) -> bool {
+    crev_source.is_empty() || crev_source == CRATES_IO_SOURCE
+}
+
+/// the source url for crev from the cargo source id
+pub fn crev_source(source_kind: SourceKind, source_id: Option<&str>) -> String {
+    let source_id = source_id.unwrap_or("");
+    match source_kind {
+        SourceKind::CratesIo => CRATES_IO_SOURCE.to_string(),
+        SourceKind::Registry => source_id.split_once('+').map(|(_, url)| url).unwrap_or(source_id).to_string(),
+        SourceKind::Git => {
+            let url = source_id.trim_start_matches("git+");
+            let url = url.split(['?', '#']).next().unwrap_or(url);
+            url.to_string()
+        }
+        SourceKind::Path => PATH_SOURCE.to_string(),
+    }
+}
+
+/// the git commit is the fragment of the source id: `git+https://github.com/x/y?branch=main#sha1`
+pub fn git_revision(source_id: Option<&str>) -> String {
+    source_id.and_then(|x| x.rsplit_once('#')).map(|(_, sha1)| sha1.to_string()).unwrap_or_default()
+}
+
+/// the crev source if the project uses this crate version from another source than crates.io
+pub fn crev_source_in_project(crate_name: &str, crate_version: &str) -> anyhow::Result<Option<String>> {
+    let graph = crate::dependency_graph_mod::dependency_graph_project()?;
+    let crev_source = graph
+        .nodes
+        .values()
+        .filter(|node| node.name == crate_name && node.version == crate_version && node.source_kind != SourceKind::CratesIo)
+        .map(|node| crev_source(node.source_kind, node.source.as_deref()))
+        .next();
+    Ok(crev_source)
+}
+
+/// find the package in the project and prepare the clean source folder
+pub fn package_source_in_project(crate_name: &str, crate_version: &str, crev_source_url: &str) -> anyhow::Result<PackageSource> {
+    let graph = crate::dependency_graph_mod::dependency_graph_project()?;
+    let node = graph
+        .nodes
+        .values()
+        .find(|node| node.name == crate_name && node.version == crate_version && crev_source(node.source_kind, node.source.as_deref()) == crev_source_url)
+        .with_context(|| format!("The project has no dependency {} {} from {}", crate_name, crate_version, crev_source_url))?;
+    package_source(node)
+}
+
+fn package_source(node: &DependencyNode) -> anyhow::Result<PackageSource> {
+    let crev_source = crev_source(node.source_kind, node.source.as_deref());
+    let package_dir = std::path::Path::new(&node.manifest_path).parent().context("manifest_path has no parent")?.to_path_buf();
+    let (revision, digest) = match node.source_kind {
+        SourceKind::CratesIo => crate::crev_mod::with_clean_src_folder(&node.name, &node.version, revision_and_digest)?,
+        SourceKind::Registry => with_alternative_registry_crate(node, &package_dir, &crev_source, revision_and_digest)?,
+        SourceKind::Git => (git_revision(node.source.as_deref()), folder_digest(&package_dir)?),
+        SourceKind::Path => (String::new(), folder_digest(&package_dir)?),
+    };
+    Ok(PackageSource { crev_source, revision, digest })
+}
+
+/// the package dir is `~/.cargo/registry/src/<registry_dir>/<name>-<version>`
+/// The `.crate` from `~/.cargo/registry/cache/<registry_dir>` is verified with `~/.cargo/registry/index/<registry_dir>` and unpacked into the temp folder.
+/// The function reads the unpacked folder while the crate version is locked.
+fn with_alternative_registry_crate<T, F>(node: &DependencyNode, package_dir: &std::path::Path, index_url: &str, function: F) -> anyhow::Result<T>
+where
+    F: FnOnce(&std::path::Path) -> anyhow::Result<T>,
+{
+    let registry_src_dir = package_dir.parent().context("package_dir has no parent")?;
+    let registry_dir_name = registry_src_dir.file_name().context("registry src dir has no name")?;
+    let registry_dir = registry_src_dir.parent().and_then(|x| x.parent()).context("registry dir not found")?;
+    let cache_folder = registry_dir.join("cache").join(registry_dir_name);
+    let crate_file = cache_folder.join(format!("{}-{}.crate", node.name, node.version));
+    let content = std::fs::read(&crate_file).with_context(|| format!("Cannot read {}", crate_file.to_string_lossy()))?;
+    crate::cargo_registry_mod::verify_checksum_alternative_registry(&registry_dir.join("index").join(registry_dir_name), index_url, &node.name, &node.version, &content)?;
+
+    let crate_version_lock = crate::cargo_registry_mod::crate_version_lock(&node.name, &node.version);
+    let _guard = crate_version_lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let temp_parent = crate::CARGO_CREV_REVIEWS_SRC.join(registry_dir_name);
+    let src_folder = temp_parent.join(format!("{}-{}", node.name, node.version));
+    if src_folder.exists() {
+        std::fs::remove_dir_all(&src_folder)?;
+    }
+    std::fs::create_dir_all(&temp_parent)?;
+    crate::cargo_registry_mod::unpack_from_targz_to_folder(&node.name, &node.version, &cache_folder, &temp_parent)?;
+    function(&src_folder)
+}
+
+/// the revision from `.cargo_vcs_info.json` and the digest of the unpacked crate
+fn revision_and_digest(src_folder: &std::path::Path) -> anyhow::Result<(String, crev_data::Digest)> {
+    let revision = crate::crev_mod::vcs_info_to_revision_string(crate::crev_mod::VcsInfoJson::read_from_crate_dir(src_folder)?);
+    Ok((revision, folder_digest(src_folder)?))
+}
+
+/// the crev digest of the package folder
+/// The git checkout and the path folder can contain files that are not in the package.
+fn folder_digest(src_folder: &std::path::Path) -> anyhow::Result<crev_data::Digest> {
+    let mut ignore_list = std::collections::HashSet::default();
+    for name in IGNORED_IN_FOLDER.iter() {
+        ignore_list.insert(std::path::PathBuf::from(name));
+    }
+    Ok(crev_lib::get_recursive_digest_for_dir(src_folder, &ignore_list)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crev_source() {
+        assert_eq!(
+            crev_source(SourceKind::CratesIo, Some("registry+https://github.com/rust-lang/crates.io-index")),
+            CRATES_IO_SOURCE
+        );
+        assert_eq!(
+            crev_source(SourceKind::Registry, Some("registry+https://mirror.example.com/git/index")),
+            "https://mirror.example.com/git/index"
+        );
+        assert_eq!(
+            crev_source(SourceKind::Registry, Some("sparse+https://mirror.example.com/index/")),
+            "https://mirror.example.com/index/"
+        );
+        let git = Some("git+https://github.com/canndrew/unwrap?rev=1a2b3c4#1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b");
+        assert_eq!(crev_source(SourceKind::Git, git), "https://github.com/canndrew/unwrap");
+        assert_eq!(git_revision(git), "1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b");
+        assert_eq!(crev_source(SourceKind::Path, None), PATH_SOURCE);
+        assert!(is_crates_io("") && is_crates_io(CRATES_IO_SOURCE) && !is_crates_io(PATH_SOURCE));
+    }
+}
@@ -0,0 +1,337 @@
+// policy_mod.rs
+
+//! review policy for one project
+//! the file `crev-policy.toml` is in the project directory next to `Cargo.toml`
+//! the evaluator decides for every dependency if it passes the policy and which rule decided it
+//!
+//! ```toml
+//! min_rating = "positive"
+//! min_thoroughness = "low"
+//! min_understanding = "low"
+//! trusted_publisher_is_sufficient = true
+//! deny_yanked = true
+//! allowed_advisories = ["RUSTSEC-2020-0071"]
+//!
+//! [[exemption]]
+//! crate_name = "time"
+//! crate_version = "0.1.44"
+//! expires = "2026-12-31"
+//! reason = "waiting for the upgrade of chrono"
+//! ```
+
+use serde::Deserialize;
+
+use crate::common_structs_mod::ReviewItemData;
+
+pub const POLICY_FILE_NAME: &str = "crev-policy.toml";
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Policy {
+    /// negative, neutral, positive, strong
+    pub min_rating: String,
+    /// none, low, medium, high
+    pub min_thoroughness: String,
+    /// none, low, medium, high
+    pub min_understanding: String,
+    /// a crate version without my review passes if the publisher is in my trusted publishers
+    pub trusted_publisher_is_sufficient: bool,
+    pub deny_yanked: bool,
+    /// RustSec advisory ids that are accepted
+    pub allowed_advisories: Vec<String>,
+    #[serde(rename = "exemption")]
+    pub exemptions: Vec<PolicyExemption>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy {
+            min_rating: "positive".to_string(),
+            min_thoroughness: "none".to_string(),
+            min_understanding: "none".to_string(),
+            trusted_publisher_is_sufficient: false,
+            deny_yanked: true,
+            allowed_advisories: vec![],
+            exemptions: vec![],
+        }
+    }
+}
+
+/// the crate is exempted from the policy until the expiry date
+#[derive(Deserialize, Debug, Clone)]
+pub struct PolicyExemption {
+    pub crate_name: String,
+    /// if None, the exemption is for all versions
+    pub crate_version: Option<String>,
+    /// date in the format 2026-12-31
+    pub expires: String,
+    pub reason: Option<String>,
+}
+
+/// the data of one dependency needed to evaluate the policy
+#[derive(Debug, Default)]
+pub struct PolicyInput<'a> {
+    pub crate_name: &'a str,
+    pub crate_version: &'a str,
+    pub rating: Option<&'a str>,
+    pub thoroughness: Option<&'a str>,
+    pub understanding: Option<&'a str>,
+    pub trusted_publisher: bool,
+    pub yanked: bool,
+    pub audit_id: Option<&'a str>,
+}
+
+/// pass or fail and the rule that decided it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyResult {
+    pub passed: bool,
+    pub rule: String,
+}
+
+impl PolicyResult {
+    fn pass(rule: &str) -> Self {
+        PolicyResult {
+            passed: true,
+            rule: rule.to_string(),
+        }
+    }
+    fn fail(rule: &str) -> Self {
+        PolicyResult {
+            passed: false,
+            rule: rule.to_string(),
+        }
+    }
+    /// status for the GUI: pass or fail
+    pub fn status(&self) -> String {
+        if self.passed {
+            "pass".to_string()
+        } else {
+            "fail".to_string()
+        }
+    }
+}
+
+/// read the policy file from the project directory
+/// returns None if the project has no policy file
+pub fn read_policy_from_project_dir(project_dir: &std::path::Path) -> anyhow::Result<Option<Policy>> {
+    let path = project_dir.join(POLICY_FILE_NAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(&path)?;
+    let policy = parse_policy(&text).map_err(|err| anyhow::anyhow!("Error in {}: {}", path.to_string_lossy(), err))?;
+    Ok(Some(policy))
+}
+
+/// read the policy file from the current project directory
+pub fn read_policy() -> anyhow::Result<Option<Policy>> {
+    read_policy_from_project_dir(&std::env::current_dir()?)
+}
+
+pub fn parse_policy(text: &str) -> anyhow::Result<Policy> {
+    let policy: Policy = toml::from_str(text)?;
+    // check the values early, so the errors are not hidden in the evaluation
+    rating_rank(&policy.min_rating)?;
+    level_rank(&policy.min_thoroughness)?;
+    level_rank(&policy.min_understanding)?;
+    for exemption in policy.exemptions.iter() {
+        chrono::NaiveDate::parse_from_str(&exemption.expires, "%Y-%m-%d")
+            .map_err(|_err| anyhow::anyhow!("exemption for {} has a wrong expires date: {}", exemption.crate_name, exemption.expires))?;
+    }
+    Ok(policy)
+}
+
+/// evaluate the policy for one dependency. The first rule that decides, wins.
+pub fn evaluate(policy: &Policy, input: &PolicyInput, today: chrono::NaiveDate) -> PolicyResult {
+    // exemptions are stronger than all other rules until they expire
+    for exemption in policy.exemptions.iter() {
+        if exemption.crate_name == input.crate_name && exemption.crate_version.as_deref().map_or(true, |v| v == input.crate_version) {
+            if let Ok(expires) = chrono::NaiveDate::parse_from_str(&exemption.expires, "%Y-%m-%d") {
+                if today <= expires {
+                    return PolicyResult::pass(&format!("exemption until {}", exemption.expires));
+                }
+            }
+        }
+    }
+    if let Some(audit_id) = input.audit_id {
+        if !policy.allowed_advisories.iter().any(|x| x == audit_id) {
+            return PolicyResult::fail(&format!("advisory {} is not allowed", audit_id));
+        }
+    }
+    if policy.deny_yanked && input.yanked {
+        return PolicyResult::fail("yanked is denied");
+    }
+    match input.rating {
+        Some(rating) => {
+            if rating_rank(rating).unwrap_or(0) < rating_rank(&policy.min_rating).unwrap_or(0) {
+                return PolicyResult::fail(&format!("rating {} is below min_rating {}", rating, policy.min_rating));
+            }
+            let thoroughness = input.thoroughness.unwrap_or("none");
+            if level_rank(thoroughness).unwrap_or(0) < level_rank(&policy.min_thoroughness).unwrap_or(0) {
+                return PolicyResult::fail(&format!("thoroughness {} is below min_thoroughness {}", thoroughness, policy.min_thoroughness));
+            }
+            let understanding = input.understanding.unwrap_or("none");
+            if level_rank(understanding).unwrap_or(0) < level_rank(&policy.min_understanding).unwrap_or(0) {
+                return PolicyResult::fail(&format!(
+                    "understanding {} is below min_understanding {}",
+                    understanding, policy.min_understanding
+                ));
+            }
+            PolicyResult::pass("my review")
+        }
+        None => {
+            if policy.trusted_publisher_is_sufficient && input.trusted_publisher {
+                PolicyResult::pass("trusted publisher is sufficient")
+            } else {
+                PolicyResult::fail("unreviewed")
+            }
+        }
+    }
+}
+
+/// evaluate with today's date
+pub fn evaluate_today(policy: &Policy, input: &PolicyInput) -> PolicyResult {
+    evaluate(policy, input, chrono::Local::today().naive_local())
+}
+
+/// evaluate one crate version with my review from the caller and yanked from the sled cache
+/// The GUI passes my review from the sled cache, the headless check reads it from crev, because the cache is synced only in the background.
+/// trusted_publisher is "T" from is_trusted_publisher()
+pub fn evaluate_crate_version(
+    policy: &Policy,
+    crate_name: &str,
+    crate_version: &str,
+    my_review: Option<&ReviewItemData>,
+    trusted_publisher: &str,
+    audit_id: Option<&str>,
+) -> PolicyResult {
+    let crate_name_version = crate::utils_mod::crate_version_join(crate_name, crate_version);
+    let yanked = crate::db_sled_mod::db_yanked_mod::exists(&crate_name_version);
+    let input = policy_input(crate_name, crate_version, my_review, trusted_publisher, yanked, audit_id);
+    evaluate_today(policy, &input)
+}
+
+/// my review from the sled cache for the GUI
+pub fn my_review_from_cache(crate_name: &str, crate_version: &str) -> Option<ReviewItemData> {
+    crate::db_sled_mod::db_review_mod::read(&crate::utils_mod::crate_version_join(crate_name, crate_version)).unwrap_or(None)
+}
+
+fn policy_input<'a>(
+    crate_name: &'a str,
+    crate_version: &'a str,
+    my_review: Option<&'a ReviewItemData>,
+    trusted_publisher: &str,
+    yanked: bool,
+    audit_id: Option<&'a str>,
+) -> PolicyInput<'a> {
+    PolicyInput {
+        crate_name,
+        crate_version,
+        rating: my_review.map(|x| x.rating.as_str()),
+        thoroughness: my_review.map(|x| x.thoroughness.as_str()),
+        understanding: my_review.map(|x| x.understanding.as_str()),
+        trusted_publisher: trusted_publisher == "T",
+        yanked,
+        audit_id,
+    }
+}
+
+fn rating_rank(rating: &str) -> anyhow::Result<u8> {
+    match rating.to_lowercase().as_str() {
+        "negative" => Ok(0),
+        "neutral" => Ok(1),
+        "positive" => Ok(2),
+        "strong" => Ok(3),
+        _ => anyhow::bail!("unrecognized rating: {}", rating),
+    }
+}
+
+fn level_rank(level: &str) -> anyhow::Result<u8> {
+    match level.to_lowercase().as_str() {
+        "none" => Ok(0),
+        "low" => Ok(1),
+        "medium" => Ok(2),
+        "high" => Ok(3),
+        _ => anyhow::bail!("unrecognized level: {}", level),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd(2026, 6, 1)
+    }
+
+    #[test]
+    fn test_parse_policy_and_evaluate() {
+        let policy = parse_policy(
+            r#"
+min_rating = "positive"
+min_thoroughness = "low"
+trusted_publisher_is_sufficient = true
+allowed_advisories = ["RUSTSEC-2020-0071"]
+
+[[exemption]]
+crate_name = "time"
+crate_version = "0.1.44"
+expires = "2026-12-31"
+"#,
+        )
+        .unwrap();
+
+        let input = PolicyInput {
+            crate_name: "anyhow",
+            crate_version: "1.0.43",
+            rating: Some("positive"),
+            thoroughness: Some("none"),
+            ..Default::default()
+        };
+        let result = evaluate(&policy, &input, today());
+        assert!(!result.passed);
+        assert!(result.rule.starts_with("thoroughness"));
+
+        let input = PolicyInput {
+            crate_name: "anyhow",
+            crate_version: "1.0.43",
+            trusted_publisher: true,
+            ..Default::default()
+        };
+        assert_eq!(evaluate(&policy, &input, today()), PolicyResult::pass("trusted publisher is sufficient"));
+
+        let input = PolicyInput {
+            crate_name: "time",
+            crate_version: "0.1.44",
+            audit_id: Some("RUSTSEC-2020-0071"),
+            ..Default::default()
+        };
+        assert_eq!(evaluate(&policy, &input, today()), PolicyResult::pass("exemption until 2026-12-31"));
+        // expired exemption
+        let result = evaluate(&policy, &input, chrono::NaiveDate::from_ymd(2027, 1, 1));
+        assert_eq!(result, PolicyResult::fail("unreviewed"));
+    }
+
+    #[test]
+    fn test_review_not_yet_in_cache() {
+        let policy = parse_policy(r#"min_thoroughness = "low""#).unwrap();
+        // the headless check passes my review from crev, the sled cache has nothing yet
+        let my_review = ReviewItemData {
+            crate_name: "anyhow".to_string(),
+            crate_version: "1.0.43".to_string(),
+            rating: "positive".to_string(),
+            thoroughness: "low".to_string(),
+            understanding: "medium".to_string(),
+            ..Default::default()
+        };
+        let input = policy_input("anyhow", "1.0.43", Some(&my_review), "", false, None);
+        assert_eq!(evaluate(&policy, &input, today()), PolicyResult::pass("my review"));
+        let input = policy_input("anyhow", "1.0.43", None, "", false, None);
+        assert_eq!(evaluate(&policy, &input, today()), PolicyResult::fail("unreviewed"));
+    }
+
+    #[test]
+    fn test_parse_policy_wrong_rating() {
+        assert!(parse_policy(r#"min_rating = "excellent""#).is_err());
+    }
+}
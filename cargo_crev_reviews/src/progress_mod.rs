@@ -0,0 +1,197 @@
+// progress_mod.rs
+
+//! progress of long-running operations as server-sent events
+//! The micro web server returns only complete responses, so the event stream has its own listener on a free port.
+//! The opened url tells the frontend this port. Every open event stream gets all the events.
+
+use anyhow::Context;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::sync::mpsc::{RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use unwrap::unwrap;
+
+use crate::common_structs_mod::ProgressEventData;
+
+pub const CORRECT_DIGEST: &str = "correct_digest";
+pub const LIST_UNCLEAN_CRATES: &str = "list_unclean_crates";
+pub const UPDATE_REGISTRY_INDEX: &str = "update_registry_index";
+pub const CARGO_TREE: &str = "cargo_tree";
+
+/// a comment line keeps the connection open through proxies and detects closed tabs
+const KEEP_ALIVE: std::time::Duration = std::time::Duration::from_secs(15);
+/// the request head must come immediately
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+const MAX_HEAD_SIZE: u64 = 16 * 1024;
+
+lazy_static! {
+    /// one channel for every open event stream
+    static ref SUBSCRIBERS: Mutex<Vec<Sender<String>>> = Mutex::new(vec![]);
+    /// the port of the event stream listener, 0 until started
+    static ref PROGRESS_PORT: Mutex<u16> = Mutex::new(0);
+}
+
+pub fn progress_port() -> u16 {
+    *PROGRESS_PORT.lock().unwrap()
+}
+
+/// the step n of m with the current crate
+pub fn progress(operation: &str, n: usize, m: usize, current: &str) {
+    send(ProgressEventData {
+        operation: operation.to_string(),
+        current: current.to_string(),
+        n,
+        m,
+        ..Default::default()
+    });
+}
+
+/// the operation continues after this error
+pub fn progress_error(operation: &str, current: &str, error: &str) {
+    send(ProgressEventData {
+        operation: operation.to_string(),
+        current: current.to_string(),
+        error: error.to_string(),
+        ..Default::default()
+    });
+}
+
+/// progress_finished when dropped, so every return path of the operation sends it, also the errors and panics
+pub struct ProgressGuard {
+    operation: &'static str,
+}
+
+impl Drop for ProgressGuard {
+    fn drop(&mut self) {
+        progress_finished(self.operation);
+    }
+}
+
+/// keep the guard until the end of the operation: `let _progress = progress_guard(CARGO_TREE);`
+pub fn progress_guard(operation: &'static str) -> ProgressGuard {
+    ProgressGuard { operation }
+}
+
+/// the frontend closes the event stream
+fn progress_finished(operation: &str) {
+    send(ProgressEventData {
+        operation: operation.to_string(),
+        finished: true,
+        ..Default::default()
+    });
+}
+
+/// the closed streams are removed
+fn send(event: ProgressEventData) {
+    let data = unwrap!(serde_json::to_string(&event));
+    SUBSCRIBERS.lock().unwrap().retain(|x| x.send(data.clone()).is_ok());
+}
+
+/// bind a free port on the listen address and serve the event streams, every stream in its own thread
+pub fn start_progress_server() -> anyhow::Result<()> {
+    let listener = std::net::TcpListener::bind((crate::server_host().as_str(), 0))?;
+    *PROGRESS_PORT.lock().unwrap() = listener.local_addr()?.port();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            std::thread::spawn(move || {
+                if let Err(err) = serve_event_stream(stream) {
+                    log::debug!("progress event stream: {}", err);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+/// GET /cargo_crev_reviews/progress?token=... from the EventSource of the frontend
+fn serve_event_stream(mut stream: std::net::TcpStream) -> anyhow::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let (path, headers) = read_request_head(std::io::Read::take(&stream, MAX_HEAD_SIZE))?;
+    let origin = headers.get("origin").map(|x| x.as_str());
+    // EventSource cannot send headers, so the token is in the query
+    let check = crate::response_post_mod::check_host(headers.get("host").map(|x| x.as_str()), &progress_port().to_string())
+        .and_then(|_| crate::response_post_mod::check_origin(origin))
+        .and_then(|_| {
+            let (route, query) = path.split_once('?').unwrap_or((path.as_str(), ""));
+            let token = query.split('&').find_map(|x| x.strip_prefix("token=")).unwrap_or("");
+            if route != format!("/{}/progress", crate::SERVER_FIRST_SUBDIRECTORY.as_str()) || !crate::response_post_mod::is_session_token(token) {
+                anyhow::bail!("Rejected the progress request without the session token.");
+            }
+            Ok(())
+        });
+    if let Err(err) = check {
+        log::warn!("{}", err);
+        write!(stream, "HTTP/1.1 403 Forbidden\r\nContent-Type: text/plain\r\nConnection: close\r\n\r\n{}", err)?;
+        return Ok(());
+    }
+    // the page and the event stream have different ports, that is cross-origin for the browser
+    let allow_origin = origin.map(|x| format!("Access-Control-Allow-Origin: {}\r\n", x)).unwrap_or_default();
+    write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\n{}\r\n", allow_origin)?;
+    stream.flush()?;
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    SUBSCRIBERS.lock().unwrap().push(sender);
+    loop {
+        let chunk = match receiver.recv_timeout(KEEP_ALIVE) {
+            Ok(data) => format!("data: {}\n\n", data),
+            Err(RecvTimeoutError::Timeout) => ": keep-alive\n\n".to_string(),
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        };
+        // the error ends the thread when the tab is closed
+        stream.write_all(chunk.as_bytes())?;
+        stream.flush()?;
+    }
+}
+
+/// the path and the headers with lowercase names
+fn read_request_head(reader: impl std::io::Read) -> anyhow::Result<(String, HashMap<String, String>)> {
+    let mut reader = std::io::BufReader::new(reader);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    if parts.next() != Some("GET") {
+        anyhow::bail!("The progress event stream accepts only GET.");
+    }
+    let path = parts.next().context("The request line has no path.")?.to_string();
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+    Ok((path, headers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_request_head() {
+        let head = "GET /cargo_crev_reviews/progress?token=abc HTTP/1.1\r\nHost: 127.0.0.1:40000\r\nOrigin: http://127.0.0.1:8182\r\n\r\nrest";
+        let (path, headers) = read_request_head(head.as_bytes()).unwrap();
+        assert_eq!(path, "/cargo_crev_reviews/progress?token=abc");
+        assert_eq!(headers.get("host").unwrap(), "127.0.0.1:40000");
+        assert_eq!(headers.get("origin").unwrap(), "http://127.0.0.1:8182");
+        assert!(read_request_head("POST / HTTP/1.1\r\n\r\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_progress_guard() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        SUBSCRIBERS.lock().unwrap().push(sender);
+        let result: anyhow::Result<()> = (|| {
+            let _progress = progress_guard(CARGO_TREE);
+            anyhow::bail!("cargo metadata failed")
+        })();
+        assert!(result.is_err());
+        let event: ProgressEventData = serde_json::from_str(&receiver.try_recv().unwrap()).unwrap();
+        assert_eq!(event.operation, CARGO_TREE);
+        assert!(event.finished);
+    }
+}
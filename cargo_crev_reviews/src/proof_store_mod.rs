@@ -0,0 +1,116 @@
+// proof_store_mod.rs
+
+//! reader for my proof store: the *.proof.crev files in my crev reviews directory
+//! Every proof is parsed with crev_data, the signature is verified and the author must be my crev id.
+//! Corrupt, unsigned or foreign proofs are collected as problems and are not returned as reviews.
+//! Malformed files must never panic the app.
+
+use crev_data::proof::CommonOps;
+use dev_bestia_string_utils::*;
+use std::ops::Range;
+
+use crate::common_structs_mod::*;
+use crate::crev_mod::ProofCrevForReview;
+
+const BEGIN_DELIMITER: &str = "----- BEGIN CREV PROOF -----";
+const SIGN_DELIMITER: &str = "----- SIGN CREV PROOF -----";
+const END_DELIMITER: &str = "----- END CREV PROOF -----";
+
+/// one valid proof and its position in the file
+#[derive(Debug, Clone)]
+pub struct ProofInFile {
+    pub file_path: String,
+    /// the range includes the delimiters and the white space after the proof
+    pub range: Range<usize>,
+    pub proof_crev: ProofCrevForReview,
+}
+
+/// all proofs from my reviews directory
+#[derive(Debug, Default)]
+pub struct ProofStoreContent {
+    pub proofs: Vec<ProofInFile>,
+    pub problems: Vec<ProofProblemItemData>,
+}
+
+/// read and verify all the proofs in my reviews directory
+pub fn read_my_proof_store() -> anyhow::Result<ProofStoreContent> {
+    let my_id = crate::crev_mod::my_crev_id()?;
+    let mut content = ProofStoreContent::default();
+    for file_path in crate::crev_mod::proof_crev_files_paths()?.iter() {
+        match std::fs::read_to_string(file_path) {
+            Err(err) => content.problems.push(ProofProblemItemData {
+                file_path: file_path.clone(),
+                line_number: 0,
+                problem: format!("cannot read file: {}", err),
+            }),
+            Ok(file_content) => read_proofs_from_file_content(file_path, &file_content, &my_id, &mut content),
+        }
+    }
+    Ok(content)
+}
+
+/// split the file into proofs and parse every proof separately, so one corrupt proof does not hide the others
+fn read_proofs_from_file_content(file_path: &str, file_content: &str, my_id: &crev_data::Id, content: &mut ProofStoreContent) {
+    let mut pos_cursor = 0;
+    loop {
+        let pos_before = pos_cursor;
+        let mut range = match find_range_including_delimiters(file_content, &mut pos_cursor, BEGIN_DELIMITER, END_DELIMITER) {
+            Some(range) => range,
+            None => {
+                // a BEGIN delimiter without the END delimiter is a truncated proof
+                if let Some(pos_begin) = file_content[pos_before..].find(BEGIN_DELIMITER) {
+                    content.problems.push(ProofProblemItemData {
+                        file_path: file_path.to_string(),
+                        line_number: file_content[..pos_before + pos_begin].lines().count() + 1,
+                        problem: "corrupt proof: missing end delimiter".to_string(),
+                    });
+                }
+                break;
+            }
+        };
+        // if there is some white space after the segment, include it in the range.
+        range.end = find_pos_before_delimiter(file_content, pos_cursor, BEGIN_DELIMITER).unwrap_or(file_content.len());
+        let line_number = file_content[..range.start].lines().count() + 1;
+        let mut push_problem = |problem: String| {
+            content.problems.push(ProofProblemItemData {
+                file_path: file_path.to_string(),
+                line_number,
+                problem,
+            })
+        };
+        let proof_text = &file_content[range.clone()];
+        if !proof_text.contains(SIGN_DELIMITER) {
+            push_problem("unsigned proof".to_string());
+            continue;
+        }
+        let proof = match crev_data::proof::Proof::parse_from(proof_text.as_bytes()) {
+            Err(err) => {
+                push_problem(format!("corrupt proof: {}", err));
+                continue;
+            }
+            Ok(mut proofs) => match proofs.pop() {
+                None => {
+                    push_problem("corrupt proof: empty".to_string());
+                    continue;
+                }
+                Some(proof) => proof,
+            },
+        };
+        if let Err(err) = proof.verify() {
+            push_problem(format!("invalid signature: {}", err));
+            continue;
+        }
+        if &proof.author_public_id().id != my_id {
+            push_problem(format!("foreign crev id: {}", proof.author_public_id().id));
+            continue;
+        }
+        match serde_yaml::from_str::<ProofCrevForReview>(proof.body()) {
+            Err(err) => push_problem(format!("not a package review: {}", err)),
+            Ok(proof_crev) => content.proofs.push(ProofInFile {
+                file_path: file_path.to_string(),
+                range,
+                proof_crev,
+            }),
+        }
+    }
+}
@@ -6,10 +6,65 @@ use crate::common_structs_mod::*;
 use unwrap::unwrap;
 
 pub fn parse_post_data_and_match_method(body: &Vec<u8>) -> anyhow::Result<String> {
-    let p: RpcRequest = unwrap!(serde_json::from_slice(body));
+    let p: RpcRequest = serde_json::from_slice(body)?;
+    // any web page in the same browser can POST to 127.0.0.1, but it cannot know the token
+    if !is_session_token(&p.token) {
+        anyhow::bail!(
+            "Rejected the request {} without the session token. Open the url that cargo_crev_reviews printed in the terminal.",
+            p.request_method
+        );
+    }
+    // the long methods don't block the server for the other tabs
+    if crate::rpc_worker_mod::is_long_running(&p.request_method) {
+        return crate::rpc_worker_mod::spawn_rpc(p.request_method, p.request_data);
+    }
     crate::auto_generated_mod::match_request_method_and_call_function(p.request_method.as_str(), p.request_data)
 }
 
+/// 128 random bits from the OS as hex
+pub fn new_session_token() -> String {
+    let mut bytes = [0u8; 16];
+    unwrap!(std::io::Read::read_exact(&mut unwrap!(std::fs::File::open("/dev/urandom")), &mut bytes));
+    bytes.iter().map(|x| format!("{:02x}", x)).collect()
+}
+
+/// compare in constant time
+pub fn is_session_token(token: &str) -> bool {
+    let session_token = crate::SESSION_TOKEN.as_bytes();
+    token.len() == session_token.len() && token.bytes().zip(session_token.iter()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// the server accepts only requests to its own host and from its own pages
+/// A foreign web page sends its own Origin. DNS rebinding sends a foreign Host with the resolved 127.0.0.1.
+pub fn check_host_and_origin(host: Option<&str>, origin: Option<&str>) -> anyhow::Result<()> {
+    check_host(host, &crate::server_port())?;
+    check_origin(origin)
+}
+
+/// the Host header must be the listen address, 127.0.0.1 or localhost with this port
+pub fn check_host(host: Option<&str>, port: &str) -> anyhow::Result<()> {
+    match host {
+        Some(host) if allowed_hosts(port).iter().any(|x| x == host) => Ok(()),
+        Some(host) => anyhow::bail!("Rejected the request for the foreign host {}.", host),
+        None => anyhow::bail!("Rejected the request without the Host header."),
+    }
+}
+
+/// the Origin must be a page of this server
+/// requests from the address bar or from the command line don't have the Origin
+pub fn check_origin(origin: Option<&str>) -> anyhow::Result<()> {
+    if let Some(origin) = origin {
+        if !allowed_hosts(&crate::server_port()).iter().any(|x| origin == format!("http://{}", x)) {
+            anyhow::bail!("Rejected the request from the foreign origin {}.", origin);
+        }
+    }
+    Ok(())
+}
+
+fn allowed_hosts(port: &str) -> [String; 3] {
+    [format!("{}:{}", crate::browser_host(), port), format!("127.0.0.1:{}", port), format!("localhost:{}", port)]
+}
+
 // the first parameter is the Serialize trait and not a struct
 pub fn return_srv_response<T>(response_method: &str, data: T, response_html: &str) -> String
 where
@@ -42,3 +97,47 @@ pub fn response_no_action() -> anyhow::Result<String> {
 pub fn response_modal_close() -> anyhow::Result<String> {
     crate::auto_generated_mod::cln_methods::cln_modal_close("", "")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_host_and_origin() {
+        let port = crate::server_port();
+        let ip_host = format!("127.0.0.1:{}", port);
+        let localhost = format!("localhost:{}", port);
+        assert!(check_host_and_origin(Some(&ip_host), None).is_ok());
+        // the browser can open localhost or 127.0.0.1, both are the same server
+        assert!(check_host_and_origin(Some(&localhost), Some(&format!("http://{}", localhost))).is_ok());
+        assert!(check_host_and_origin(Some(&localhost), Some(&format!("http://{}", ip_host))).is_ok());
+        assert!(check_host_and_origin(Some(&ip_host), Some(&format!("http://{}", localhost))).is_ok());
+        // foreign host like in DNS rebinding, another address or another port
+        assert!(check_host_and_origin(Some(&format!("attacker.example.com:{}", port)), None).is_err());
+        assert!(check_host_and_origin(Some(&format!("127.0.0.2:{}", port)), None).is_err());
+        assert!(check_host(Some("127.0.0.1:1"), &port).is_err());
+        assert!(check_host_and_origin(Some("127.0.0.1"), None).is_err());
+        // foreign origin
+        assert!(check_host_and_origin(Some(&ip_host), Some("http://attacker.example.com")).is_err());
+        assert!(check_host_and_origin(Some(&ip_host), Some(&format!("https://{}", ip_host))).is_err());
+        assert!(check_host_and_origin(Some(&ip_host), Some("http://127.0.0.1")).is_err());
+        assert!(check_host_and_origin(Some(&ip_host), Some("null")).is_err());
+        // missing host
+        assert!(check_host_and_origin(None, None).is_err());
+        assert!(check_host_and_origin(None, Some(&format!("http://{}", ip_host))).is_err());
+    }
+
+    #[test]
+    fn test_is_session_token() {
+        let token = crate::SESSION_TOKEN.as_str();
+        assert_eq!(token.len(), 32);
+        assert!(is_session_token(token));
+        // wrong in the last character
+        let last = if token.ends_with('0') { '1' } else { '0' };
+        assert!(!is_session_token(&format!("{}{}", &token[..31], last)));
+        // too short, too long and empty
+        assert!(!is_session_token(&token[..31]));
+        assert!(!is_session_token(&format!("{}0", token)));
+        assert!(!is_session_token(""));
+    }
+}
@@ -0,0 +1,161 @@
+// risk_scan_mod.rs
+
+//! risk scanner for the clean source code of a crate version
+//! It finds the constructs that a reviewer must read first: unsafe, build script, proc-macro,
+//! process, network, filesystem writes, include_bytes!, extern "C", #[no_mangle] and env reads.
+//! It is a simple text search line by line, not a parser. It can find too much, but it does not hide anything.
+//! The result never changes for a crate version, so it is cached in sled.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::common_structs_mod::*;
+
+/// long lines are cut in the list of findings
+const MAX_TEXT_LEN: usize = 200;
+
+lazy_static! {
+    /// the kind is the name of the field in RiskCountsData
+    static ref RUST_RULES: Vec<(&'static str, Regex)> = vec![
+        ("unsafe_code", Regex::new(r"\bunsafe\s*(\{|fn\b|impl\b|trait\b|extern\b)").unwrap()),
+        ("process", Regex::new(r"\bprocess::Command\b|\bCommand::new\s*\(").unwrap()),
+        (
+            "network",
+            Regex::new(r"\bstd::net\b|\bTcpStream\b|\bTcpListener\b|\bUdpSocket\b|\bUnixStream\b|\bUnixListener\b|\bToSocketAddrs\b|\breqwest::|\bhyper::|\bureq::").unwrap()
+        ),
+        (
+            "fs_write",
+            Regex::new(r"\bfs::(write|create_dir|create_dir_all|remove_file|remove_dir|remove_dir_all|rename|copy|hard_link|set_permissions)\b|\bFile::create\b|\bOpenOptions\b").unwrap()
+        ),
+        ("include_bytes", Regex::new(r"\binclude_bytes!").unwrap()),
+        ("extern_c", Regex::new(r#"\bextern\s+"(C|system)""#).unwrap()),
+        ("no_mangle", Regex::new(r"\bno_mangle\b").unwrap()),
+        ("env", Regex::new(r"\benv::(var|var_os|vars|vars_os)\b|\b(option_)?env!\s*\(").unwrap()),
+    ];
+    static ref CARGO_TOML_RULES: Vec<(&'static str, Regex)> = vec![
+        ("proc_macro", Regex::new(r"^\s*proc[-_]macro\s*=\s*true").unwrap()),
+        ("build_script", Regex::new(r#"^\s*build\s*=\s*""#).unwrap()),
+    ];
+}
+
+/// from the sled cache or scan now
+pub fn risk_scan(crate_name: &str, crate_version: &str) -> anyhow::Result<RiskScanData> {
+    let crate_name_version = crate::utils_mod::crate_version_join(crate_name, crate_version);
+    if let Some(data) = crate::db_sled_mod::db_risk_scan_mod::read(&crate_name_version)? {
+        return Ok(data);
+    }
+    crate::crev_mod::with_clean_src_folder(crate_name, crate_version, |src_folder| scan_and_store(crate_name, crate_version, src_folder))
+}
+
+/// scan the unpacked crate and store the result
+pub fn scan_and_store(crate_name: &str, crate_version: &str, src_folder: &std::path::Path) -> anyhow::Result<RiskScanData> {
+    let crate_name_version = crate::utils_mod::crate_version_join(crate_name, crate_version);
+    let (counts, list_of_finding) = scan_folder(src_folder)?;
+    let data = RiskScanData {
+        crate_name: crate_name.to_string(),
+        crate_version: crate_version.to_string(),
+        counts,
+        list_of_finding,
+    };
+    crate::db_sled_mod::db_risk_scan_mod::insert(&crate_name_version, &data)?;
+    Ok(data)
+}
+
+/// all the findings in the files of the folder, sorted by file and line
+pub fn scan_folder(src_folder: &std::path::Path) -> anyhow::Result<(RiskCountsData, Vec<RiskFindingItemData>)> {
+    let mut counts = RiskCountsData::default();
+    let mut list_of_finding = vec![];
+    for entry in walkdir::WalkDir::new(src_folder).min_depth(1).sort_by_file_name() {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path().strip_prefix(src_folder)?.to_string_lossy().replace('\\', "/");
+        let rules: &[(&str, Regex)] = if path == "Cargo.toml" {
+            &CARGO_TOML_RULES
+        } else if path.ends_with(".rs") {
+            &RUST_RULES
+        } else {
+            continue;
+        };
+        if path == "build.rs" {
+            add_finding(&mut counts, &mut list_of_finding, "build_script", &path, 1, "build.rs");
+        }
+        // binary files and invalid utf8 are not source code
+        let content = match std::fs::read_to_string(entry.path()) {
+            Ok(content) => content,
+            Err(_err) => continue,
+        };
+        scan_text(&path, &content, rules, &mut counts, &mut list_of_finding);
+    }
+    Ok((counts, list_of_finding))
+}
+
+/// every line can have more findings of different kinds
+fn scan_text(path: &str, content: &str, rules: &[(&str, Regex)], counts: &mut RiskCountsData, list_of_finding: &mut Vec<RiskFindingItemData>) {
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        // comments and doc comments are not code
+        if trimmed.starts_with("//") || (trimmed.starts_with('#') && !trimmed.starts_with("#[") && !trimmed.starts_with("#!")) {
+            continue;
+        }
+        for (kind, regex) in rules.iter() {
+            if regex.is_match(line) {
+                add_finding(counts, list_of_finding, kind, path, i + 1, trimmed);
+            }
+        }
+    }
+}
+
+fn add_finding(counts: &mut RiskCountsData, list_of_finding: &mut Vec<RiskFindingItemData>, kind: &str, path: &str, line_number: usize, text: &str) {
+    let count = match kind {
+        "unsafe_code" => &mut counts.unsafe_code,
+        "build_script" => &mut counts.build_script,
+        "proc_macro" => &mut counts.proc_macro,
+        "process" => &mut counts.process,
+        "network" => &mut counts.network,
+        "fs_write" => &mut counts.fs_write,
+        "include_bytes" => &mut counts.include_bytes,
+        "extern_c" => &mut counts.extern_c,
+        "no_mangle" => &mut counts.no_mangle,
+        _ => &mut counts.env,
+    };
+    *count += 1;
+    list_of_finding.push(RiskFindingItemData {
+        kind: kind.to_string(),
+        path: path.to_string(),
+        line_number,
+        text: text.chars().take(MAX_TEXT_LEN).collect(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_folder() {
+        let folder = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(folder.path().join("src")).unwrap();
+        std::fs::write(folder.path().join("Cargo.toml"), "[package]\nname = \"x\"\n\n[lib]\nproc-macro = true\n").unwrap();
+        std::fs::write(folder.path().join("build.rs"), "fn main() {\n    let _ = std::env::var(\"OUT_DIR\");\n}\n").unwrap();
+        std::fs::write(
+            folder.path().join("src/lib.rs"),
+            "// unsafe { in a comment }\npub fn f() {\n    unsafe { g() }\n    std::process::Command::new(\"ls\");\n}\n#[no_mangle]\npub extern \"C\" fn h() {}\nstatic B: &[u8] = include_bytes!(\"x.bin\");\n",
+        )
+        .unwrap();
+
+        let (counts, list) = scan_folder(folder.path()).unwrap();
+        assert_eq!(counts.unsafe_code, 1);
+        assert_eq!(counts.build_script, 1);
+        assert_eq!(counts.proc_macro, 1);
+        assert_eq!(counts.process, 1);
+        assert_eq!(counts.env, 1);
+        assert_eq!(counts.no_mangle, 1);
+        assert_eq!(counts.extern_c, 1);
+        assert_eq!(counts.include_bytes, 1);
+        assert_eq!(counts.network + counts.fs_write, 0);
+        let unsafe_finding = list.iter().find(|x| x.kind == "unsafe_code").unwrap();
+        assert_eq!((unsafe_finding.path.as_str(), unsafe_finding.line_number), ("src/lib.rs", 3));
+    }
+}
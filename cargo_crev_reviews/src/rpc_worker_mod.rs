@@ -0,0 +1,109 @@
+// rpc_worker_mod.rs
+
+//! long-running rpc methods on worker threads
+//! The micro web server handles one request at a time, so `cargo tree` or `cargo audit` would freeze every tab.
+//! The long method runs on a worker thread and the server responds immediately with cln_rpc_pending.
+//! The client asks for the result with srv_rpc_result until it is ready. The result is the response of the original method.
+//! sled is safe for concurrent use. The crev state and the proofs directory are guarded by mutexes in crev_mod.
+//! The unpacked src folder of a crate version is read only inside crev_mod::with_clean_src_folder while the crate version is locked,
+//! so a worker never reads a folder that another worker or a background job unpacks again.
+
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::auto_generated_mod::cln_methods;
+use crate::common_structs_mod::RpcPendingData;
+
+/// these methods call cargo, git or crates.io or read all the proofs
+const LONG_RUNNING_METHODS: [&str; 8] = [
+    "srv_cargo_tree_project",
+    "srv_correct_digest",
+    "srv_list_unclean_crates",
+    "srv_review_publish",
+    "srv_risk_scan",
+    "srv_update_registry_index",
+    "srv_vcs_verify",
+    "srv_version_diff",
+];
+/// the worker threads for the long methods, the background jobs have their own pool
+const WORKER_THREADS: usize = 4;
+/// the result of a closed tab is never taken
+const RESULT_TTL: Duration = Duration::from_secs(600);
+
+enum RpcState {
+    Running,
+    Done(Instant, anyhow::Result<String>),
+}
+
+static NEXT_RPC_ID: AtomicU64 = AtomicU64::new(1);
+
+lazy_static! {
+    static ref RPC_POOL: rayon::ThreadPool = rayon::ThreadPoolBuilder::new().num_threads(WORKER_THREADS).build().unwrap();
+    /// the results wait here until the client takes them
+    static ref RPC_RESULTS: Mutex<HashMap<u64, RpcState>> = Mutex::new(HashMap::new());
+}
+
+pub fn is_long_running(request_method: &str) -> bool {
+    LONG_RUNNING_METHODS.contains(&request_method)
+}
+
+/// queue the method on a worker thread and respond immediately
+pub fn spawn_rpc(request_method: String, request_data: serde_json::Value) -> anyhow::Result<String> {
+    let rpc_id = NEXT_RPC_ID.fetch_add(1, Ordering::Relaxed);
+    {
+        let mut results = RPC_RESULTS.lock().unwrap();
+        results.retain(|_, state| !matches!(state, RpcState::Done(ended, _) if ended.elapsed() > RESULT_TTL));
+        results.insert(rpc_id, RpcState::Running);
+    }
+    RPC_POOL.spawn(move || {
+        let call = || crate::auto_generated_mod::match_request_method_and_call_function(&request_method, request_data);
+        // the panic message is in the terminal, the client gets an error instead of waiting forever
+        let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(call)) {
+            Ok(result) => result,
+            Err(_) => Err(anyhow::anyhow!("The server method {} panicked. See the log in the terminal.", request_method)),
+        };
+        RPC_RESULTS.lock().unwrap().insert(rpc_id, RpcState::Done(Instant::now(), result));
+    });
+    cln_methods::cln_rpc_pending(RpcPendingData { rpc_id }, "")
+}
+
+/// the response of the long method or again cln_rpc_pending
+pub fn take_result(rpc_id: u64) -> anyhow::Result<String> {
+    match take_from(&mut RPC_RESULTS.lock().unwrap(), rpc_id)? {
+        Some(result) => result,
+        None => cln_methods::cln_rpc_pending(RpcPendingData { rpc_id }, ""),
+    }
+}
+
+/// None while running, the result is removed when taken
+fn take_from(results: &mut HashMap<u64, RpcState>, rpc_id: u64) -> anyhow::Result<Option<anyhow::Result<String>>> {
+    match results.remove(&rpc_id) {
+        Some(RpcState::Done(_, result)) => Ok(Some(result)),
+        Some(RpcState::Running) => {
+            results.insert(rpc_id, RpcState::Running);
+            Ok(None)
+        }
+        None => anyhow::bail!("The result of the request {} is not available anymore. Repeat the request.", rpc_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_from() {
+        assert!(is_long_running("srv_cargo_tree_project"));
+        assert!(!is_long_running("srv_rpc_result"));
+        let mut results = HashMap::new();
+        results.insert(1, RpcState::Running);
+        results.insert(2, RpcState::Done(Instant::now(), Ok("response".to_string())));
+        assert!(take_from(&mut results, 1).unwrap().is_none());
+        assert!(take_from(&mut results, 1).unwrap().is_none());
+        assert_eq!(take_from(&mut results, 2).unwrap().unwrap().unwrap(), "response");
+        assert!(take_from(&mut results, 2).is_err());
+    }
+}
@@ -0,0 +1,282 @@
+// source_code_mod.rs
+
+//! read-only source browser for the clean unpacked crate folder
+//! The GUI shows the file tree and the file content with syntax highlighting.
+//! Works without a code editor and over remote port-forwarding.
+//! Only reading: the files in the `cargo registry src` can never be modified from the GUI.
+
+use lazy_static::lazy_static;
+
+use crate::common_structs_mod::*;
+
+/// bigger files are shown only partially
+const MAX_LINES: usize = 20_000;
+/// very long lines are typical for minified files. The highlighter is slow on them.
+const MAX_LINE_LEN_FOR_HIGHLIGHT: usize = 2_000;
+
+lazy_static! {
+    static ref SYNTAX_SET: syntect::parsing::SyntaxSet = syntect::parsing::SyntaxSet::load_defaults_newlines();
+    static ref THEME_SET: syntect::highlighting::ThemeSet = syntect::highlighting::ThemeSet::load_defaults();
+}
+
+/// all files and folders of the clean unpacked crate, sorted like in a file tree
+pub fn source_tree(crate_name: &str, crate_version: &str) -> anyhow::Result<SourceTreeData> {
+    let list_of_file = crate::crev_mod::with_clean_src_folder(crate_name, crate_version, list_of_file)?;
+    Ok(SourceTreeData {
+        crate_name: crate_name.to_string(),
+        crate_version: crate_version.to_string(),
+        list_of_file,
+    })
+}
+
+/// the caller holds the lock of the src folder
+fn list_of_file(src_folder: &std::path::Path) -> anyhow::Result<Vec<SourceFileItemData>> {
+    let mut list_of_file = vec![];
+    for entry in walkdir::WalkDir::new(src_folder).min_depth(1).sort_by_file_name() {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(src_folder)?.to_string_lossy().replace('\\', "/");
+        if relative == ".cargo-ok" {
+            continue;
+        }
+        let is_dir = entry.file_type().is_dir();
+        list_of_file.push(SourceFileItemData {
+            name: entry.file_name().to_string_lossy().to_string(),
+            path: relative,
+            depth: entry.depth() - 1,
+            is_dir,
+            size: if is_dir { 0 } else { entry.metadata()?.len() },
+        });
+    }
+    Ok(list_of_file)
+}
+
+/// the content of one file with every line highlighted as html
+pub fn source_file(crate_name: &str, crate_version: &str, path: &str) -> anyhow::Result<SourceFileData> {
+    let (file_path, content) = crate::crev_mod::with_clean_src_folder(crate_name, crate_version, |src_folder| {
+        let file_path = safe_join(src_folder, path)?;
+        let content = std::fs::read(&file_path)?;
+        Ok((file_path, content))
+    })?;
+    let mut data = SourceFileData {
+        crate_name: crate_name.to_string(),
+        crate_version: crate_version.to_string(),
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let text = match std::str::from_utf8(&content) {
+        Ok(text) if !text.contains('\0') => text,
+        _ => {
+            data.binary = true;
+            return Ok(data);
+        }
+    };
+
+    let syntax = SYNTAX_SET
+        .find_syntax_for_file(&file_path)?
+        .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+    let highlight = text.lines().all(|line| line.len() <= MAX_LINE_LEN_FOR_HIGHLIGHT);
+    data.syntax_name = if highlight { syntax.name.clone() } else { "Plain Text".to_string() };
+    let mut highlighter = syntect::easy::HighlightLines::new(syntax, &THEME_SET.themes["base16-ocean.dark"]);
+    for (i, line) in syntect::util::LinesWithEndings::from(text).enumerate() {
+        if i >= MAX_LINES {
+            data.truncated = true;
+            break;
+        }
+        let html = if highlight {
+            let ranges = highlighter.highlight_line(line, &SYNTAX_SET)?;
+            syntect::html::styled_line_to_highlighted_html(&ranges, syntect::html::IncludeBackground::No)?
+        } else {
+            encode_5_xml_control_characters(line)
+        };
+        data.list_of_line.push(SourceLineItemData {
+            line_number: i + 1,
+            html: html.trim_end_matches(['\n', '\r']).to_string(),
+        });
+    }
+    Ok(data)
+}
+
+/// mark the file as read or unread again
+pub fn toggle_read_file(crate_name: &str, crate_version: &str, path: &str) -> anyhow::Result<ReadProgressData> {
+    let (bytes, lines) = crate::crev_mod::with_clean_src_folder(crate_name, crate_version, |src_folder| {
+        let file_path = safe_join(src_folder, path)?;
+        Ok(text_file_counts(&file_path)?.unwrap_or((std::fs::metadata(&file_path)?.len(), 0)))
+    })?;
+    let crate_name_version = crate::utils_mod::crate_version_join(crate_name, crate_version);
+    crate::db_sled_mod::db_read_file_mod::update(&crate_name_version, |list| match list.iter().position(|x| x.path == path) {
+        Some(pos) => {
+            list.remove(pos);
+        }
+        None => list.push(ReadFileItemData {
+            path: path.to_string(),
+            bytes,
+            lines,
+        }),
+    })?;
+    read_progress(crate_name, crate_version)
+}
+
+/// the percent of lines read in all text files of the crate version
+pub fn read_progress(crate_name: &str, crate_version: &str) -> anyhow::Result<ReadProgressData> {
+    read_progress_of_files(crate_name, crate_version, None)
+}
+
+/// the percent of lines read only in the files changed after the diff base
+/// The removed files cannot be read and are not counted.
+pub fn read_progress_since_diff_base(crate_name: &str, crate_version: &str, diff_base_version: &str) -> anyhow::Result<ReadProgressData> {
+    let crate_diff = crate::crate_diff_mod::crate_diff(crate_name, diff_base_version, crate_version)?;
+    let changed_paths: Vec<String> = crate_diff
+        .files
+        .into_iter()
+        .filter(|x| x.status != crate::crate_diff_mod::FileDiffStatus::Removed)
+        .map(|x| x.path)
+        .collect();
+    read_progress_of_files(crate_name, crate_version, Some(&changed_paths))
+}
+
+/// only_paths limits the counted files, None counts all files
+fn read_progress_of_files(crate_name: &str, crate_version: &str, only_paths: Option<&[String]>) -> anyhow::Result<ReadProgressData> {
+    let crate_name_version = crate::utils_mod::crate_version_join(crate_name, crate_version);
+    let read_files = crate::db_sled_mod::db_read_file_mod::read(&crate_name_version)?.unwrap_or_default();
+    let mut data = ReadProgressData {
+        crate_name: crate_name.to_string(),
+        crate_version: crate_version.to_string(),
+        list_of_read_path: read_files.iter().map(|x| x.path.clone()).collect(),
+        ..Default::default()
+    };
+    crate::crev_mod::with_clean_src_folder(crate_name, crate_version, |src_folder| {
+        for file in list_of_file(src_folder)?
+            .iter()
+            .filter(|x| !x.is_dir && only_paths.map_or(true, |only_paths| only_paths.contains(&x.path)))
+        {
+            // binary files cannot be read and are not counted
+            if let Some((bytes, lines)) = text_file_counts(&src_folder.join(&file.path))? {
+                data.files_total += 1;
+                data.bytes_total += bytes;
+                data.lines_total += lines;
+                if data.list_of_read_path.contains(&file.path) {
+                    data.files_read += 1;
+                    data.bytes_read += bytes;
+                    data.lines_read += lines;
+                }
+            }
+        }
+        Ok(())
+    })?;
+    data.percent_read = percent(data.lines_read, data.lines_total);
+    data.suggested_thoroughness = suggested_thoroughness(data.percent_read).to_string();
+    Ok(data)
+}
+
+/// bytes and lines of a text file, None for binary files
+fn text_file_counts(file_path: &std::path::Path) -> anyhow::Result<Option<(u64, usize)>> {
+    let content = std::fs::read(file_path)?;
+    match std::str::from_utf8(&content) {
+        Ok(text) if !text.contains('\0') => Ok(Some((content.len() as u64, text.lines().count()))),
+        _ => Ok(None),
+    }
+}
+
+/// rounded down, so 100% means really everything
+fn percent(part: usize, total: usize) -> usize {
+    (part * 100).checked_div(total).unwrap_or(0)
+}
+
+/// the thoroughness claimed in the review must match the code really read
+pub fn suggested_thoroughness(percent_read: usize) -> &'static str {
+    match percent_read {
+        100.. => "high",
+        50..=99 => "medium",
+        10..=49 => "low",
+        _ => "none",
+    }
+}
+
+/// the levels are ordered none < low < medium < high
+pub fn thoroughness_rank(thoroughness: &str) -> usize {
+    match thoroughness {
+        "low" => 1,
+        "medium" => 2,
+        "high" => 3,
+        _ => 0,
+    }
+}
+
+/// the thoroughness cannot claim more than the code really read
+/// A differential review counts only the files changed after the diff base.
+/// The thoroughness unchanged from the stored review is not checked again, so the review can be edited.
+pub fn check_thoroughness(
+    crate_name: &str,
+    crate_version: &str,
+    thoroughness: &str,
+    diff_base_version: &str,
+    stored_thoroughness: Option<&str>,
+) -> anyhow::Result<()> {
+    if stored_thoroughness == Some(thoroughness) {
+        return Ok(());
+    }
+    let progress = if diff_base_version.is_empty() {
+        read_progress(crate_name, crate_version)?
+    } else {
+        read_progress_since_diff_base(crate_name, crate_version, diff_base_version)?
+    };
+    if thoroughness_rank(thoroughness) > thoroughness_rank(&progress.suggested_thoroughness) {
+        anyhow::bail!(
+            "The thoroughness {} is more than the suggested {}. You marked as read {}% of the lines of code. Mark the files as read in the source browser or lower the thoroughness.",
+            thoroughness,
+            progress.suggested_thoroughness,
+            progress.percent_read
+        );
+    }
+    Ok(())
+}
+
+/// plain text lines must be escaped like the highlighted ones
+fn encode_5_xml_control_characters(input: &str) -> String {
+    input.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+/// join the relative path from the browser to the crate folder
+/// Only normal path components are allowed, so the path cannot escape the folder with `..`, root or prefix.
+/// Symlinks that point outside of the folder are also refused.
+pub fn safe_join(folder: &std::path::Path, relative_path: &str) -> anyhow::Result<std::path::PathBuf> {
+    let relative = std::path::Path::new(relative_path);
+    if relative_path.is_empty() || !relative.components().all(|x| matches!(x, std::path::Component::Normal(_))) {
+        anyhow::bail!("Invalid path {}", relative_path);
+    }
+    let joined = folder.join(relative).canonicalize()?;
+    if !joined.starts_with(folder.canonicalize()?) {
+        anyhow::bail!("Invalid path {}", relative_path);
+    }
+    Ok(joined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_join() {
+        let folder = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(folder.path().join("src")).unwrap();
+        std::fs::write(folder.path().join("src/lib.rs"), "").unwrap();
+
+        assert!(safe_join(folder.path(), "src/lib.rs").is_ok());
+        assert!(safe_join(folder.path(), "src/../src/lib.rs").is_err());
+        assert!(safe_join(folder.path(), "../etc/passwd").is_err());
+        assert!(safe_join(folder.path(), "/etc/passwd").is_err());
+        assert!(safe_join(folder.path(), "").is_err());
+    }
+
+    #[test]
+    fn test_suggested_thoroughness() {
+        assert_eq!(percent(0, 0), 0);
+        assert_eq!(percent(999, 1000), 99);
+        assert_eq!(suggested_thoroughness(percent(0, 1000)), "none");
+        assert_eq!(suggested_thoroughness(percent(100, 1000)), "low");
+        assert_eq!(suggested_thoroughness(percent(500, 1000)), "medium");
+        assert_eq!(suggested_thoroughness(percent(999, 1000)), "medium");
+        assert_eq!(suggested_thoroughness(percent(1000, 1000)), "high");
+        assert!(thoroughness_rank("high") > thoroughness_rank("medium"));
+    }
+}
@@ -5,4 +5,5 @@
 
 pub mod srv_publisher_mod;
 pub mod srv_review_mod;
+pub mod srv_source_mod;
 pub mod srv_utils_mod;
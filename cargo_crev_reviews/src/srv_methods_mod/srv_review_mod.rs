@@ -9,7 +9,6 @@ use crate::crev_mod::*;
 use anyhow::Context;
 use function_name::named;
 use std::str::FromStr;
-use std::time::Duration;
 use unwrap::unwrap;
 
 /// maybe add filter for one crate_name
@@ -38,11 +37,17 @@ pub fn srv_review_new(request_data: serde_json::Value) -> anyhow::Result<String>
     let filter: ReviewFilterData = unwrap!(serde_json::from_value(request_data));
 
     let response_html = crate::html_mod::process_include(&crate::auto_generated_files_mod::get_file_text("/cargo_crev_reviews/review_new.html"));
+    let crate_version = filter.crate_version.context("none version")?;
+    // the thoroughness is suggested from the files marked as read
+    let thoroughness = match crate::source_code_mod::read_progress(&filter.crate_name, &crate_version) {
+        Ok(progress) => progress.suggested_thoroughness,
+        Err(_err) => "none".to_string(),
+    };
     let response_data = ReviewItemData {
         crate_name: filter.crate_name.to_string(),
-        crate_version: filter.crate_version.context("none version")?.to_string(),
+        crate_version,
         date: "".to_string(),
-        thoroughness: "none".to_string(),
+        thoroughness,
         understanding: "none".to_string(),
         rating: "neutral".to_string(),
         comment_md: r#"Try to comment important aspects: 
@@ -56,15 +61,60 @@ used in projects:
 alternative crates explored:
         "#
         .to_string(),
+        issues: vec![],
+        advisories: vec![],
+        diff_base_version: "".to_string(),
+        suggested_diff_base_version: String::new(),
+        source: if filter.source.is_empty() {
+            project_source(&filter.crate_name, &crate_version)
+        } else {
+            filter.source.clone()
+        },
     };
+    let response_data = with_suggested_diff_base(response_data);
     cln_methods::cln_review_new(response_data, &response_html)
 }
 
+/// only crates.io reviews can be differential
+fn with_suggested_diff_base(mut review_item: ReviewItemData) -> ReviewItemData {
+    if crate::package_source_mod::is_crates_io(&review_item.source) {
+        review_item.suggested_diff_base_version = suggested_diff_base_version(&review_item.crate_name, &review_item.crate_version).unwrap_or_default();
+    }
+    review_item
+}
+
+/// the crev source if the current project uses the crate version from git, path or an alternative registry, else empty for crates.io
+fn project_source(crate_name: &str, crate_version: &str) -> String {
+    match crate::package_source_mod::crev_source_in_project(crate_name, crate_version) {
+        Ok(source) => source.unwrap_or_default(),
+        Err(_err) => String::new(),
+    }
+}
+
 #[named]
 pub fn srv_review_save(request_data: serde_json::Value) -> anyhow::Result<String> {
     log::info!(function_name!());
 
     let p: ReviewItemData = unwrap!(serde_json::from_value(request_data));
+    // the source browser shows only crates from crates.io
+    if crate::package_source_mod::is_crates_io(&p.source) {
+        let filter = ReviewFilterData {
+            crate_name: p.crate_name.clone(),
+            crate_version: Some(p.crate_version.clone()),
+            old_crate_version: None,
+            source: p.source.clone(),
+        };
+        let stored_thoroughness = crev_edit_review(filter).ok().and_then(|x| x.review).map(|x| x.thoroughness.to_string());
+        if let Err(err) = crate::source_code_mod::check_thoroughness(
+            &p.crate_name,
+            &p.crate_version,
+            &p.thoroughness,
+            &p.diff_base_version,
+            stored_thoroughness.as_deref(),
+        ) {
+            return crate::response_post_mod::response_modal_message(&err.to_string());
+        }
+    }
 
     match crev_save_review(
         &p.crate_name,
@@ -73,6 +123,10 @@ pub fn srv_review_save(request_data: serde_json::Value) -> anyhow::Result<String
         crev_data::Level::from_str(&p.understanding)?,
         rating_parse(&p.rating)?,
         &p.comment_md,
+        &p.issues,
+        &p.advisories,
+        &p.diff_base_version,
+        &p.source,
     ) {
         Err(err) => Err(err),
         Ok(()) => crate::response_post_mod::response_modal_message("Review saved."),
@@ -84,6 +138,7 @@ fn request_review_list() -> anyhow::Result<String> {
         crate_name: String::new(),
         crate_version: None,
         old_crate_version: None,
+        source: String::new(),
     };
     let request_data = unwrap!(serde_json::to_value(request_data));
     srv_reviews_list(request_data)
@@ -95,7 +150,7 @@ pub fn srv_review_edit(request_data: serde_json::Value) -> anyhow::Result<String
     let filter: ReviewFilterData = unwrap!(serde_json::from_value(request_data));
     // find the item from the list
     let p = crev_edit_review(filter)?;
-    let response_data = crate::utils_mod::from_crev_to_item(&p);
+    let response_data = with_suggested_diff_base(crate::utils_mod::from_crev_to_item(&p));
     let response_html = crate::html_mod::process_include(&crate::auto_generated_files_mod::get_file_text("/cargo_crev_reviews/review_edit.html"));
 
     cln_methods::cln_review_edit(response_data, &response_html)
@@ -105,12 +160,21 @@ pub fn srv_review_edit(request_data: serde_json::Value) -> anyhow::Result<String
 #[named]
 pub fn srv_review_edit_or_new(request_data: serde_json::Value) -> anyhow::Result<String> {
     log::info!(function_name!());
-    let filter: ReviewFilterData = unwrap!(serde_json::from_value(request_data.clone()));
+    let mut filter: ReviewFilterData = unwrap!(serde_json::from_value(request_data.clone()));
+    // the project can use this version from git, path or an alternative registry
+    if filter.source.is_empty() {
+        filter.source = project_source(&filter.crate_name, filter.crate_version.as_deref().unwrap_or_default());
+    }
 
     match crev_edit_or_new_review(filter) {
         Err(_err) => srv_review_new(request_data),
         Ok(p) => {
-            let response_data = crate::utils_mod::from_crev_to_item(&p);
+            let mut response_data = crate::utils_mod::from_crev_to_item(&p);
+            // only crates.io reviews can be differential
+            if !response_data.source.is_empty() {
+                response_data.diff_base_version = String::new();
+            }
+            let response_data = with_suggested_diff_base(response_data);
             let response_html = crate::html_mod::process_include(&crate::auto_generated_files_mod::get_file_text("/cargo_crev_reviews/review_edit.html"));
             cln_methods::cln_review_edit(response_data, &response_html)
         }
@@ -123,7 +187,7 @@ pub fn srv_review_new_version(request_data: serde_json::Value) -> anyhow::Result
     let filter: ReviewFilterData = unwrap!(serde_json::from_value(request_data));
     // find the item from the list
     let p = crev_new_version(filter)?;
-    let response_data = crate::utils_mod::from_crev_to_item(&p);
+    let response_data = with_suggested_diff_base(crate::utils_mod::from_crev_to_item(&p));
     let response_html = crate::html_mod::process_include(&crate::auto_generated_files_mod::get_file_text("/cargo_crev_reviews/review_edit.html"));
     cln_methods::cln_review_edit(response_data, &response_html)
 }
@@ -149,46 +213,60 @@ pub fn srv_review_open_source_code(request_data: serde_json::Value) -> anyhow::R
     let crate_name = filter.crate_name;
     let crate_version = filter.crate_version.context("Parameter version in None.")?;
 
-    let mut cache_crate_file = crate::cargo_registry_mod::cargo_registry_cache_file_for_crate(&crate_name, &crate_version);
-    if !cache_crate_file.exists() {
-        // download into temp folder and change PathBuf
-        cache_crate_file = crate::cargo_registry_mod::download_crate_from_crate_io(&crate_name, &crate_version)?;
-    }
-    log::info!("Unpack and open source code from {:#?}", &cache_crate_file);
-
-    // unpack the .crate into temp directory
-    // ~\.cache\cargo_crev_reviews\src
-    let temp_path_dir = crate::cargo_registry_mod::cargo_crev_reviews_src_dir_for_crate(&crate_name, &crate_version);
-    if temp_path_dir.exists() {
-        std::fs::remove_dir_all(&temp_path_dir)?;
-    }
-    crate::cargo_registry_mod::unpack_from_targz_to_folder(&crate_name, &crate_version, cache_crate_file.parent().unwrap(), temp_path_dir.parent().unwrap())?;
-    log::info!("Unpacked into {:#?}", &temp_path_dir);
-
     let config = unwrap!(crate::get_config());
-    // test if the `/usr/bin/code` exists.
-    if !std::path::Path::new(&config.code_editor_path).exists() {
-        log::error!(
-            "The editor `{}` does not exist. Change it in the config menu. Or open manually the directory `{}`.",
-            &config.code_editor_path,
-            unwrap!(temp_path_dir.to_str())
-        );
-    } else {
-        let mut child = std::process::Command::new(&config.code_editor_path).arg(&temp_path_dir).spawn()?;
-        std::thread::sleep(Duration::new(1, 0));
-        child.kill()?;
-    }
-    // return nothing
-    crate::response_post_mod::response_no_action()
+    // the .crate from the cargo registry cache or downloaded, verified with the registry index checksum
+    // and unpacked into the temp directory ~\.cache\cargo_crev_reviews\src, a clean folder is reused
+    crate::crev_mod::with_clean_temp_src_folder(&crate_name, &crate_version, |temp_path_dir| {
+        log::info!("Open source code in {:#?}", temp_path_dir);
+        // test if the `/usr/bin/code` exists.
+        if !std::path::Path::new(&config.code_editor_path).exists() {
+            return crate::response_post_mod::response_modal_message(&format!(
+                "The editor `{}` does not exist. Change it in the config menu, use the built-in source browser or open manually the directory `{}`.",
+                &config.code_editor_path,
+                temp_path_dir.to_string_lossy()
+            ));
+        }
+        // The editor stays open. The thread waits for the child to exit, so it does not remain a zombie process.
+        let mut child = std::process::Command::new(&config.code_editor_path).arg(temp_path_dir).spawn()?;
+        std::thread::spawn(move || {
+            if let Err(err) = child.wait() {
+                log::error!("code editor: {}", err);
+            }
+        });
+        // return nothing
+        crate::response_post_mod::response_no_action()
+    })
 }
 
 #[named]
 pub fn srv_review_delete(filter_data: serde_json::Value) -> anyhow::Result<String> {
     log::info!(function_name!());
 
-    let filter: ReviewFilterData = unwrap!(serde_json::from_value(filter_data));
-    let version = filter.crate_version.context("Parameter version in None.")?;
-    crate::crev_mod::delete_review_proofs(filter.crate_name.as_str(), &version)?;
+    let p: ReviewDeleteData = unwrap!(serde_json::from_value(filter_data));
+    // only the review of the row, not the reviews of the same version from other sources
+    crate::crev_mod::delete_review_proofs(&p.crate_name, &p.crate_version, Some(&p.source))?;
 
     request_review_list()
 }
+
+/// modal to edit one issue of the review. The review is edited in the browser and saved together.
+#[named]
+pub fn srv_issue_edit(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+
+    let response_data: IssueEditData = serde_json::from_value(request_data)?;
+    let response_html = crate::html_mod::process_include(&crate::auto_generated_files_mod::get_file_text("/cargo_crev_reviews/issue_edit.html"));
+
+    cln_methods::cln_issue_edit_modal(response_data, &response_html)
+}
+
+/// modal to edit one advisory of the review. The review is edited in the browser and saved together.
+#[named]
+pub fn srv_advisory_edit(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+
+    let response_data: AdvisoryEditData = serde_json::from_value(request_data)?;
+    let response_html = crate::html_mod::process_include(&crate::auto_generated_files_mod::get_file_text("/cargo_crev_reviews/advisory_edit.html"));
+
+    cln_methods::cln_advisory_edit_modal(response_data, &response_html)
+}
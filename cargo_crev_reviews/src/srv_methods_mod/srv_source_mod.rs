@@ -0,0 +1,164 @@
+// srv_source_mod.rs
+
+//! rpc methods prepare the data to respond the POST rpc requests
+
+use crate::auto_generated_mod::cln_methods;
+use crate::common_structs_mod::*;
+
+use anyhow::Context;
+use function_name::named;
+
+/// the file tree of the clean unpacked crate
+#[named]
+pub fn srv_source_tree(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let filter: ReviewFilterData = serde_json::from_value(request_data)?;
+    let crate_version = filter.crate_version.context("Parameter version in None.")?;
+
+    let response_data = crate::source_code_mod::source_tree(&filter.crate_name, &crate_version)?;
+    let response_html = crate::html_mod::process_include(&crate::auto_generated_files_mod::get_file_text("/cargo_crev_reviews/source_tree.html"));
+
+    cln_methods::cln_source_tree(response_data, &response_html)
+}
+
+/// one highlighted file. The html is already in the page, the response has only the data.
+#[named]
+pub fn srv_source_file(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let filter: SourceFileFilterData = serde_json::from_value(request_data)?;
+
+    let response_data = crate::source_code_mod::source_file(&filter.crate_name, &filter.crate_version, &filter.path)?;
+
+    cln_methods::cln_source_file(response_data, "")
+}
+
+/// the draft annotations of one crate version. The response has only the data.
+#[named]
+pub fn srv_annotation_list(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let filter: ReviewFilterData = serde_json::from_value(request_data)?;
+    let crate_version = filter.crate_version.context("Parameter version in None.")?;
+
+    let response_data = annotation_list_data(&filter.crate_name, &crate_version)?;
+
+    cln_methods::cln_annotation_list(response_data, "")
+}
+
+/// modal to edit one annotation
+#[named]
+pub fn srv_annotation_edit(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+
+    let response_data: AnnotationEditData = serde_json::from_value(request_data)?;
+    let response_html = crate::html_mod::process_include(&crate::auto_generated_files_mod::get_file_text("/cargo_crev_reviews/annotation_edit.html"));
+
+    cln_methods::cln_annotation_edit_modal(response_data, &response_html)
+}
+
+/// insert or replace one annotation in the draft
+#[named]
+pub fn srv_annotation_save(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let data: AnnotationEditData = serde_json::from_value(request_data)?;
+    let mut annotation = data.annotation;
+    if annotation.note.trim().is_empty() {
+        return crate::response_post_mod::response_modal_message("The note is mandatory.");
+    }
+    if annotation.path.is_empty() || annotation.line_start == 0 {
+        return crate::response_post_mod::response_modal_message("The annotation needs a file and a line.");
+    }
+    if annotation.line_end < annotation.line_start {
+        annotation.line_end = annotation.line_start;
+    }
+
+    let crate_name_version = crate::utils_mod::crate_version_join(&data.crate_name, &data.crate_version);
+    let mut list = crate::db_sled_mod::db_annotation_mod::read(&crate_name_version)?.unwrap_or_default();
+    match data.row_number {
+        Some(row_number) if row_number < list.len() => list[row_number] = annotation,
+        _ => list.push(annotation),
+    }
+    crate::db_sled_mod::db_annotation_mod::insert(&crate_name_version, &list)?;
+
+    let response_data = annotation_list_data(&data.crate_name, &data.crate_version)?;
+    cln_methods::cln_annotation_list(response_data, "")
+}
+
+/// remove one annotation from the draft
+#[named]
+pub fn srv_annotation_delete(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let data: AnnotationEditData = serde_json::from_value(request_data)?;
+    let row_number = data.row_number.context("Parameter row_number in None.")?;
+
+    let crate_name_version = crate::utils_mod::crate_version_join(&data.crate_name, &data.crate_version);
+    let mut list = crate::db_sled_mod::db_annotation_mod::read(&crate_name_version)?.unwrap_or_default();
+    if row_number < list.len() {
+        list.remove(row_number);
+    }
+    // the empty list stays in the db, so the next save removes the section from the comment
+    crate::db_sled_mod::db_annotation_mod::insert(&crate_name_version, &list)?;
+
+    let response_data = annotation_list_data(&data.crate_name, &data.crate_version)?;
+    cln_methods::cln_annotation_list(response_data, "")
+}
+
+fn annotation_list_data(crate_name: &str, crate_version: &str) -> anyhow::Result<AnnotationListData> {
+    let crate_name_version = crate::utils_mod::crate_version_join(crate_name, crate_version);
+    Ok(AnnotationListData {
+        crate_name: crate_name.to_string(),
+        crate_version: crate_version.to_string(),
+        list_of_annotation: crate::db_sled_mod::db_annotation_mod::read(&crate_name_version)?.unwrap_or_default(),
+    })
+}
+
+/// percent of code read and the suggested thoroughness. The response has only the data.
+#[named]
+pub fn srv_read_progress(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let filter: ReviewFilterData = serde_json::from_value(request_data)?;
+    let crate_version = filter.crate_version.context("Parameter version in None.")?;
+
+    let response_data = crate::source_code_mod::read_progress(&filter.crate_name, &crate_version)?;
+
+    cln_methods::cln_read_progress(response_data, "")
+}
+
+/// mark the file as read or unread
+#[named]
+pub fn srv_source_mark_read(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let filter: SourceFileFilterData = serde_json::from_value(request_data)?;
+
+    let response_data = crate::source_code_mod::toggle_read_file(&filter.crate_name, &filter.crate_version, &filter.path)?;
+
+    cln_methods::cln_read_progress(response_data, "")
+}
+
+/// dangerous constructs in the source code for the review editor. The response has only the data.
+#[named]
+pub fn srv_risk_scan(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let filter: ReviewFilterData = serde_json::from_value(request_data)?;
+    let crate_version = filter.crate_version.context("Parameter version in None.")?;
+
+    let response_data = crate::risk_scan_mod::risk_scan(&filter.crate_name, &crate_version)?;
+
+    cln_methods::cln_risk_scan(response_data, "")
+}
+
+/// compare the package with its git commit in a local clone. The response has only the data.
+#[named]
+pub fn srv_vcs_verify(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let filter: VcsVerifyFilterData = serde_json::from_value(request_data)?;
+    if filter.repo_path.trim().is_empty() {
+        return crate::response_post_mod::response_modal_message("Write the path of the local clone of the repository.");
+    }
+
+    let response_data = match crate::vcs_verify_mod::vcs_verify(&filter.crate_name, &filter.crate_version, filter.repo_path.trim()) {
+        Ok(data) => data,
+        Err(err) => return crate::response_post_mod::response_modal_message(&err.to_string()),
+    };
+
+    cln_methods::cln_vcs_verify(response_data, "")
+}
@@ -6,12 +6,17 @@ use crate::auto_generated_mod::cln_methods;
 use crate::common_structs_mod::*;
 use crate::crev_mod::*;
 
+use anyhow::Context;
 use function_name::named;
 use unwrap::unwrap;
 
+/// the browser is slow with very big html
+const MAX_DIFF_ROWS: usize = 20_000;
+
 #[named]
 pub fn srv_cargo_tree_project(_filter_data: serde_json::Value) -> anyhow::Result<String> {
     log::info!(function_name!());
+    let _progress = crate::progress_mod::progress_guard(crate::progress_mod::CARGO_TREE);
 
     let response_data = crate::cargo_tree_mod::cargo_tree_project()?;
     let response_html = crate::html_mod::process_include(&crate::auto_generated_files_mod::get_file_text("/cargo_crev_reviews/cargo_tree.html"));
@@ -39,9 +44,29 @@ pub fn srv_version_list(request_data: serde_json::Value) -> anyhow::Result<Strin
     cln_methods::cln_version_list(response_data, &response_html)
 }
 
+/// side-by-side diff between two versions of a crate
+/// crate_version is the new version and old_crate_version is the old version
+#[named]
+pub fn srv_version_diff(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let filter: ReviewFilterData = serde_json::from_value(request_data)?;
+    let mut new_version = filter.crate_version.context("none version")?;
+    let mut old_version = filter.old_crate_version.context("none old version")?;
+    if semver::Version::parse(&old_version)? > semver::Version::parse(&new_version)? {
+        std::mem::swap(&mut old_version, &mut new_version);
+    }
+
+    let crate_diff = crate::crate_diff_mod::crate_diff(&filter.crate_name, &old_version, &new_version)?;
+    let response_data = crate_diff.to_version_diff_data(MAX_DIFF_ROWS);
+    let response_html = crate::html_mod::process_include(&crate::auto_generated_files_mod::get_file_text("/cargo_crev_reviews/version_diff.html"));
+
+    cln_methods::cln_version_diff(response_data, &response_html)
+}
+
 #[named]
 pub fn srv_update_registry_index(_request_data: serde_json::Value) -> anyhow::Result<String> {
     log::info!(function_name!());
+    let _progress = crate::progress_mod::progress_guard(crate::progress_mod::UPDATE_REGISTRY_INDEX);
     match crate::cargo_registry_mod::update_registry_index() {
         Ok(_ret_val) => crate::response_post_mod::response_modal_message("Registry index updated."),
         Err(err) => crate::response_post_mod::response_err_message(&err),
@@ -72,6 +97,7 @@ pub fn srv_config_save(request_data: serde_json::Value) -> anyhow::Result<String
 #[named]
 pub fn srv_list_unclean_crates(_request_data: serde_json::Value) -> anyhow::Result<String> {
     log::info!(function_name!());
+    let _progress = crate::progress_mod::progress_guard(crate::progress_mod::LIST_UNCLEAN_CRATES);
     let mut list = crate::cargo_registry_mod::list_unclean_crates()?;
     log::info!("srv_list_unclean_crates() finished.");
     if list.is_empty() {
@@ -88,6 +114,7 @@ pub fn srv_list_unclean_crates(_request_data: serde_json::Value) -> anyhow::Resu
 #[named]
 pub fn srv_correct_digest(_request_data: serde_json::Value) -> anyhow::Result<String> {
     log::info!(function_name!());
+    let _progress = crate::progress_mod::progress_guard(crate::progress_mod::CORRECT_DIGEST);
     let mut ret_string = String::new();
     let mut num_of_corrected = 0;
 
@@ -95,12 +122,20 @@ pub fn srv_correct_digest(_request_data: serde_json::Value) -> anyhow::Result<St
     let mut vec_proof = unwrap!(crev_list_my_reviews(&None));
     crev_sort_list_by_name_version(&mut vec_proof);
     let num_of_all = vec_proof.len();
-    for p in vec_proof.iter() {
+    use crate::progress_mod::{progress, progress_error, CORRECT_DIGEST};
+    // the digest of git, path and alternative registry sources is calculated only when saving the review
+    let vec_proof: Vec<_> = vec_proof.iter().filter(|p| crate::package_source_mod::is_crates_io(&p.package.source)).collect();
+    for (i, p) in vec_proof.iter().enumerate() {
         let crate_name = p.package.name.clone();
         let crate_version = p.package.version.clone();
         log::info!("check digest: {} {}", &crate_name, &crate_version);
+        let crate_name_version = crate::utils_mod::crate_version_join(&crate_name, &crate_version);
+        progress(CORRECT_DIGEST, i + 1, vec_proof.len(), &crate_name_version);
         match calculate_crate_digest(&p.package.name, &p.package.version) {
-            Err(err) => ret_string.push_str(&format!("{}\n", &err)),
+            Err(err) => {
+                progress_error(CORRECT_DIGEST, &crate_name_version, &err.to_string());
+                ret_string.push_str(&format!("{}\n", &err))
+            }
             Ok(digest) => {
                 if p.package.digest != digest.to_string() {
                     // review can be optional in cargo-crev, but cargo_crev_reviews saves it always.
@@ -119,7 +154,11 @@ pub fn srv_correct_digest(_request_data: serde_json::Value) -> anyhow::Result<St
                         thoroughness,
                         understanding,
                         rating,
-                        &p.comment.as_deref().unwrap_or("")
+                        &p.comment.as_deref().unwrap_or(""),
+                        &p.issues,
+                        &p.advisories,
+                        p.diff_base.as_ref().map(|x| x.version.as_str()).unwrap_or(""),
+                        ""
                     ));
                     num_of_corrected += 1;
                 }
@@ -139,3 +178,47 @@ pub fn srv_correct_digest(_request_data: serde_json::Value) -> anyhow::Result<St
     }
     crate::response_post_mod::response_modal_message(&ret_string)
 }
+
+/// list the proofs in my reviews directory that are corrupt, unsigned or signed by a foreign crev id
+#[named]
+pub fn srv_proof_problem_list(_request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let content = crate::proof_store_mod::read_my_proof_store()?;
+    let response_data = ProofProblemListData {
+        reviews_dir: reviews_dir()?,
+        list_of_problem: content.problems,
+    };
+    let response_html = crate::html_mod::process_include(&crate::auto_generated_files_mod::get_file_text("/cargo_crev_reviews/proof_problem_list.html"));
+
+    cln_methods::cln_proof_problem_list(response_data, &response_html)
+}
+
+/// the client asks again for the result of a long method
+#[named]
+pub fn srv_rpc_result(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::debug!(function_name!());
+    let pending: RpcPendingData = serde_json::from_value(request_data)?;
+    crate::rpc_worker_mod::take_result(pending.rpc_id)
+}
+
+/// background jobs of this run and the last failures also from the previous runs
+#[named]
+pub fn srv_jobs_list(_request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let response_data = JobListData {
+        list_of_job: crate::jobs_mod::list_jobs(),
+        list_of_failure: crate::db_sled_mod::db_job_failure_mod::list()?,
+    };
+    let response_html = crate::html_mod::process_include(&crate::auto_generated_files_mod::get_file_text("/cargo_crev_reviews/jobs_list.html"));
+
+    cln_methods::cln_jobs_list(response_data, &response_html)
+}
+
+/// cancel a queued job and refresh the list
+#[named]
+pub fn srv_job_cancel(request_data: serde_json::Value) -> anyhow::Result<String> {
+    log::info!(function_name!());
+    let filter: JobFilterData = serde_json::from_value(request_data)?;
+    crate::jobs_mod::cancel_job(filter.job_id)?;
+    srv_jobs_list(serde_json::Value::Null)
+}
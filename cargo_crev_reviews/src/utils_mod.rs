@@ -105,5 +105,10 @@ pub fn from_crev_to_item(p: &crev_mod::ProofCrevForReview) -> common_structs_mod
         understanding,
         rating,
         comment_md: p.comment.as_ref().unwrap_or(&"".to_string()).clone(),
+        issues: p.issues.clone(),
+        advisories: p.advisories.clone(),
+        diff_base_version: p.diff_base.as_ref().map(|x| x.version.clone()).unwrap_or_default(),
+        suggested_diff_base_version: String::new(),
+        source: if crate::package_source_mod::is_crates_io(&p.package.source) { String::new() } else { p.package.source.clone() },
     }
 }
@@ -0,0 +1,145 @@
+// vcs_verify_mod.rs
+
+//! verify a published .crate against its upstream git commit in a local clone
+//! The file `.cargo_vcs_info.json` in the package has the git sha1 and the `path_in_vcs`.
+//! The files of that commit are read with `git archive`, so the working tree of the clone is never touched.
+//! Files that exist only in the package or differ from the repository are the ones to review in the package.
+
+use anyhow::Context;
+use std::collections::BTreeMap;
+use std::io::Read;
+
+use crate::common_structs_mod::*;
+
+/// files that cargo adds or rewrites when packaging or unpacking. `Cargo.toml.orig` is compared instead of `Cargo.toml`.
+const GENERATED_FILES: [&str; 4] = [".cargo-ok", ".cargo_vcs_info.json", "Cargo.toml", "Cargo.lock"];
+
+/// compare the clean unpacked crate with the commit in the local clone
+pub fn vcs_verify(crate_name: &str, crate_version: &str, repo_path: &str) -> anyhow::Result<VcsVerifyData> {
+    let (vcs_info, package_files) = crate::crev_mod::with_clean_src_folder(crate_name, crate_version, |src_folder| {
+        let vcs_info = crate::crev_mod::VcsInfoJson::read_from_crate_dir(src_folder)?
+            .context("The package has no .cargo_vcs_info.json. It was published without git information.")?;
+        Ok((vcs_info, read_folder(src_folder)?))
+    })?;
+    let repo_files = read_git_commit(std::path::Path::new(repo_path), &vcs_info.git.sha1, &vcs_info.path_in_vcs)?;
+    let list_of_file = compare_files(&package_files, &repo_files, &vcs_info.path_in_vcs);
+    Ok(VcsVerifyData {
+        crate_name: crate_name.to_string(),
+        crate_version: crate_version.to_string(),
+        repo_path: repo_path.to_string(),
+        git_sha1: vcs_info.git.sha1,
+        path_in_vcs: vcs_info.path_in_vcs,
+        dirty: vcs_info.git.dirty,
+        files_checked: package_files.keys().filter(|x| !GENERATED_FILES.contains(&x.as_str())).count(),
+        list_of_file,
+    })
+}
+
+/// the files of the commit inside `path_in_vcs` and in the repository root, with the path relative to the repository root
+/// Cargo copies the readme and license from the workspace root into the package, so the root is also needed.
+fn read_git_commit(repo_path: &std::path::Path, git_sha1: &str, path_in_vcs: &str) -> anyhow::Result<BTreeMap<String, Vec<u8>>> {
+    if !repo_path.join(".git").exists() {
+        anyhow::bail!("The folder {} is not a git clone.", repo_path.to_string_lossy());
+    }
+    let object = format!("{}^{{commit}}", git_sha1);
+    let output = std::process::Command::new("git").args(["cat-file", "-e", &object]).current_dir(repo_path).output()?;
+    if !output.status.success() {
+        anyhow::bail!("The commit {} is not in the clone {}. Run `git fetch` in the clone.", git_sha1, repo_path.to_string_lossy());
+    }
+    let output = std::process::Command::new("git").args(["archive", "--format=tar", git_sha1]).current_dir(repo_path).output()?;
+    if !output.status.success() {
+        anyhow::bail!("git archive failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    let prefix = if path_in_vcs.is_empty() { String::new() } else { format!("{}/", path_in_vcs.trim_end_matches('/')) };
+    let mut files = BTreeMap::new();
+    let mut archive = tar::Archive::new(&output.stdout[..]);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        // symlinks and submodules are not packaged as such by cargo
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?.to_string_lossy().replace('\\', "/");
+        if path.starts_with(&prefix) || !path.contains('/') {
+            let mut content = vec![];
+            entry.read_to_end(&mut content)?;
+            files.insert(path, content);
+        }
+    }
+    Ok(files)
+}
+
+/// all files of the folder, with the path relative to the folder
+fn read_folder(folder: &std::path::Path) -> anyhow::Result<BTreeMap<String, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+    for entry in walkdir::WalkDir::new(folder).min_depth(1) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            let path = entry.path().strip_prefix(folder)?.to_string_lossy().replace('\\', "/");
+            files.insert(path, std::fs::read(entry.path())?);
+        }
+    }
+    Ok(files)
+}
+
+/// the package files that are not exactly the same in the repository, sorted by path
+/// Files in the repository that are not in the package are excluded from the package and are not interesting.
+pub fn compare_files(package_files: &BTreeMap<String, Vec<u8>>, repo_files: &BTreeMap<String, Vec<u8>>, path_in_vcs: &str) -> Vec<VcsFileItemData> {
+    let prefix = if path_in_vcs.is_empty() { String::new() } else { format!("{}/", path_in_vcs.trim_end_matches('/')) };
+    let mut list_of_file = vec![];
+    for (path, content) in package_files.iter() {
+        if GENERATED_FILES.contains(&path.as_str()) {
+            continue;
+        }
+        // cargo keeps the original manifest as Cargo.toml.orig
+        let repo_name = if path == "Cargo.toml.orig" { "Cargo.toml" } else { path.as_str() };
+        let repo_content = repo_files.get(&format!("{}{}", prefix, repo_name)).or_else(|| {
+            if repo_name.contains('/') {
+                None
+            } else {
+                repo_files.get(repo_name)
+            }
+        });
+        let status = match repo_content {
+            None => "only_in_package",
+            Some(repo_content) if repo_content != content => "differs",
+            Some(_) => continue,
+        };
+        list_of_file.push(VcsFileItemData {
+            path: path.to_string(),
+            status: status.to_string(),
+        });
+    }
+    list_of_file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_files() {
+        let files = |list: &[(&str, &str)]| list.iter().map(|(k, v)| (k.to_string(), v.as_bytes().to_vec())).collect::<BTreeMap<String, Vec<u8>>>();
+        let package_files = files(&[
+            (".cargo_vcs_info.json", "{}"),
+            ("Cargo.toml", "# normalized"),
+            ("Cargo.toml.orig", "[package]"),
+            ("README.md", "readme"),
+            ("src/lib.rs", "fn f() {}"),
+            ("src/evil.rs", "fn g() {}"),
+            ("src/main.rs", "fn main() { changed() }"),
+        ]);
+        let repo_files = files(&[
+            ("README.md", "readme"),
+            ("Cargo.toml", "[workspace]"),
+            ("my_crate/Cargo.toml", "[package]"),
+            ("my_crate/src/lib.rs", "fn f() {}"),
+            ("my_crate/src/main.rs", "fn main() {}"),
+            ("my_crate/tests/t.rs", "excluded from the package"),
+        ]);
+        let list = compare_files(&package_files, &repo_files, "my_crate");
+        let list: Vec<(&str, &str)> = list.iter().map(|x| (x.path.as_str(), x.status.as_str())).collect();
+        assert_eq!(list, vec![("src/evil.rs", "only_in_package"), ("src/main.rs", "differs")]);
+    }
+}
@@ -5,29 +5,47 @@
 
 use crate::cln_methods_mod::cln_cargo_tree_mod::*;
 use crate::cln_methods_mod::cln_config_mod::*;
+use crate::cln_methods_mod::cln_jobs_mod::*;
+use crate::cln_methods_mod::cln_proof_problem_mod::*;
 use crate::cln_methods_mod::cln_publisher_item_mod::*;
 use crate::cln_methods_mod::cln_publisher_list_mod::*;
 use crate::cln_methods_mod::cln_review_item_mod::*;
 use crate::cln_methods_mod::cln_review_list_mod::*;
+use crate::cln_methods_mod::cln_risk_scan_mod::*;
+use crate::cln_methods_mod::cln_source_mod::*;
 use crate::cln_methods_mod::cln_utils_mod::*;
+use crate::cln_methods_mod::cln_version_diff_mod::*;
 use crate::cln_methods_mod::cln_version_mod::*;
 
 /// match the string and call a function
 pub async fn match_response_method_and_call_function(response: common_structs_mod::RpcResponse) {
     match response.response_method.as_str() {
         // region: generated match_response_method
+        "cln_advisory_edit_modal" => cln_advisory_edit_modal(response),
+        "cln_annotation_edit_modal" => cln_annotation_edit_modal(response),
+        "cln_annotation_list" => cln_annotation_list(response),
         "cln_cargo_tree_list" => cln_cargo_tree_list(response),
         "cln_config_edit" => cln_config_edit(response),
+        "cln_issue_edit_modal" => cln_issue_edit_modal(response),
+        "cln_jobs_list" => cln_jobs_list(response),
         "cln_modal_close" => cln_modal_close(response),
         "cln_modal_error" => cln_modal_error(response),
         "cln_no_action" => cln_no_action(response),
+        "cln_proof_problem_list" => cln_proof_problem_list(response),
         "cln_publisher_edit_modal" => cln_publisher_edit_modal(response),
         "cln_publisher_list" => cln_publisher_list(response),
         "cln_publisher_new_modal" => cln_publisher_new_modal(response),
+        "cln_read_progress" => cln_read_progress(response),
         "cln_review_edit" => cln_review_edit(response),
         "cln_review_list" => cln_review_list(response),
         "cln_review_new" => cln_review_new(response),
         "cln_review_publish_modal" => cln_review_publish_modal(response),
+        "cln_risk_scan" => cln_risk_scan(response),
+        "cln_rpc_pending" => cln_rpc_pending(response),
+        "cln_source_file" => cln_source_file(response),
+        "cln_source_tree" => cln_source_tree(response),
+        "cln_vcs_verify" => cln_vcs_verify(response),
+        "cln_version_diff" => cln_version_diff(response),
         "cln_version_list" => cln_version_list(response),
         // endregion: generated match_response_method
         _ => log::error!("Error: Unrecognized response_method {}", response.response_method),
@@ -41,6 +59,51 @@ pub mod srv_methods {
 
     // region: generated srv_methods
 
+    #[named]
+    pub fn srv_advisory_edit<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
+    #[named]
+    pub fn srv_annotation_delete<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
+    #[named]
+    pub fn srv_annotation_edit<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
+    #[named]
+    pub fn srv_annotation_list<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
+    #[named]
+    pub fn srv_annotation_save<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
     #[named]
     pub fn srv_cargo_tree_project<T>(request_data: T)
     where
@@ -77,6 +140,33 @@ pub mod srv_methods {
         post_request_await_run_response_method(request_method, request_data);
     }
 
+    #[named]
+    pub fn srv_issue_edit<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
+    #[named]
+    pub fn srv_job_cancel<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
+    #[named]
+    pub fn srv_jobs_list<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
     #[named]
     pub fn srv_list_unclean_crates<T>(request_data: T)
     where
@@ -86,6 +176,15 @@ pub mod srv_methods {
         post_request_await_run_response_method(request_method, request_data);
     }
 
+    #[named]
+    pub fn srv_proof_problem_list<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
     #[named]
     pub fn srv_publisher_delete<T>(request_data: T)
     where
@@ -131,6 +230,15 @@ pub mod srv_methods {
         post_request_await_run_response_method(request_method, request_data);
     }
 
+    #[named]
+    pub fn srv_read_progress<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
     #[named]
     pub fn srv_review_delete<T>(request_data: T)
     where
@@ -212,6 +320,51 @@ pub mod srv_methods {
         post_request_await_run_response_method(request_method, request_data);
     }
 
+    #[named]
+    pub fn srv_risk_scan<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
+    #[named]
+    pub fn srv_rpc_result<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
+    #[named]
+    pub fn srv_source_file<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
+    #[named]
+    pub fn srv_source_mark_read<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
+    #[named]
+    pub fn srv_source_tree<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
     #[named]
     pub fn srv_update_registry_index<T>(request_data: T)
     where
@@ -221,6 +374,24 @@ pub mod srv_methods {
         post_request_await_run_response_method(request_method, request_data);
     }
 
+    #[named]
+    pub fn srv_vcs_verify<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
+    #[named]
+    pub fn srv_version_diff<T>(request_data: T)
+    where
+        T: serde::Serialize,
+    {
+        let request_method = function_name!();
+        post_request_await_run_response_method(request_method, request_data);
+    }
+
     #[named]
     pub fn srv_version_list<T>(request_data: T)
     where
@@ -248,6 +419,9 @@ pub mod common_structs_mod {
     pub struct RpcRequest {
         pub request_method: String,
         pub request_data: serde_json::Value,
+        /// the session token from the url opened by the server
+        #[serde(default)]
+        pub token: String,
     }
 
     /// the response_method will be processed on the client
@@ -267,6 +441,12 @@ pub mod common_structs_mod {
     /// generic empty data for Rpc
     #[derive(Serialize, Deserialize, Debug)]
     pub struct RpcEmptyData {}
+
+    /// the long method runs on a worker thread, the client asks again for the result with this id
+    #[derive(Serialize, Deserialize, Debug)]
+    pub struct RpcPendingData {
+        pub rpc_id: u64,
+    }
     // endregion: platform wide structs
 
     // region: review
@@ -276,6 +456,18 @@ pub mod common_structs_mod {
         pub crate_name: String,
         pub crate_version: Option<String>,
         pub old_crate_version: Option<String>,
+        /// crev source url of git, path or alternative registry packages, empty for crates.io
+        #[serde(default)]
+        pub source: String,
+    }
+
+    /// the review to delete from one row of a list, the source is empty for crates.io
+    #[derive(Serialize, Deserialize, Debug, Default)]
+    pub struct ReviewDeleteData {
+        pub crate_name: String,
+        pub crate_version: String,
+        #[serde(default)]
+        pub source: String,
     }
 
     #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -287,6 +479,19 @@ pub mod common_structs_mod {
         pub understanding: String,
         pub rating: String,
         pub comment_md: String,
+        #[serde(default)]
+        pub issues: Vec<IssueItemData>,
+        #[serde(default)]
+        pub advisories: Vec<AdvisoryItemData>,
+        /// empty for a full review. A differential review covers only the changes after this version.
+        #[serde(default)]
+        pub diff_base_version: String,
+        /// the highest of my reviewed versions below this version, the editor offers it as diff base
+        #[serde(default)]
+        pub suggested_diff_base_version: String,
+        /// crev source url of git, path or alternative registry packages, empty for crates.io
+        #[serde(default)]
+        pub source: String,
     }
 
     #[derive(Serialize, Deserialize, Debug, Default)]
@@ -295,6 +500,40 @@ pub mod common_structs_mod {
         pub list_of_review: Vec<ReviewItemData>,
     }
 
+    /// crev issue in a package review
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct IssueItemData {
+        pub id: String,
+        pub severity: String,
+        #[serde(default)]
+        pub comment: String,
+    }
+
+    /// crev advisory in a package review. The range is all, major or minor.
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct AdvisoryItemData {
+        pub ids: Vec<String>,
+        pub severity: String,
+        #[serde(default)]
+        pub range: String,
+        #[serde(default)]
+        pub comment: String,
+    }
+
+    /// row_number is None for a new issue
+    #[derive(Serialize, Deserialize, Debug, Default)]
+    pub struct IssueEditData {
+        pub row_number: Option<usize>,
+        pub issue: IssueItemData,
+    }
+
+    /// row_number is None for a new advisory
+    #[derive(Serialize, Deserialize, Debug, Default)]
+    pub struct AdvisoryEditData {
+        pub row_number: Option<usize>,
+        pub advisory: AdvisoryItemData,
+    }
+
     // endregion: review
 
     #[derive(Serialize, Deserialize, Debug, Default)]
@@ -307,6 +546,27 @@ pub mod common_structs_mod {
         pub trusted_publisher: Option<String>,
         pub status: Option<String>,
         pub audit_id: Option<String>,
+        /// policy from crev-policy.toml: pass, fail or None if the project has no policy
+        #[serde(default)]
+        pub policy_status: Option<String>,
+        /// the policy rule that decided pass or fail
+        #[serde(default)]
+        pub policy_rule: Option<String>,
+        /// crates_io, registry, git or path
+        #[serde(default)]
+        pub source_kind: Option<String>,
+        /// the source url for crev
+        #[serde(default)]
+        pub crev_source: Option<String>,
+        /// enabled features after the resolve
+        #[serde(default)]
+        pub features: Option<String>,
+        /// None until the background risk scan of the source code is finished
+        #[serde(default)]
+        pub risk_counts: Option<RiskCountsData>,
+        /// the list of suspicious files in the package for the warning badge, None if nothing found
+        #[serde(default)]
+        pub file_warning: Option<String>,
     }
 
     #[derive(Serialize, Deserialize, Debug, Default)]
@@ -317,14 +577,38 @@ pub mod common_structs_mod {
 
     #[derive(Serialize, Deserialize, Debug, Default)]
     pub struct VerifyItemData {
-        /// status: none, pass, warn, yanked
+        /// status: none, pass, warn. Verified natively with the crev proof db and trust set.
         pub status: String,
+        /// the version is yanked on crates.io, independent of the crev status
+        #[serde(default)]
+        pub yanked: bool,
         /// rating if exists, version number if exists for crate
         pub my_review: String,
         pub crate_name: String,
         pub crate_version: String,
         pub published_by_url: String,
         pub trusted_publisher: String,
+        /// policy from crev-policy.toml: pass, fail or empty if the project has no policy
+        #[serde(default)]
+        pub policy_status: String,
+        /// the policy rule that decided pass or fail
+        #[serde(default)]
+        pub policy_rule: String,
+        /// count of reviews in the trusted proof db for this version
+        #[serde(default)]
+        pub reviews_count: usize,
+        /// count of reviews in the trusted proof db for all versions of the crate
+        #[serde(default)]
+        pub reviews_count_all: usize,
+        /// count of open issues reported by trusted reviewers
+        #[serde(default)]
+        pub issues_count: usize,
+        /// owners from crates.io, comma separated
+        #[serde(default)]
+        pub owners: String,
+        /// digest of the source code compared to the reviews: ok, mismatch or empty if not checked
+        #[serde(default)]
+        pub digest_check: String,
     }
 
     #[derive(Serialize, Deserialize, Debug, Default)]
@@ -369,6 +653,308 @@ pub mod common_structs_mod {
     pub struct ConfigData {
         pub code_editor_path: String,
         pub browser_path: String,
+        /// base url of the crates.io API or a mirror, empty means crates.io
+        #[serde(default)]
+        pub crates_io_api_url: String,
+        /// download url template with `{crate}` and `{version}`, empty means crates.io
+        #[serde(default)]
+        pub crates_io_download_url: String,
+        /// sparse index url, empty means <https://index.crates.io>
+        #[serde(default)]
+        pub crates_io_sparse_url: String,
+    }
+
+    // region: proof problems
+
+    /// a proof in my reviews directory that is corrupt, unsigned or signed by a foreign crev id
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct ProofProblemItemData {
+        pub file_path: String,
+        pub line_number: usize,
+        pub problem: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Default)]
+    pub struct ProofProblemListData {
+        pub reviews_dir: String,
+        pub list_of_problem: Vec<ProofProblemItemData>,
+    }
+    // endregion: proof problems
+
+    // region: version diff
+
+    /// one changed file in the summary of the diff
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct FileDiffItemData {
+        pub path: String,
+        /// added, removed or modified
+        pub status: String,
+        pub binary: bool,
+        pub lines_added: usize,
+        pub lines_removed: usize,
+    }
+
+    /// one row of the side-by-side diff. The row_kind is file, hunk or line.
+    /// For the line the change is equal, delete, insert or replace.
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct DiffRowItemData {
+        pub row_kind: String,
+        pub text: String,
+        pub change: String,
+        pub old_line_number: String,
+        pub old_text: String,
+        pub new_line_number: String,
+        pub new_text: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Default)]
+    pub struct VersionDiffData {
+        pub crate_name: String,
+        pub old_version: String,
+        pub new_version: String,
+        pub lines_added: usize,
+        pub lines_removed: usize,
+        pub list_of_file: Vec<FileDiffItemData>,
+        pub list_of_row: Vec<DiffRowItemData>,
+        /// very big diffs are cut, the rest is only in the summary
+        pub truncated: bool,
+    }
+    // endregion: version diff
+
+    // region: source code
+
+    /// request for one file in the clean unpacked crate
+    #[derive(Serialize, Deserialize, Debug, Default)]
+    pub struct SourceFileFilterData {
+        pub crate_name: String,
+        pub crate_version: String,
+        /// relative to the crate folder
+        pub path: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct SourceFileItemData {
+        pub name: String,
+        pub path: String,
+        pub depth: usize,
+        pub is_dir: bool,
+        pub size: u64,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Default)]
+    pub struct SourceTreeData {
+        pub crate_name: String,
+        pub crate_version: String,
+        pub list_of_file: Vec<SourceFileItemData>,
+    }
+
+    /// the html of the line is already highlighted and escaped by the server
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct SourceLineItemData {
+        pub line_number: usize,
+        pub html: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Default)]
+    pub struct SourceFileData {
+        pub crate_name: String,
+        pub crate_version: String,
+        pub path: String,
+        pub syntax_name: String,
+        pub binary: bool,
+        pub truncated: bool,
+        pub list_of_line: Vec<SourceLineItemData>,
+    }
+    // endregion: source code
+
+    // region: annotations
+
+    /// a note about a `file:line` range of the crate source code
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct AnnotationItemData {
+        /// relative to the crate folder
+        pub path: String,
+        pub line_start: usize,
+        pub line_end: usize,
+        pub note: String,
+    }
+
+    /// draft annotations for one crate version, stored in sled until the review is saved
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct AnnotationListData {
+        pub crate_name: String,
+        pub crate_version: String,
+        pub list_of_annotation: Vec<AnnotationItemData>,
+    }
+
+    /// row_number is None for a new annotation
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct AnnotationEditData {
+        pub crate_name: String,
+        pub crate_version: String,
+        pub row_number: Option<usize>,
+        pub annotation: AnnotationItemData,
+    }
+    // endregion: annotations
+
+    // region: review progress
+
+    /// one file marked as read, with the counts at the time of marking
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct ReadFileItemData {
+        /// relative to the crate folder
+        pub path: String,
+        pub bytes: u64,
+        pub lines: usize,
+    }
+
+    /// how much of the crate version the reviewer has read
+    /// The percent is for lines of text files. The thoroughness is suggested from the percent.
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct ReadProgressData {
+        pub crate_name: String,
+        pub crate_version: String,
+        pub files_read: usize,
+        pub files_total: usize,
+        pub lines_read: usize,
+        pub lines_total: usize,
+        pub bytes_read: u64,
+        pub bytes_total: u64,
+        pub percent_read: usize,
+        pub suggested_thoroughness: String,
+        pub list_of_read_path: Vec<String>,
+    }
+    // endregion: review progress
+
+    // region: risk scan
+
+    /// count of dangerous constructs in the source code of one crate version
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct RiskCountsData {
+        pub unsafe_code: usize,
+        pub build_script: usize,
+        pub proc_macro: usize,
+        pub process: usize,
+        pub network: usize,
+        pub fs_write: usize,
+        pub include_bytes: usize,
+        pub extern_c: usize,
+        pub no_mangle: usize,
+        pub env: usize,
+    }
+
+    /// one place in the source code, the kind is the name of the field in RiskCountsData
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct RiskFindingItemData {
+        pub kind: String,
+        pub path: String,
+        pub line_number: usize,
+        pub text: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct RiskScanData {
+        pub crate_name: String,
+        pub crate_version: String,
+        pub counts: RiskCountsData,
+        pub list_of_finding: Vec<RiskFindingItemData>,
+    }
+    // endregion: risk scan
+
+    // region: file classes
+
+    /// one suspicious file in the crate package: precompiled binary, archive, large opaque data or minified source
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct FileFindingItemData {
+        /// relative to the crate folder
+        pub path: String,
+        pub kind: String,
+        pub size: u64,
+        pub detail: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct FileClassData {
+        pub crate_name: String,
+        pub crate_version: String,
+        pub list_of_finding: Vec<FileFindingItemData>,
+    }
+    // endregion: file classes
+
+    // region: vcs verify
+
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct VcsVerifyFilterData {
+        pub crate_name: String,
+        pub crate_version: String,
+        /// local clone of the crate repository
+        pub repo_path: String,
+    }
+
+    /// one file of the package that does not match the repository, the status is `only_in_package` or `differs`
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct VcsFileItemData {
+        /// relative to the crate folder
+        pub path: String,
+        pub status: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct VcsVerifyData {
+        pub crate_name: String,
+        pub crate_version: String,
+        pub repo_path: String,
+        pub git_sha1: String,
+        pub path_in_vcs: String,
+        /// published with uncommitted changes
+        pub dirty: bool,
+        pub files_checked: usize,
+        pub list_of_file: Vec<VcsFileItemData>,
+    }
+    // endregion: vcs verify
+
+    // region: progress
+
+    /// one event of a long-running operation, sent as server-sent event
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct ProgressEventData {
+        pub operation: String,
+        /// the current crate or step
+        pub current: String,
+        pub n: usize,
+        pub m: usize,
+        pub error: String,
+        pub finished: bool,
+    }
+    // endregion: progress
+
+    // region: jobs
+
+    /// one background job, the state is `queued`, `running`, `finished`, `failed` or `cancelled`
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct JobItemData {
+        pub job_id: u64,
+        pub kind: String,
+        /// the crate or the other argument of the job
+        pub description: String,
+        pub state: String,
+        pub queued_at: String,
+        pub started_at: String,
+        pub ended_at: String,
+        pub error: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct JobListData {
+        pub list_of_job: Vec<JobItemData>,
+        /// the last failures persisted in db_sled, also from the previous runs
+        pub list_of_failure: Vec<JobItemData>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+    pub struct JobFilterData {
+        pub job_id: u64,
     }
+    // endregion: jobs
     // endregion: generated common_structs_mod
 }
@@ -38,7 +38,10 @@ impl tmplt::HtmlTemplatingDataTrait for CargoTreeItemData {
             "wt_tree_line" => self.cargo_tree_line.replace("──", "─"),
             "wt_tree_line_class" => format!("review_header0_cell left codetree pointer c_{}", self.my_rating.as_deref().unwrap_or("")),
             "wt_my_rating" => self.my_rating.as_deref().unwrap_or("").to_string(),
-            "wt_crate_description" => self.crate_description.as_deref().unwrap_or("").to_string(),
+            "wt_crate_description" => match &self.features {
+                Some(features) => format!("{}\nfeatures: {}", self.crate_description.as_deref().unwrap_or(""), features),
+                None => self.crate_description.as_deref().unwrap_or("").to_string(),
+            },
             "wt_published_by_url" => {
                 cln_methods_mod::cln_publisher_item_mod::published_by_url_shorten(self.published_by_url.as_deref().unwrap_or("")).to_string()
             }
@@ -54,9 +57,34 @@ impl tmplt::HtmlTemplatingDataTrait for CargoTreeItemData {
                 .as_deref()
                 .unwrap_or("")
                 .replace("RUSTSEC-", "https://rustsec.org/advisories/RUSTSEC-"),
+            "wt_policy_status" => self.policy_status.as_deref().unwrap_or("").to_string(),
+            "wt_policy_class" => format!("review_header0_cell left codetree c_{}", self.policy_status.as_deref().unwrap_or("")),
+            "wt_policy_rule" => self.policy_rule.as_deref().unwrap_or("").to_string(),
+            // the risk counts are empty until the background scan is finished, zero is also empty for readability
+            x if x.starts_with("wt_risk_") => match &self.risk_counts {
+                Some(risk_counts) => match cln_methods_mod::cln_risk_scan_mod::risk_count(risk_counts, x.trim_start_matches("wt_risk_")) {
+                    0 => String::new(),
+                    count => count.to_string(),
+                },
+                None => String::new(),
+            },
+            "wt_file_warning" => self.file_warning.as_deref().unwrap_or("").to_string(),
+            "wt_source_kind" => match self.source_kind.as_deref() {
+                Some("crates_io") | None => String::new(),
+                Some(source_kind) => source_kind.to_string(),
+            },
+            "wt_crev_source" => self.crev_source.as_deref().unwrap_or("").to_string(),
             _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
         }
     }
+
+    /// boolean : is the next node rendered or not: "wb_" or "sb_"
+    fn exists_next_node_or_attribute(&self, placeholder: &str) -> bool {
+        match placeholder {
+            "wb_file_warning" => self.file_warning.is_some(),
+            _ => tmplt::utils::match_else_for_exists_next_node_or_attribute(&self.data_model_name(), placeholder),
+        }
+    }
 }
 
 impl tmplt::HtmlTemplatingDataTrait for CargoTreeListData {
@@ -104,6 +132,7 @@ impl tmplt::HtmlTemplatingDataTrait for CargoTreeListData {
 #[named]
 pub fn request_cargo_tree_list(_element_id: &str) {
     log::info!("{}", function_name!());
+    show_modal_progress("cargo_tree", "Reading the dependency tree, cargo audit and the crev data...");
     let request_data = RpcEmptyData {};
     srv_methods::srv_cargo_tree_project(request_data);
 }
@@ -121,6 +150,7 @@ pub fn cln_cargo_tree_list(srv_response: RpcResponse) {
     };
 
     inject_into_html(&html_after_process);
+    close_modal();
     // navigation menu bar
     use cln_methods_mod::cln_config_mod::button_open_config_edit_on_click;
     use cln_methods_mod::cln_publisher_list_mod::open_publisher_list;
@@ -155,6 +185,7 @@ pub fn request_review_edit_or_new(_element_id: &str, row_number: usize) {
                 crate_name: crate_name.to_string(),
                 crate_version: Some(crate_version.to_string()),
                 old_crate_version: None,
+                source: String::new(),
             };
             srv_methods::srv_review_edit_or_new(request_data);
         }
@@ -173,6 +204,18 @@ fn open_all_links(_element_id: &str, row_number: usize) {
             let crate_name = spl.next().unwrap();
             let crate_version = spl.next().unwrap();
 
+            // git, path and alternative registry packages are not on crates.io, open only the review
+            if item.source_kind.as_deref() != Some("crates_io") {
+                let request_data = ReviewFilterData {
+                    crate_name: crate_name.to_string(),
+                    crate_version: Some(crate_version.to_string()),
+                    old_crate_version: None,
+                    source: String::new(),
+                };
+                srv_methods::srv_review_edit_or_new(request_data);
+                return;
+            }
+
             let url = format!("https://web.crev.dev/rust-reviews/crate/{}/", crate_name);
             unwrap!(w::window().open_with_url(&url));
 
@@ -186,6 +229,7 @@ fn open_all_links(_element_id: &str, row_number: usize) {
                 crate_name: crate_name.to_string(),
                 crate_version: Some(crate_version.to_string()),
                 old_crate_version: None,
+                source: String::new(),
             };
             srv_methods::srv_review_open_source_code(request_data);
 
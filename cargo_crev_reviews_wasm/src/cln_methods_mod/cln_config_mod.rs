@@ -36,6 +36,9 @@ impl tmplt::HtmlTemplatingDataTrait for ConfigData {
         match placeholder {
             "wt_code_editor_path" => self.code_editor_path.clone(),
             "wt_browser_path" => self.browser_path.clone(),
+            "wt_crates_io_api_url" => self.crates_io_api_url.clone(),
+            "wt_crates_io_download_url" => self.crates_io_download_url.clone(),
+            "wt_crates_io_sparse_url" => self.crates_io_sparse_url.clone(),
             _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
         }
     }
@@ -70,6 +73,10 @@ pub fn cln_config_edit(srv_response: RpcResponse) {
     on_click!("button_close", close_on_click);
     on_click!("button_correct_digest", request_correct_digest);
     on_click!("button_list_unclean_crates", request_list_unclean_crates);
+    use crate::cln_methods_mod::cln_proof_problem_mod::button_open_proof_problem_list_on_click;
+    on_click!("button_proof_problem_list", button_open_proof_problem_list_on_click);
+    use crate::cln_methods_mod::cln_jobs_mod::button_open_jobs_list_on_click;
+    on_click!("button_jobs_list", button_open_jobs_list_on_click);
 }
 
 /// send rpc requests
@@ -80,6 +87,9 @@ fn request_config_save(_element_id: &str) {
     let request_data = ConfigData {
         code_editor_path: w::get_input_element_value_string_by_id("code_editor_path"),
         browser_path: w::get_input_element_value_string_by_id("browser_path"),
+        crates_io_api_url: w::get_input_element_value_string_by_id("crates_io_api_url"),
+        crates_io_download_url: w::get_input_element_value_string_by_id("crates_io_download_url"),
+        crates_io_sparse_url: w::get_input_element_value_string_by_id("crates_io_sparse_url"),
     };
     srv_methods::srv_config_save(request_data);
 }
@@ -91,7 +101,7 @@ fn close_on_click(_element_id: &str) {
 #[named]
 fn request_correct_digest(_element_id: &str) {
     log::info!("{}", function_name!());
-    show_modal_message("Correcting digest can take some time. You can see the progress in the terminal...");
+    show_modal_progress("correct_digest", "Correcting digest can take some time...");
     let request_data = RpcEmptyData {};
     srv_methods::srv_correct_digest(request_data);
 }
@@ -99,7 +109,7 @@ fn request_correct_digest(_element_id: &str) {
 #[named]
 fn request_list_unclean_crates(_element_id: &str) {
     log::info!("{}", function_name!());
-    show_modal_message("The search for unclean crates can take some time. You can see the progress in the terminal...");
+    show_modal_progress("list_unclean_crates", "The search for unclean crates can take some time...");
     let request_data = RpcEmptyData {};
 
     srv_methods::srv_list_unclean_crates(request_data);
@@ -0,0 +1,145 @@
+// cln_jobs_mod.rs
+
+use crate::web_sys_mod as w;
+use function_name::named;
+use lazy_static::{__Deref, lazy_static};
+use std::sync::Mutex;
+use unwrap::unwrap;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use dev_bestia_html_templating as tmplt;
+use dev_bestia_string_utils::*;
+
+use crate::auto_generated_mod::{common_structs_mod::*, srv_methods};
+use crate::html_mod::*;
+use crate::{on_click, row_on_click};
+
+lazy_static! {
+    /// mutable static, because it is hard to pass variables around with on_click events
+    static ref JOB_LIST_DATA: Mutex<JobListData> = Mutex::new(JobListData::default());
+}
+
+impl tmplt::HtmlTemplatingDataTrait for JobListData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("JobListData")
+    }
+
+    /// renders sub-template: "stmplt_" or "wtmplt_"
+    fn process_sub_template(&self, template_name: &str, sub_templates: &Vec<tmplt::utils::SubTemplate>) -> Vec<tmplt::utils::Node> {
+        log::info!("{}", template_name);
+        // the same JobItemData for the jobs of this run and for the persisted failures
+        let list = match template_name {
+            "wtmplt_JobItemData" => &self.list_of_job,
+            "wtmplt_JobFailureItemData" => &self.list_of_failure,
+            _ => return tmplt::utils::match_else_for_process_sub_template(&self.data_model_name(), template_name),
+        };
+        let sub_template = unwrap!(sub_templates.iter().find(|&template| template.name == template_name));
+        let mut nodes = vec![];
+        for (row_number, item) in list.iter().enumerate() {
+            let vec_node = unwrap!(tmplt::utils::process_template_raw_to_nodes(
+                item,
+                &sub_template.template,
+                tmplt::utils::HtmlOrSvg::Html,
+                "",
+                row_number,
+            ));
+            nodes.extend_from_slice(&vec_node);
+        }
+        // return
+        nodes
+    }
+
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        // log::debug!(&placeholder);
+        match placeholder {
+            "wt_cargo_crev_reviews_version" => s!(env!("CARGO_PKG_VERSION")),
+            "wt_count_of_jobs" => self.list_of_job.len().to_string(),
+            "wt_count_of_failures" => self.list_of_failure.len().to_string(),
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+impl tmplt::HtmlTemplatingDataTrait for JobItemData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("JobItemData")
+    }
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        // log::debug!(&placeholder);
+        match placeholder {
+            // only a queued job can be cancelled
+            "wt_cancel" => {
+                if self.state == "queued" {
+                    s!("cancel")
+                } else {
+                    String::new()
+                }
+            }
+            "wt_kind" => self.kind.clone(),
+            "wt_description" => self.description.clone(),
+            "wt_state" => self.state.clone(),
+            "wt_queued_at" => format!("queued at {}", &self.queued_at),
+            "wt_started_at" => self.started_at.clone(),
+            "wt_ended_at" => self.ended_at.clone(),
+            "wt_error" => self.error.clone(),
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+// button_open_jobs_list_on_click > routing_jobs_list > srv_jobs_list > cln_jobs_list
+
+#[named]
+pub fn button_open_jobs_list_on_click(_element_id: &str) {
+    log::info!("{}", function_name!());
+    let url = format!("index.html#jobs_list");
+    unwrap!(w::window().open_with_url(&url));
+}
+
+pub fn routing_jobs_list() {
+    let request_data = RpcEmptyData {};
+    srv_methods::srv_jobs_list(request_data);
+}
+
+#[named]
+pub fn cln_jobs_list(srv_response: RpcResponse) {
+    log::info!("{}", function_name!());
+    let html = extract_html(&srv_response);
+    *JOB_LIST_DATA.lock().unwrap() = unwrap!(serde_json::from_value(srv_response.response_data));
+    // the mutex is locked inside a scope. When this structure falls out of scope, the lock will be unlocked.
+    let html_after_process = {
+        let data = JOB_LIST_DATA.lock().unwrap();
+        tmplt::process_html(data.deref(), &html)
+    };
+    inject_into_html(&html_after_process);
+
+    on_click!("button_refresh_jobs", button_refresh_jobs_on_click);
+    // on_click only for the queued jobs
+    for (row_number, item) in JOB_LIST_DATA.lock().unwrap().list_of_job.iter().enumerate() {
+        if item.state == "queued" {
+            row_on_click!("button_cancel", row_number, button_cancel_on_click);
+        }
+    }
+}
+
+#[named]
+fn button_refresh_jobs_on_click(_element_id: &str) {
+    log::info!("{}", function_name!());
+    routing_jobs_list();
+}
+
+#[named]
+fn button_cancel_on_click(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    let request_data = JobFilterData {
+        job_id: JOB_LIST_DATA.lock().unwrap().list_of_job[row_number].job_id,
+    };
+    srv_methods::srv_job_cancel(request_data);
+}
@@ -0,0 +1,107 @@
+// cln_proof_problem_mod.rs
+
+use crate::web_sys_mod as w;
+use function_name::named;
+use lazy_static::{__Deref, lazy_static};
+use std::sync::Mutex;
+use unwrap::unwrap;
+
+use dev_bestia_html_templating as tmplt;
+use dev_bestia_string_utils::*;
+
+use crate::auto_generated_mod::{common_structs_mod::*, srv_methods};
+use crate::html_mod::*;
+
+lazy_static! {
+    /// mutable static, because it is hard to pass variables around with on_click events
+    static ref PROOF_PROBLEM_LIST_DATA: Mutex<ProofProblemListData> = Mutex::new(ProofProblemListData::default());
+}
+
+impl tmplt::HtmlTemplatingDataTrait for ProofProblemListData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("ProofProblemListData")
+    }
+
+    /// renders sub-template: "stmplt_" or "wtmplt_"
+    fn process_sub_template(&self, template_name: &str, sub_templates: &Vec<tmplt::utils::SubTemplate>) -> Vec<tmplt::utils::Node> {
+        log::info!("{}", template_name);
+        match template_name {
+            "wtmplt_ProofProblemItemData" => {
+                let sub_template = unwrap!(sub_templates.iter().find(|&template| template.name == template_name));
+                let mut nodes = vec![];
+                for (row_number, item) in self.list_of_problem.iter().enumerate() {
+                    let vec_node = unwrap!(tmplt::utils::process_template_raw_to_nodes(
+                        item,
+                        &sub_template.template,
+                        tmplt::utils::HtmlOrSvg::Html,
+                        "",
+                        row_number,
+                    ));
+                    nodes.extend_from_slice(&vec_node);
+                }
+                // return
+                nodes
+            }
+            _ => tmplt::utils::match_else_for_process_sub_template(&self.data_model_name(), template_name),
+        }
+    }
+
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        // log::debug!(&placeholder);
+        match placeholder {
+            "wt_cargo_crev_reviews_version" => s!(env!("CARGO_PKG_VERSION")),
+            "wt_reviews_dir" => self.reviews_dir.clone(),
+            "wt_count_of_problems" => self.list_of_problem.len().to_string(),
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+impl tmplt::HtmlTemplatingDataTrait for ProofProblemItemData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("ProofProblemItemData")
+    }
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        // log::debug!(&placeholder);
+        match placeholder {
+            "wt_file_name" => self.file_path.rsplit('/').next().unwrap_or("").to_string(),
+            "wt_file_path" => self.file_path.clone(),
+            "wt_line_number" => self.line_number.to_string(),
+            "wt_problem" => self.problem.clone(),
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+// button_open_proof_problem_list_on_click > routing_proof_problem_list > srv_proof_problem_list > cln_proof_problem_list
+
+#[named]
+pub fn button_open_proof_problem_list_on_click(_element_id: &str) {
+    log::info!("{}", function_name!());
+    let url = format!("index.html#proof_problem_list");
+    unwrap!(w::window().open_with_url(&url));
+}
+
+pub fn routing_proof_problem_list() {
+    let request_data = RpcEmptyData {};
+    srv_methods::srv_proof_problem_list(request_data);
+}
+
+#[named]
+pub fn cln_proof_problem_list(srv_response: RpcResponse) {
+    log::info!("{}", function_name!());
+    let html = extract_html(&srv_response);
+    *PROOF_PROBLEM_LIST_DATA.lock().unwrap() = unwrap!(serde_json::from_value(srv_response.response_data));
+    // the mutex is locked inside a scope. When this structure falls out of scope, the lock will be unlocked.
+    let html_after_process = {
+        let data = PROOF_PROBLEM_LIST_DATA.lock().unwrap();
+        tmplt::process_html(data.deref(), &html)
+    };
+    inject_into_html(&html_after_process);
+}
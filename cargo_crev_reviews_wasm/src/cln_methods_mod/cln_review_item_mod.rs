@@ -25,6 +25,10 @@ use crate::*;
 lazy_static! {
     /// mutable static, because it is hard to pass variables around with on_click events
     static ref REVIEW_ITEM_DATA: Mutex<ReviewItemData> = Mutex::new(ReviewItemData::default());
+    /// the page is rendered again after every change of issues or advisories
+    static ref REVIEW_HTML: Mutex<String> = Mutex::new(String::new());
+    static ref ISSUE_EDIT_DATA: Mutex<IssueEditData> = Mutex::new(IssueEditData::default());
+    static ref ADVISORY_EDIT_DATA: Mutex<AdvisoryEditData> = Mutex::new(AdvisoryEditData::default());
 }
 
 /// store data in static Mutex because of events like on_click
@@ -41,6 +45,46 @@ impl tmplt::HtmlTemplatingDataTrait for ReviewItemData {
         s!("ReviewItemData")
     }
 
+    /// renders sub-template: "stmplt_" or "wtmplt_"
+    fn process_sub_template(&self, template_name: &str, sub_templates: &Vec<tmplt::utils::SubTemplate>) -> Vec<tmplt::utils::Node> {
+        log::info!("{}", template_name);
+        match template_name {
+            "wtmplt_IssueItemData" => {
+                let sub_template = unwrap!(sub_templates.iter().find(|&template| template.name == template_name));
+                let mut nodes = vec![];
+                for (row_number, issue) in self.issues.iter().enumerate() {
+                    let vec_node = unwrap!(tmplt::utils::process_template_raw_to_nodes(
+                        issue,
+                        &sub_template.template,
+                        tmplt::utils::HtmlOrSvg::Html,
+                        "",
+                        row_number,
+                    ));
+                    nodes.extend_from_slice(&vec_node);
+                }
+                // return
+                nodes
+            }
+            "wtmplt_AdvisoryItemData" => {
+                let sub_template = unwrap!(sub_templates.iter().find(|&template| template.name == template_name));
+                let mut nodes = vec![];
+                for (row_number, advisory) in self.advisories.iter().enumerate() {
+                    let vec_node = unwrap!(tmplt::utils::process_template_raw_to_nodes(
+                        advisory,
+                        &sub_template.template,
+                        tmplt::utils::HtmlOrSvg::Html,
+                        "",
+                        row_number,
+                    ));
+                    nodes.extend_from_slice(&vec_node);
+                }
+                // return
+                nodes
+            }
+            _ => tmplt::utils::match_else_for_process_sub_template(&self.data_model_name(), template_name),
+        }
+    }
+
     /// returns a String to replace the next text-node: "wt_" or "st_"
     fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
         // log::debug!(&placeholder);
@@ -48,6 +92,15 @@ impl tmplt::HtmlTemplatingDataTrait for ReviewItemData {
             "wt_comment_md" => self.comment_md.clone(),
             "wt_crate_name" => self.crate_name.clone(),
             "wt_crate_version" => self.crate_version.clone(),
+            "wt_diff_base_version" => self.diff_base_version.clone(),
+            "wt_suggested_diff_base_label" => {
+                if self.suggested_diff_base_version.is_empty() {
+                    String::new()
+                } else {
+                    format!("review as diff against {}", self.suggested_diff_base_version)
+                }
+            }
+            "wt_source" => self.source.clone(),
             "wt_crate_name_version" => crate_version_join(&self.crate_name, &self.crate_version),
             "wt_thoroughness" => self.thoroughness.clone(),
             "wt_understanding" => self.understanding.clone(),
@@ -56,6 +109,8 @@ impl tmplt::HtmlTemplatingDataTrait for ReviewItemData {
             "wt_review_date" => self.date[..10].to_string(),
             "wt_rating_class_color" => format!("review_header0_cell c_{} bold", self.rating),
             "wt_cargo_crev_reviews_version" => env!("CARGO_PKG_VERSION").to_string(),
+            "wt_count_of_issues" => self.issues.len().to_string(),
+            "wt_count_of_advisories" => self.advisories.len().to_string(),
 
             _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
         }
@@ -84,6 +139,104 @@ impl tmplt::HtmlTemplatingDataTrait for ReviewItemData {
     }
 }
 
+impl tmplt::HtmlTemplatingDataTrait for IssueItemData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("IssueItemData")
+    }
+
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        match placeholder {
+            "wt_issue_id" => self.id.clone(),
+            "wt_issue_severity" => self.severity.clone(),
+            "wt_issue_severity_class" => format!("review_header0_cell c_{}_severity bold", self.severity),
+            "wt_issue_comment" => self.comment.clone(),
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+impl tmplt::HtmlTemplatingDataTrait for AdvisoryItemData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("AdvisoryItemData")
+    }
+
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        match placeholder {
+            "wt_advisory_ids" => self.ids.join(", "),
+            "wt_advisory_severity" => self.severity.clone(),
+            "wt_advisory_severity_class" => format!("review_header0_cell c_{}_severity bold", self.severity),
+            "wt_advisory_range" => self.range.clone(),
+            "wt_advisory_comment" => self.comment.clone(),
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+impl tmplt::HtmlTemplatingDataTrait for IssueEditData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("IssueEditData")
+    }
+
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        match placeholder {
+            "wt_issue_id" => self.issue.id.clone(),
+            "wt_issue_comment" => self.issue.comment.clone(),
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+
+    /// boolean : is the next node rendered or not: "wb_" or "sb_"
+    fn exists_next_node_or_attribute(&self, placeholder: &str) -> bool {
+        match placeholder {
+            "wb_checked_se_none" => self.issue.severity == "none",
+            "wb_checked_se_low" => self.issue.severity == "low",
+            "wb_checked_se_medium" => self.issue.severity == "medium",
+            "wb_checked_se_high" => self.issue.severity == "high",
+            _ => tmplt::utils::match_else_for_exists_next_node_or_attribute(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+impl tmplt::HtmlTemplatingDataTrait for AdvisoryEditData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("AdvisoryEditData")
+    }
+
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        match placeholder {
+            "wt_advisory_ids" => self.advisory.ids.join(", "),
+            "wt_advisory_comment" => self.advisory.comment.clone(),
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+
+    /// boolean : is the next node rendered or not: "wb_" or "sb_"
+    fn exists_next_node_or_attribute(&self, placeholder: &str) -> bool {
+        match placeholder {
+            "wb_checked_se_none" => self.advisory.severity == "none",
+            "wb_checked_se_low" => self.advisory.severity == "low",
+            "wb_checked_se_medium" => self.advisory.severity == "medium",
+            "wb_checked_se_high" => self.advisory.severity == "high",
+            "wb_checked_rg_all" => self.advisory.range == "all" || self.advisory.range.is_empty(),
+            "wb_checked_rg_major" => self.advisory.range == "major",
+            "wb_checked_rg_minor" => self.advisory.range == "minor",
+            _ => tmplt::utils::match_else_for_exists_next_node_or_attribute(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
 // endregion: HtmlTemplatingDataTrait for data structs
 
 // region: cln methods to render the page and data
@@ -91,36 +244,119 @@ impl tmplt::HtmlTemplatingDataTrait for ReviewItemData {
 #[named]
 pub fn cln_review_new(srv_response: RpcResponse) {
     log::info!("{}", function_name!());
-    let html = extract_html(&srv_response);
+    *REVIEW_HTML.lock().unwrap() = extract_html(&srv_response);
+    store_to_review_item_data(srv_response);
+    render_review_page();
+}
+
+/// the code for processing the cln_review_edit
+/// the data and html are already in static Mutex REVIEW_ITEM_DATA
+#[named]
+pub fn cln_review_edit(srv_response: RpcResponse) {
+    log::info!("{}", function_name!());
+    *REVIEW_HTML.lock().unwrap() = extract_html(&srv_response);
     store_to_review_item_data(srv_response);
+    render_review_page();
+}
+
+/// render the page from REVIEW_HTML and REVIEW_ITEM_DATA and attach the event handlers
+fn render_review_page() {
     // the mutex is locked inside a scope. When this structure falls out of scope, the lock will be unlocked.
     let html_after_process = {
         let data = REVIEW_ITEM_DATA.lock().unwrap();
+        let html = REVIEW_HTML.lock().unwrap();
         tmplt::process_html(data.deref(), &html)
     };
     inject_into_html(&html_after_process);
 
     on_click!("button_review_save", request_review_save);
     on_click!("button_review_close", close_on_click);
+    on_click!("button_issue_new", request_issue_new);
+    on_click!("button_advisory_new", request_advisory_new);
+    on_click!("button_browse_source", browse_source_on_click);
+    on_click!("button_use_diff_base", use_diff_base_on_click);
+
+    let (count_of_issues, count_of_advisories) = {
+        let data = REVIEW_ITEM_DATA.lock().unwrap();
+        (data.issues.len(), data.advisories.len())
+    };
+    for row_number in 0..count_of_issues {
+        row_on_click!("button_issue_edit", row_number, request_issue_edit);
+        row_on_click!("button_issue_delete", row_number, issue_delete);
+    }
+    for row_number in 0..count_of_advisories {
+        row_on_click!("button_advisory_edit", row_number, request_advisory_edit);
+        row_on_click!("button_advisory_delete", row_number, advisory_delete);
+    }
+
+    // the percent of code read is shown under the thoroughness
+    let request_data = {
+        let data = REVIEW_ITEM_DATA.lock().unwrap();
+        ReviewFilterData {
+            crate_name: data.crate_name.clone(),
+            crate_version: Some(data.crate_version.clone()),
+            old_crate_version: None,
+            source: String::new(),
+        }
+    };
+    srv_methods::srv_read_progress(&request_data);
+    srv_methods::srv_risk_scan(&request_data);
 }
 
-/// the code for processing the cln_review_edit
-/// the data and html are already in static Mutex REVIEW_ITEM_DATA
+/// the differential review is an explicit choice: fill the suggested diff base into the input
 #[named]
-pub fn cln_review_edit(srv_response: RpcResponse) {
+fn use_diff_base_on_click(_element_id: &str) {
+    log::info!("{}", function_name!());
+    let suggested = REVIEW_ITEM_DATA.lock().unwrap().suggested_diff_base_version.clone();
+    if !suggested.is_empty() {
+        w::set_input_element_value_string_by_id("diff_base_version", &suggested);
+    }
+}
+
+/// the user can change the form before adding an issue, so read the form before rendering again
+fn read_form_into_review_item_data() {
+    let mut data = REVIEW_ITEM_DATA.lock().unwrap();
+    data.crate_name = w::get_input_element_value_string_by_id("crate_name");
+    data.crate_version = w::get_input_element_value_string_by_id("crate_version");
+    data.diff_base_version = w::get_input_element_value_string_by_id("diff_base_version").trim().to_string();
+    data.thoroughness = w::get_value_of_radio_group_by_name("thoroughness");
+    data.understanding = w::get_value_of_radio_group_by_name("understanding");
+    data.rating = w::get_value_of_radio_group_by_name("rating");
+    data.comment_md = w::get_text_area_element_value_string_by_id("comment_md");
+}
+
+#[named]
+pub fn cln_issue_edit_modal(srv_response: RpcResponse) {
     log::info!("{}", function_name!());
+    // request_issue_edit > srv_issue_edit > cln_issue_edit_modal
     let html = extract_html(&srv_response);
-    store_to_review_item_data(srv_response);
-    // the mutex is locked inside a scope. When this structure falls out of scope, the lock will be unlocked.
+    *ISSUE_EDIT_DATA.lock().unwrap() = unwrap!(serde_json::from_value(srv_response.response_data));
     let html_after_process = {
-        let data = REVIEW_ITEM_DATA.lock().unwrap();
+        let data = ISSUE_EDIT_DATA.lock().unwrap();
         tmplt::process_html(data.deref(), &html)
     };
+    show_modal_html(&html_after_process);
 
-    inject_into_html(&html_after_process);
+    use crate::cln_methods_mod::cln_utils_mod::modal_close_on_click;
+    on_click!("modal_close", modal_close_on_click);
+    on_click!("issue_save", issue_save);
+}
 
-    on_click!("button_review_save", request_review_save);
-    on_click!("button_review_close", close_on_click);
+#[named]
+pub fn cln_advisory_edit_modal(srv_response: RpcResponse) {
+    log::info!("{}", function_name!());
+    // request_advisory_edit > srv_advisory_edit > cln_advisory_edit_modal
+    let html = extract_html(&srv_response);
+    *ADVISORY_EDIT_DATA.lock().unwrap() = unwrap!(serde_json::from_value(srv_response.response_data));
+    let html_after_process = {
+        let data = ADVISORY_EDIT_DATA.lock().unwrap();
+        tmplt::process_html(data.deref(), &html)
+    };
+    show_modal_html(&html_after_process);
+
+    use crate::cln_methods_mod::cln_utils_mod::modal_close_on_click;
+    on_click!("modal_close", modal_close_on_click);
+    on_click!("advisory_save", advisory_save);
 }
 
 // endregion: cln methods to render the page and data
@@ -156,21 +392,148 @@ pub fn request_review_new(_element_id: &str) {
 #[named]
 fn request_review_save(_element_id: &str) {
     log::info!("{}", function_name!());
-    // values from form
+    // values from form, issues and advisories from the static data
+    read_form_into_review_item_data();
     let request_data = ReviewItemData {
-        crate_name: w::get_input_element_value_string_by_id("crate_name"),
-        crate_version: w::get_input_element_value_string_by_id("crate_version"),
         date: "".to_string(),
-        thoroughness: w::get_value_of_radio_group_by_name("thoroughness"),
-        understanding: w::get_value_of_radio_group_by_name("understanding"),
-        rating: w::get_value_of_radio_group_by_name("rating"),
-        comment_md: w::get_text_area_element_value_string_by_id("comment_md"),
+        ..REVIEW_ITEM_DATA.lock().unwrap().clone()
     };
     srv_methods::srv_review_save(request_data);
 }
 
+#[named]
+fn request_issue_new(_element_id: &str) {
+    log::info!("{}", function_name!());
+    let request_data = IssueEditData {
+        row_number: None,
+        issue: IssueItemData {
+            id: "".to_string(),
+            severity: "medium".to_string(),
+            comment: "".to_string(),
+        },
+    };
+    srv_methods::srv_issue_edit(request_data);
+}
+
+#[named]
+fn request_issue_edit(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    let request_data = IssueEditData {
+        row_number: Some(row_number),
+        issue: REVIEW_ITEM_DATA.lock().unwrap().issues[row_number].clone(),
+    };
+    srv_methods::srv_issue_edit(request_data);
+}
+
+/// values from the modal go into REVIEW_ITEM_DATA. They are saved to crev with the review.
+#[named]
+fn issue_save(_element_id: &str) {
+    log::info!("{}", function_name!());
+    let issue = IssueItemData {
+        id: w::get_input_element_value_string_by_id("modal_issue_id").trim().to_string(),
+        severity: w::get_value_of_checked_radio_by_name("modal_severity"),
+        comment: w::get_text_area_element_value_string_by_id("modal_issue_comment"),
+    };
+    if issue.id.is_empty() {
+        show_modal_message("The issue id is mandatory.");
+        return;
+    }
+    let row_number = ISSUE_EDIT_DATA.lock().unwrap().row_number;
+    close_modal();
+    read_form_into_review_item_data();
+    {
+        let mut data = REVIEW_ITEM_DATA.lock().unwrap();
+        match row_number {
+            Some(row_number) => data.issues[row_number] = issue,
+            None => data.issues.push(issue),
+        }
+    }
+    render_review_page();
+}
+
+#[named]
+fn issue_delete(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    read_form_into_review_item_data();
+    REVIEW_ITEM_DATA.lock().unwrap().issues.remove(row_number);
+    render_review_page();
+}
+
+#[named]
+fn request_advisory_new(_element_id: &str) {
+    log::info!("{}", function_name!());
+    let request_data = AdvisoryEditData {
+        row_number: None,
+        advisory: AdvisoryItemData {
+            ids: vec![],
+            severity: "medium".to_string(),
+            range: "all".to_string(),
+            comment: "".to_string(),
+        },
+    };
+    srv_methods::srv_advisory_edit(request_data);
+}
+
+#[named]
+fn request_advisory_edit(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    let request_data = AdvisoryEditData {
+        row_number: Some(row_number),
+        advisory: REVIEW_ITEM_DATA.lock().unwrap().advisories[row_number].clone(),
+    };
+    srv_methods::srv_advisory_edit(request_data);
+}
+
+/// values from the modal go into REVIEW_ITEM_DATA. They are saved to crev with the review.
+#[named]
+fn advisory_save(_element_id: &str) {
+    log::info!("{}", function_name!());
+    // the ids are separated by comma or space
+    let ids: Vec<String> = w::get_input_element_value_string_by_id("modal_advisory_ids")
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|x| !x.is_empty())
+        .map(|x| x.to_string())
+        .collect();
+    let advisory = AdvisoryItemData {
+        ids,
+        severity: w::get_value_of_checked_radio_by_name("modal_severity"),
+        range: w::get_value_of_checked_radio_by_name("modal_range"),
+        comment: w::get_text_area_element_value_string_by_id("modal_advisory_comment"),
+    };
+    if advisory.ids.is_empty() {
+        show_modal_message("The advisory needs at least one id.");
+        return;
+    }
+    let row_number = ADVISORY_EDIT_DATA.lock().unwrap().row_number;
+    close_modal();
+    read_form_into_review_item_data();
+    {
+        let mut data = REVIEW_ITEM_DATA.lock().unwrap();
+        match row_number {
+            Some(row_number) => data.advisories[row_number] = advisory,
+            None => data.advisories.push(advisory),
+        }
+    }
+    render_review_page();
+}
+
+#[named]
+fn advisory_delete(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    read_form_into_review_item_data();
+    REVIEW_ITEM_DATA.lock().unwrap().advisories.remove(row_number);
+    render_review_page();
+}
+
 fn close_on_click(_element_id: &str) {
     w::close_tab();
 }
 
+#[named]
+fn browse_source_on_click(_element_id: &str) {
+    log::info!("{}", function_name!());
+    let data = REVIEW_ITEM_DATA.lock().unwrap();
+    crate::cln_methods_mod::cln_source_mod::open_source_browser(&data.crate_name, &data.crate_version);
+}
+
 // region: functions for event handlers (on_click)
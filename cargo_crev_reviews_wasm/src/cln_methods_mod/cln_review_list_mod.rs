@@ -84,6 +84,7 @@ pub fn routing_edit_or_new(param2: &str, param3: &str) {
         crate_name: param2.to_string(),
         crate_version: Some(param3.to_string()),
         old_crate_version: None,
+        source: String::new(),
     };
     srv_methods::srv_review_edit_or_new(request_data);
 }
@@ -112,6 +113,7 @@ pub fn cln_review_list(srv_response: RpcResponse) {
         row_on_click!("button_open_crates_io", row_number, button_open_crates_io_onclick);
         row_on_click!("button_open_lib_rs", row_number, button_open_lib_rs_onclick);
         row_on_click!("button_open_source_code", row_number, button_open_source_code_onclick);
+        row_on_click!("button_browse_source", row_number, button_browse_source_onclick);
         row_on_click!("button_review_delete", row_number, modal_delete);
     }
 }
@@ -167,14 +169,24 @@ fn button_open_source_code_onclick(_element_id: &str, row_number: usize) {
         crate_name: item.crate_name.clone(),
         crate_version: Some(item.crate_version.clone()),
         old_crate_version: None,
+        source: String::new(),
     };
     srv_methods::srv_review_open_source_code(request_data);
 }
 
+#[named]
+fn button_browse_source_onclick(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    // from list get crate name and version
+    let item = &REVIEW_LIST_DATA.lock().unwrap().list_of_review[row_number];
+    crate::cln_methods_mod::cln_source_mod::open_source_browser(&item.crate_name, &item.crate_version);
+}
+
 #[named]
 pub fn request_update_registry_index(_element_id: &str) {
     log::info!("{}", function_name!());
-    show_modal_message(
+    show_modal_progress(
+        "update_registry_index",
         "Updating local cargo registry index. Wait a minute...
 This is necessary for your own crates that are published to crates.io just a moment ago.",
     );
@@ -198,6 +210,7 @@ fn request_review_new_version(_element_id: &str, row_number: usize) {
         crate_name: item.crate_name.clone(),
         crate_version: Some(item.crate_version.clone()),
         old_crate_version: None,
+        source: String::new(),
     };
     srv_methods::srv_review_new_version(request_data);
 }
@@ -215,6 +228,11 @@ fn request_review_save(_element_id: &str) {
         understanding: w::get_value_of_radio_group_by_name("understanding"),
         rating: w::get_value_of_radio_group_by_name("rating"),
         comment_md: w::get_text_area_element_value_string_by_id("comment_md"),
+        issues: vec![],
+        advisories: vec![],
+        diff_base_version: "".to_string(),
+        suggested_diff_base_version: "".to_string(),
+        source: "".to_string(),
     };
     srv_methods::srv_review_save(request_data);
 }
@@ -224,10 +242,12 @@ fn request_review_edit_from_list(_element_id: &str, row_number: usize) {
     log::info!("{}", function_name!());
     // from list get crate name and version
     let item = &REVIEW_LIST_DATA.lock().unwrap().list_of_review[row_number];
+    // the same version can have a review from crates.io and one from git
     let request_data = ReviewFilterData {
         crate_name: item.crate_name.clone(),
         crate_version: Some(item.crate_version.clone()),
         old_crate_version: None,
+        source: item.source.clone(),
     };
     srv_methods::srv_review_edit(request_data);
 }
@@ -259,12 +279,12 @@ fn request_review_delete(_element_id: &str, row_number: usize) {
     use crate::cln_methods_mod::cln_utils_mod::modal_close_on_click;
     modal_close_on_click("");
 
-    // from list get crate name and version
+    // from list get crate name, version and source
     let item = &REVIEW_LIST_DATA.lock().unwrap().list_of_review[row_number];
-    let request_data = ReviewFilterData {
+    let request_data = ReviewDeleteData {
         crate_name: item.crate_name.clone(),
-        crate_version: Some(item.crate_version.clone()),
-        old_crate_version: None,
+        crate_version: item.crate_version.clone(),
+        source: item.source.clone(),
     };
     srv_methods::srv_review_delete(request_data);
 }
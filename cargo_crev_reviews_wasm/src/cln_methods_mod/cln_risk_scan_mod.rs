@@ -0,0 +1,112 @@
+// cln_risk_scan_mod.rs
+
+//! dangerous constructs in the source code of the crate version
+//! counts in the cargo tree and the detailed list in the review editor
+
+use function_name::named;
+use lazy_static::lazy_static;
+use std::sync::Mutex;
+use unwrap::unwrap;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::auto_generated_mod::common_structs_mod::*;
+use crate::html_mod::*;
+use crate::row_on_click;
+use crate::utils_mod::row_element_id;
+use crate::web_sys_mod as w;
+
+/// the detailed list can be very long for big crates
+const MAX_FINDINGS_SHOWN: usize = 500;
+
+/// the kind is the name of the field in RiskCountsData and the label is for the GUI
+pub const RISK_KINDS: [(&str, &str); 10] = [
+    ("unsafe_code", "unsafe"),
+    ("build_script", "build.rs"),
+    ("proc_macro", "proc-macro"),
+    ("process", "process"),
+    ("network", "network"),
+    ("fs_write", "fs write"),
+    ("include_bytes", "include_bytes!"),
+    ("extern_c", "extern \"C\""),
+    ("no_mangle", "no_mangle"),
+    ("env", "env"),
+];
+
+lazy_static! {
+    /// mutable static, because it is hard to pass variables around with on_click events
+    static ref RISK_SCAN_DATA: Mutex<RiskScanData> = Mutex::new(RiskScanData::default());
+}
+
+pub fn risk_count(counts: &RiskCountsData, kind: &str) -> usize {
+    match kind {
+        "unsafe_code" => counts.unsafe_code,
+        "build_script" => counts.build_script,
+        "proc_macro" => counts.proc_macro,
+        "process" => counts.process,
+        "network" => counts.network,
+        "fs_write" => counts.fs_write,
+        "include_bytes" => counts.include_bytes,
+        "extern_c" => counts.extern_c,
+        "no_mangle" => counts.no_mangle,
+        "env" => counts.env,
+        _ => 0,
+    }
+}
+
+/// the label of the kind for the GUI
+fn risk_label(kind: &str) -> &str {
+    RISK_KINDS.iter().find(|(x, _)| *x == kind).map(|(_, label)| *label).unwrap_or(kind)
+}
+
+/// the list is rendered as raw html into the review editor, because it comes after the review data
+#[named]
+pub fn cln_risk_scan(srv_response: RpcResponse) {
+    log::info!("{}", function_name!());
+    *RISK_SCAN_DATA.lock().unwrap() = unwrap!(serde_json::from_value(srv_response.response_data));
+    if unwrap!(w::window().document()).get_element_by_id("div_risk_scan").is_none() {
+        return;
+    }
+    let count_shown = {
+        let data = RISK_SCAN_DATA.lock().unwrap();
+        let summary: Vec<String> = RISK_KINDS
+            .iter()
+            .map(|(kind, label)| (label, risk_count(&data.counts, kind)))
+            .filter(|(_, count)| *count > 0)
+            .map(|(label, count)| format!("{}: {}", label, count))
+            .collect();
+        let mut html = if summary.is_empty() {
+            String::from("<p>risk scan: nothing found</p>")
+        } else {
+            format!("<p>risk scan: {}</p>", encode_5_xml_control_characters(&summary.join(", ")))
+        };
+        let count_shown = data.list_of_finding.len().min(MAX_FINDINGS_SHOWN);
+        for (row_number, x) in data.list_of_finding.iter().take(count_shown).enumerate() {
+            html.push_str(&format!(
+                r#"<div class="risk_finding"><span class="risk_kind">{}</span> <span id="{}" class="c_link_1 source_tree_file">{}:{}</span> <code>{}</code></div>"#,
+                encode_5_xml_control_characters(risk_label(&x.kind)),
+                row_element_id("button_risk_finding", row_number),
+                encode_5_xml_control_characters(&x.path),
+                x.line_number,
+                encode_5_xml_control_characters(&x.text),
+            ));
+        }
+        if data.list_of_finding.len() > count_shown {
+            html.push_str(&format!("<p>... and {} more</p>", data.list_of_finding.len() - count_shown));
+        }
+        w::set_inner_html("div_risk_scan", &html);
+        count_shown
+    };
+    for row_number in 0..count_shown {
+        row_on_click!("button_risk_finding", row_number, risk_finding_on_click);
+    }
+}
+
+/// open the source browser at the file and line
+#[named]
+fn risk_finding_on_click(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    let data = RISK_SCAN_DATA.lock().unwrap();
+    let finding = &data.list_of_finding[row_number];
+    crate::cln_methods_mod::cln_source_mod::open_source_browser_at(&data.crate_name, &data.crate_version, &finding.path, finding.line_number);
+}
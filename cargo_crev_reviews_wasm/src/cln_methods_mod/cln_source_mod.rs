@@ -0,0 +1,511 @@
+// cln_source_mod.rs
+
+//! read-only source browser: file tree and highlighted file content
+
+use crate::web_sys_mod as w;
+use function_name::named;
+use lazy_static::{__Deref, lazy_static};
+use std::sync::Mutex;
+use unwrap::unwrap;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use dev_bestia_html_templating as tmplt;
+use dev_bestia_string_utils::*;
+
+use crate::auto_generated_mod::{common_structs_mod::*, srv_methods};
+use crate::html_mod::*;
+use crate::{on_click, row_on_click};
+use crate::utils_mod::{crate_version_join, row_element_id};
+
+lazy_static! {
+    /// mutable static, because it is hard to pass variables around with on_click events
+    static ref SOURCE_TREE_DATA: Mutex<SourceTreeData> = Mutex::new(SourceTreeData::default());
+    static ref SOURCE_FILE_DATA: Mutex<SourceFileData> = Mutex::new(SourceFileData::default());
+    static ref ANNOTATION_LIST_DATA: Mutex<AnnotationListData> = Mutex::new(AnnotationListData::default());
+    static ref ANNOTATION_EDIT_DATA: Mutex<AnnotationEditData> = Mutex::new(AnnotationEditData::default());
+    static ref READ_PROGRESS_DATA: Mutex<ReadProgressData> = Mutex::new(ReadProgressData::default());
+    static ref VCS_VERIFY_DATA: Mutex<VcsVerifyData> = Mutex::new(VcsVerifyData::default());
+    /// the file and line from the routing are opened after the tree
+    static ref OPEN_FILE_AFTER_TREE: Mutex<(String, usize)> = Mutex::new((String::new(), 0));
+    /// after the file is loaded, scroll to this line
+    static ref SCROLL_TO_LINE: Mutex<usize> = Mutex::new(0);
+}
+
+impl tmplt::HtmlTemplatingDataTrait for SourceTreeData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("SourceTreeData")
+    }
+
+    /// renders sub-template: "stmplt_" or "wtmplt_"
+    fn process_sub_template(&self, template_name: &str, sub_templates: &Vec<tmplt::utils::SubTemplate>) -> Vec<tmplt::utils::Node> {
+        log::info!("{}", template_name);
+        match template_name {
+            "wtmplt_SourceFileItemData" => {
+                let sub_template = unwrap!(sub_templates.iter().find(|&template| template.name == template_name));
+                let mut nodes = vec![];
+                for (row_number, item) in self.list_of_file.iter().enumerate() {
+                    let vec_node = unwrap!(tmplt::utils::process_template_raw_to_nodes(
+                        item,
+                        &sub_template.template,
+                        tmplt::utils::HtmlOrSvg::Html,
+                        "",
+                        row_number,
+                    ));
+                    nodes.extend_from_slice(&vec_node);
+                }
+                // return
+                nodes
+            }
+            _ => tmplt::utils::match_else_for_process_sub_template(&self.data_model_name(), template_name),
+        }
+    }
+
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        // log::debug!(&placeholder);
+        match placeholder {
+            "wt_cargo_crev_reviews_version" => s!(env!("CARGO_PKG_VERSION")),
+            "wt_crate_name_version" => crate_version_join(&self.crate_name, &self.crate_version),
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+impl tmplt::HtmlTemplatingDataTrait for SourceFileItemData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("SourceFileItemData")
+    }
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        match placeholder {
+            "wt_name" => {
+                if self.is_dir {
+                    format!("{}/", self.name)
+                } else {
+                    self.name.clone()
+                }
+            }
+            "wt_path" => self.path.clone(),
+            "wt_indent_style" => format!("padding-left: {}em;", self.depth * 2),
+            "wt_file_class" => {
+                if self.is_dir {
+                    "source_tree_dir".to_string()
+                } else {
+                    "source_tree_file c_link_1".to_string()
+                }
+            }
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+impl tmplt::HtmlTemplatingDataTrait for AnnotationEditData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("AnnotationEditData")
+    }
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        match placeholder {
+            "wt_path" => self.annotation.path.clone(),
+            "wt_line_start" => self.annotation.line_start.to_string(),
+            "wt_line_end" => self.annotation.line_end.to_string(),
+            "wt_note" => self.annotation.note.clone(),
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+/// open the source browser in a new tab
+pub fn open_source_browser(crate_name: &str, crate_version: &str) {
+    let url = format!("index.html#source_tree/{}/{}", crate_name, crate_version);
+    unwrap!(w::window().open_with_url(&url));
+}
+
+/// open the source browser in a new tab and show the file at the line
+/// The path is percent-encoded, because the hash routing splits the params on `/`.
+pub fn open_source_browser_at(crate_name: &str, crate_version: &str, path: &str, line_number: usize) {
+    let path = percent_encoding::utf8_percent_encode(path, percent_encoding::NON_ALPHANUMERIC);
+    let url = format!("index.html#source_tree/{}/{}:{}:{}", crate_name, crate_version, path, line_number);
+    unwrap!(w::window().open_with_url(&url));
+}
+
+// open_source_browser > routing_source_tree > srv_source_tree > cln_source_tree
+
+/// param3 is the version or `version:path:line`
+pub fn routing_source_tree(param2: &str, param3: &str) {
+    let mut spl = param3.split(':');
+    let crate_version = spl.next().unwrap_or("");
+    let path = percent_encoding::percent_decode_str(spl.next().unwrap_or("")).decode_utf8_lossy().to_string();
+    let line_number: usize = spl.next().unwrap_or("").parse().unwrap_or(0);
+    *OPEN_FILE_AFTER_TREE.lock().unwrap() = (path, line_number);
+
+    let request_data = ReviewFilterData {
+        crate_name: param2.to_string(),
+        crate_version: Some(crate_version.to_string()),
+        old_crate_version: None,
+        source: String::new(),
+    };
+    srv_methods::srv_source_tree(request_data);
+}
+
+#[named]
+pub fn cln_source_tree(srv_response: RpcResponse) {
+    log::info!("{}", function_name!());
+    let html = extract_html(&srv_response);
+    *SOURCE_TREE_DATA.lock().unwrap() = unwrap!(serde_json::from_value(srv_response.response_data));
+    // the mutex is locked inside a scope. When this structure falls out of scope, the lock will be unlocked.
+    let html_after_process = {
+        let data = SOURCE_TREE_DATA.lock().unwrap();
+        tmplt::process_html(data.deref(), &html)
+    };
+    inject_into_html(&html_after_process);
+
+    // on_click only for files
+    for (row_number, item) in SOURCE_TREE_DATA.lock().unwrap().list_of_file.iter().enumerate() {
+        if !item.is_dir {
+            row_on_click!("button_source_file", row_number, request_source_file);
+        }
+    }
+    on_click!("button_annotation_new", request_annotation_new);
+    on_click!("button_mark_read", request_mark_read);
+    on_click!("button_vcs_verify", request_vcs_verify);
+
+    let request_data = {
+        let data = SOURCE_TREE_DATA.lock().unwrap();
+        ReviewFilterData {
+            crate_name: data.crate_name.clone(),
+            crate_version: Some(data.crate_version.clone()),
+            old_crate_version: None,
+            source: String::new(),
+        }
+    };
+    srv_methods::srv_annotation_list(&request_data);
+    srv_methods::srv_read_progress(&request_data);
+
+    let (path, line_number) = std::mem::take(&mut *OPEN_FILE_AFTER_TREE.lock().unwrap());
+    if !path.is_empty() {
+        *SCROLL_TO_LINE.lock().unwrap() = line_number;
+        request_source_file_by_path(&path);
+    }
+}
+
+#[named]
+fn request_source_file(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    let path = SOURCE_TREE_DATA.lock().unwrap().list_of_file[row_number].path.clone();
+    request_source_file_by_path(&path);
+}
+
+fn request_source_file_by_path(path: &str) {
+    let data = SOURCE_TREE_DATA.lock().unwrap();
+    let request_data = SourceFileFilterData {
+        crate_name: data.crate_name.clone(),
+        crate_version: data.crate_version.clone(),
+        path: path.to_string(),
+    };
+    srv_methods::srv_source_file(request_data);
+}
+
+#[named]
+pub fn cln_source_file(srv_response: RpcResponse) {
+    log::info!("{}", function_name!());
+    *SOURCE_FILE_DATA.lock().unwrap() = unwrap!(serde_json::from_value(srv_response.response_data));
+    render_source_file();
+    render_read_progress();
+    let line_number = std::mem::take(&mut *SCROLL_TO_LINE.lock().unwrap());
+    if line_number > 0 {
+        if let Some(element) = w::window().document().unwrap().get_element_by_id(&format!("line_{}", line_number)) {
+            element.scroll_into_view();
+        }
+    }
+}
+
+/// the lines are already highlighted and escaped by the server, so they are injected as html
+/// The annotated lines of this file are marked.
+fn render_source_file() {
+    let data = SOURCE_FILE_DATA.lock().unwrap();
+    if data.path.is_empty() {
+        return;
+    }
+    let annotations = ANNOTATION_LIST_DATA.lock().unwrap();
+    let is_annotated = |line_number: usize| {
+        annotations
+            .list_of_annotation
+            .iter()
+            .any(|x| x.path == data.path && line_number >= x.line_start && line_number <= x.line_end.max(x.line_start))
+    };
+    w::set_text("source_file_path", &format!("{} ({})", data.path, data.syntax_name));
+    let mut html = String::new();
+    if data.binary {
+        html.push_str(r#"<div class="c_alert">Binary file is not shown.</div>"#);
+    }
+    for line in data.list_of_line.iter() {
+        let class = if is_annotated(line.line_number) { "source_line source_line_annotated" } else { "source_line" };
+        html.push_str(&format!(
+            r#"<div class="{0}" id="line_{1}"><span class="source_line_number">{1}</span><span class="source_code">{2}</span></div>"#,
+            class, line.line_number, line.html
+        ));
+    }
+    if data.truncated {
+        html.push_str(r#"<div class="c_alert">The file is too big. Only the beginning is shown.</div>"#);
+    }
+    w::set_inner_html("div_source_file", &html);
+}
+
+// region: review progress
+
+#[named]
+fn request_mark_read(_element_id: &str) {
+    log::info!("{}", function_name!());
+    let data = SOURCE_FILE_DATA.lock().unwrap();
+    if data.path.is_empty() {
+        show_modal_message("Open a file first.");
+        return;
+    }
+    let request_data = SourceFileFilterData {
+        crate_name: data.crate_name.clone(),
+        crate_version: data.crate_version.clone(),
+        path: data.path.clone(),
+    };
+    srv_methods::srv_source_mark_read(request_data);
+}
+
+/// the same response is used in the source browser and in the review editor
+#[named]
+pub fn cln_read_progress(srv_response: RpcResponse) {
+    log::info!("{}", function_name!());
+    *READ_PROGRESS_DATA.lock().unwrap() = unwrap!(serde_json::from_value(srv_response.response_data));
+    render_read_progress();
+}
+
+/// only the elements that exist in the current page are changed
+fn render_read_progress() {
+    let document = unwrap!(w::window().document());
+    let progress = READ_PROGRESS_DATA.lock().unwrap();
+    if document.get_element_by_id("div_read_progress").is_some() {
+        w::set_text(
+            "div_read_progress",
+            &format!(
+                "code read: {}% ({} of {} lines, {} of {} files), suggested thoroughness: {}",
+                progress.percent_read, progress.lines_read, progress.lines_total, progress.files_read, progress.files_total, progress.suggested_thoroughness
+            ),
+        );
+    }
+    if document.get_element_by_id("button_mark_read").is_some() {
+        let path = SOURCE_FILE_DATA.lock().unwrap().path.clone();
+        let text = if progress.list_of_read_path.contains(&path) { "Mark as unread" } else { "Mark as read" };
+        w::set_text("button_mark_read", text);
+        // mark the read files in the tree
+        for (row_number, item) in SOURCE_TREE_DATA.lock().unwrap().list_of_file.iter().enumerate() {
+            if !item.is_dir {
+                let class = if progress.list_of_read_path.contains(&item.path) {
+                    "source_tree_file c_link_1 source_tree_read"
+                } else {
+                    "source_tree_file c_link_1"
+                };
+                unwrap!(w::get_element_by_id(&row_element_id("button_source_file", row_number)).set_attribute("class", class));
+            }
+        }
+    }
+}
+
+// endregion: review progress
+
+// region: annotations
+
+/// the list of draft annotations is rendered as raw html, because it changes without rendering the whole page
+#[named]
+pub fn cln_annotation_list(srv_response: RpcResponse) {
+    log::info!("{}", function_name!());
+    *ANNOTATION_LIST_DATA.lock().unwrap() = unwrap!(serde_json::from_value(srv_response.response_data));
+    let count = {
+        let data = ANNOTATION_LIST_DATA.lock().unwrap();
+        let mut html = String::new();
+        for (row_number, x) in data.list_of_annotation.iter().enumerate() {
+            let location = if x.line_end > x.line_start {
+                format!("{}:{}-{}", x.path, x.line_start, x.line_end)
+            } else {
+                format!("{}:{}", x.path, x.line_start)
+            };
+            html.push_str(&format!(
+                r#"<div class="annotation_item"><span id="{0}" class="c_link_1 source_tree_file">{1}</span> {2} <span id="{3}" class="c_link_1 source_tree_file">edit</span> <span id="{4}" class="c_link_1 source_tree_file">delete</span></div>"#,
+                row_element_id("button_annotation_goto", row_number),
+                encode_5_xml_control_characters(&location),
+                encode_5_xml_control_characters(&x.note),
+                row_element_id("button_annotation_edit", row_number),
+                row_element_id("button_annotation_delete", row_number),
+            ));
+        }
+        w::set_inner_html("div_annotation_list", &html);
+        data.list_of_annotation.len()
+    };
+    for row_number in 0..count {
+        row_on_click!("button_annotation_goto", row_number, annotation_goto);
+        row_on_click!("button_annotation_edit", row_number, request_annotation_edit);
+        row_on_click!("button_annotation_delete", row_number, request_annotation_delete);
+    }
+    // the marks of annotated lines
+    render_source_file();
+}
+
+#[named]
+fn annotation_goto(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    let annotation = ANNOTATION_LIST_DATA.lock().unwrap().list_of_annotation[row_number].clone();
+    *SCROLL_TO_LINE.lock().unwrap() = annotation.line_start;
+    request_source_file_by_path(&annotation.path);
+}
+
+/// new annotation for the lines from the toolbar in the opened file
+#[named]
+fn request_annotation_new(_element_id: &str) {
+    log::info!("{}", function_name!());
+    let path = SOURCE_FILE_DATA.lock().unwrap().path.clone();
+    if path.is_empty() {
+        show_modal_message("Open a file first.");
+        return;
+    }
+    let line_start: usize = w::get_input_element_value_string_by_id("annotation_line_start").trim().parse().unwrap_or(0);
+    let line_end: usize = w::get_input_element_value_string_by_id("annotation_line_end").trim().parse().unwrap_or(line_start);
+    let request_data = {
+        let tree = SOURCE_TREE_DATA.lock().unwrap();
+        AnnotationEditData {
+            crate_name: tree.crate_name.clone(),
+            crate_version: tree.crate_version.clone(),
+            row_number: None,
+            annotation: AnnotationItemData {
+                path,
+                line_start,
+                line_end: line_end.max(line_start),
+                note: String::new(),
+            },
+        }
+    };
+    srv_methods::srv_annotation_edit(request_data);
+}
+
+#[named]
+fn request_annotation_edit(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    let request_data = {
+        let data = ANNOTATION_LIST_DATA.lock().unwrap();
+        AnnotationEditData {
+            crate_name: data.crate_name.clone(),
+            crate_version: data.crate_version.clone(),
+            row_number: Some(row_number),
+            annotation: data.list_of_annotation[row_number].clone(),
+        }
+    };
+    srv_methods::srv_annotation_edit(request_data);
+}
+
+#[named]
+fn request_annotation_delete(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    let request_data = {
+        let data = ANNOTATION_LIST_DATA.lock().unwrap();
+        AnnotationEditData {
+            crate_name: data.crate_name.clone(),
+            crate_version: data.crate_version.clone(),
+            row_number: Some(row_number),
+            annotation: AnnotationItemData::default(),
+        }
+    };
+    srv_methods::srv_annotation_delete(request_data);
+}
+
+#[named]
+pub fn cln_annotation_edit_modal(srv_response: RpcResponse) {
+    log::info!("{}", function_name!());
+    // request_annotation_edit > srv_annotation_edit > cln_annotation_edit_modal
+    let html = extract_html(&srv_response);
+    *ANNOTATION_EDIT_DATA.lock().unwrap() = unwrap!(serde_json::from_value(srv_response.response_data));
+    let html_after_process = {
+        let data = ANNOTATION_EDIT_DATA.lock().unwrap();
+        tmplt::process_html(data.deref(), &html)
+    };
+    show_modal_html(&html_after_process);
+
+    use crate::cln_methods_mod::cln_utils_mod::modal_close_on_click;
+    on_click!("modal_close", modal_close_on_click);
+    on_click!("annotation_save", annotation_save);
+}
+
+#[named]
+fn annotation_save(_element_id: &str) {
+    log::info!("{}", function_name!());
+    let mut request_data = ANNOTATION_EDIT_DATA.lock().unwrap().clone();
+    request_data.annotation.line_start = w::get_input_element_value_string_by_id("modal_line_start").trim().parse().unwrap_or(0);
+    request_data.annotation.line_end = w::get_input_element_value_string_by_id("modal_line_end").trim().parse().unwrap_or(0);
+    request_data.annotation.note = w::get_text_area_element_value_string_by_id("modal_note");
+    close_modal();
+    srv_methods::srv_annotation_save(request_data);
+}
+
+// endregion: annotations
+
+// region: vcs verify
+
+#[named]
+fn request_vcs_verify(_element_id: &str) {
+    log::info!("{}", function_name!());
+    let request_data = {
+        let tree = SOURCE_TREE_DATA.lock().unwrap();
+        VcsVerifyFilterData {
+            crate_name: tree.crate_name.clone(),
+            crate_version: tree.crate_version.clone(),
+            repo_path: w::get_input_element_value_string_by_id("vcs_repo_path"),
+        }
+    };
+    w::set_text("div_vcs_verify", "Comparing with the git commit...");
+    srv_methods::srv_vcs_verify(request_data);
+}
+
+/// the files that do not match the repository are rendered as raw html, a click opens the file
+#[named]
+pub fn cln_vcs_verify(srv_response: RpcResponse) {
+    log::info!("{}", function_name!());
+    *VCS_VERIFY_DATA.lock().unwrap() = unwrap!(serde_json::from_value(srv_response.response_data));
+    let count = {
+        let data = VCS_VERIFY_DATA.lock().unwrap();
+        let mut html = format!(
+            "<div>git commit {} path_in_vcs '{}': {} of {} files match the repository.</div>",
+            encode_5_xml_control_characters(&data.git_sha1),
+            encode_5_xml_control_characters(&data.path_in_vcs),
+            data.files_checked - data.list_of_file.len(),
+            data.files_checked
+        );
+        if data.dirty {
+            html.push_str(r#"<div class="c_alert">The package was published with uncommitted changes.</div>"#);
+        }
+        for (row_number, x) in data.list_of_file.iter().enumerate() {
+            html.push_str(&format!(
+                r#"<div class="c_alert">{0} <span id="{1}" class="c_link_1 source_tree_file">{2}</span></div>"#,
+                encode_5_xml_control_characters(&x.status),
+                row_element_id("button_vcs_file", row_number),
+                encode_5_xml_control_characters(&x.path),
+            ));
+        }
+        w::set_inner_html("div_vcs_verify", &html);
+        data.list_of_file.len()
+    };
+    for row_number in 0..count {
+        row_on_click!("button_vcs_file", row_number, vcs_file_open);
+    }
+}
+
+#[named]
+fn vcs_file_open(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    let path = VCS_VERIFY_DATA.lock().unwrap().list_of_file[row_number].path.clone();
+    request_source_file_by_path(&path);
+}
+
+// endregion: vcs verify
@@ -2,14 +2,19 @@
 
 //! helper functions and utils
 
-use crate::auto_generated_mod::common_structs_mod::{RpcMessageData, RpcResponse};
+use crate::auto_generated_mod::common_structs_mod::{RpcMessageData, RpcPendingData, RpcResponse};
+use crate::auto_generated_mod::srv_methods;
 use crate::on_click;
+use crate::web_sys_mod as w;
 use crate::*;
 use dev_bestia_html_templating as tmplt;
 use function_name::named;
 use unwrap::unwrap;
 use wasm_bindgen::JsCast;
 
+/// pause before the client asks again for the result of a long method
+const RPC_RESULT_POLL_MS: i32 = 300;
+
 #[named]
 pub fn cln_modal_error(srv_response: RpcResponse) {
     log::info!("{}", function_name!());
@@ -25,6 +30,12 @@ pub fn cln_no_action(_srv_response: RpcResponse) {
     log::info!("{}", function_name!());
 }
 
+/// the long method runs on a worker thread of the server, ask again for the result after a short pause
+pub fn cln_rpc_pending(srv_response: RpcResponse) {
+    let pending: RpcPendingData = unwrap!(serde_json::from_value(srv_response.response_data));
+    w::set_timeout(RPC_RESULT_POLL_MS, move || srv_methods::srv_rpc_result(pending));
+}
+
 pub fn cln_modal_close(_srv_response: RpcResponse) {
     html_mod::close_modal();
 }
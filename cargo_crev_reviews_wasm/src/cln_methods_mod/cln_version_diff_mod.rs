@@ -0,0 +1,180 @@
+// cln_version_diff_mod.rs
+
+//! side-by-side diff between two versions of a crate
+
+use function_name::named;
+use lazy_static::{__Deref, lazy_static};
+use std::sync::Mutex;
+use unwrap::unwrap;
+
+use dev_bestia_html_templating as tmplt;
+use dev_bestia_string_utils::*;
+
+use crate::auto_generated_mod::{common_structs_mod::*, srv_methods};
+use crate::html_mod::*;
+
+lazy_static! {
+    /// mutable static, because it is hard to pass variables around with on_click events
+    static ref VERSION_DIFF_DATA: Mutex<VersionDiffData> = Mutex::new(VersionDiffData::default());
+}
+
+impl tmplt::HtmlTemplatingDataTrait for VersionDiffData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("VersionDiffData")
+    }
+
+    /// renders sub-template: "stmplt_" or "wtmplt_"
+    fn process_sub_template(&self, template_name: &str, sub_templates: &Vec<tmplt::utils::SubTemplate>) -> Vec<tmplt::utils::Node> {
+        log::info!("{}", template_name);
+        match template_name {
+            "wtmplt_FileDiffItemData" => {
+                let sub_template = unwrap!(sub_templates.iter().find(|&template| template.name == template_name));
+                let mut nodes = vec![];
+                for (row_number, item) in self.list_of_file.iter().enumerate() {
+                    let vec_node = unwrap!(tmplt::utils::process_template_raw_to_nodes(
+                        item,
+                        &sub_template.template,
+                        tmplt::utils::HtmlOrSvg::Html,
+                        "",
+                        row_number,
+                    ));
+                    nodes.extend_from_slice(&vec_node);
+                }
+                // return
+                nodes
+            }
+            "wtmplt_DiffRowItemData" => {
+                let sub_template = unwrap!(sub_templates.iter().find(|&template| template.name == template_name));
+                let mut nodes = vec![];
+                for (row_number, item) in self.list_of_row.iter().enumerate() {
+                    let vec_node = unwrap!(tmplt::utils::process_template_raw_to_nodes(
+                        item,
+                        &sub_template.template,
+                        tmplt::utils::HtmlOrSvg::Html,
+                        "",
+                        row_number,
+                    ));
+                    nodes.extend_from_slice(&vec_node);
+                }
+                // return
+                nodes
+            }
+            _ => tmplt::utils::match_else_for_process_sub_template(&self.data_model_name(), template_name),
+        }
+    }
+
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        // log::debug!(&placeholder);
+        match placeholder {
+            "wt_cargo_crev_reviews_version" => s!(env!("CARGO_PKG_VERSION")),
+            "wt_crate_name" => self.crate_name.clone(),
+            "wt_old_version" => self.old_version.clone(),
+            "wt_new_version" => self.new_version.clone(),
+            "wt_count_of_files" => self.list_of_file.len().to_string(),
+            "wt_lines_added" => self.lines_added.to_string(),
+            "wt_lines_removed" => self.lines_removed.to_string(),
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+
+    /// boolean : is the next node rendered or not: "wb_" or "sb_"
+    fn exists_next_node_or_attribute(&self, placeholder: &str) -> bool {
+        match placeholder {
+            "wb_truncated" => self.truncated,
+            _ => tmplt::utils::match_else_for_exists_next_node_or_attribute(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+impl tmplt::HtmlTemplatingDataTrait for FileDiffItemData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("FileDiffItemData")
+    }
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        match placeholder {
+            "wt_path" => self.path.clone(),
+            "wt_status" => self.status.clone(),
+            "wt_status_class" => format!("review_header0_cell c_diff_{}", self.status),
+            "wt_lines_changed" => {
+                if self.binary {
+                    "binary".to_string()
+                } else {
+                    format!("+{} -{}", self.lines_added, self.lines_removed)
+                }
+            }
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+impl tmplt::HtmlTemplatingDataTrait for DiffRowItemData {
+    /// data model name is used for eprint
+    fn data_model_name(&self) -> String {
+        // return
+        s!("DiffRowItemData")
+    }
+    /// returns a String to replace the next text-node: "wt_" or "st_"
+    fn replace_with_string(&self, placeholder: &str, _subtemplate_name: &str, _pos_cursor: usize) -> String {
+        match placeholder {
+            "wt_text" => self.text.clone(),
+            "wt_old_line_number" => self.old_line_number.clone(),
+            "wt_old_text" => self.old_text.clone(),
+            "wt_new_line_number" => self.new_line_number.clone(),
+            "wt_new_text" => self.new_text.clone(),
+            "wt_old_class" => match self.change.as_str() {
+                "delete" | "replace" => "diff_cell diff_delete".to_string(),
+                "insert" => "diff_cell diff_empty".to_string(),
+                _ => "diff_cell".to_string(),
+            },
+            "wt_new_class" => match self.change.as_str() {
+                "insert" | "replace" => "diff_cell diff_insert".to_string(),
+                "delete" => "diff_cell diff_empty".to_string(),
+                _ => "diff_cell".to_string(),
+            },
+            _ => tmplt::utils::match_else_for_replace_with_string(&self.data_model_name(), placeholder),
+        }
+    }
+
+    /// boolean : is the next node rendered or not: "wb_" or "sb_"
+    fn exists_next_node_or_attribute(&self, placeholder: &str) -> bool {
+        match placeholder {
+            "wb_row_is_file" => self.row_kind == "file",
+            "wb_row_is_hunk" => self.row_kind == "hunk",
+            "wb_row_is_line" => self.row_kind == "line",
+            _ => tmplt::utils::match_else_for_exists_next_node_or_attribute(&self.data_model_name(), placeholder),
+        }
+    }
+}
+
+// cln_version_mod::button_version_diff_on_click > routing_version_diff > srv_version_diff > cln_version_diff
+
+/// param3 is like `1.2.3..1.2.4`, because the hash routing has only 3 params
+pub fn routing_version_diff(param2: &str, param3: &str) {
+    let (old_version, new_version) = param3.split_once("..").unwrap_or(("", param3));
+    let request_data = ReviewFilterData {
+        crate_name: param2.to_string(),
+        crate_version: Some(new_version.to_string()),
+        old_crate_version: Some(old_version.to_string()),
+        source: String::new(),
+    };
+    srv_methods::srv_version_diff(request_data);
+}
+
+#[named]
+pub fn cln_version_diff(srv_response: RpcResponse) {
+    log::info!("{}", function_name!());
+    let html = extract_html(&srv_response);
+    *VERSION_DIFF_DATA.lock().unwrap() = unwrap!(serde_json::from_value(srv_response.response_data));
+    // the mutex is locked inside a scope. When this structure falls out of scope, the lock will be unlocked.
+    let html_after_process = {
+        let data = VERSION_DIFF_DATA.lock().unwrap();
+        tmplt::process_html(data.deref(), &html)
+    };
+    inject_into_html(&html_after_process);
+}
@@ -83,17 +83,14 @@ impl tmplt::HtmlTemplatingDataTrait for VersionItemData {
             "wt_crate_published_by_url" => {
                 crate::cln_methods_mod::cln_publisher_item_mod::published_by_url_shorten(self.published_by_url.as_deref().unwrap_or("")).to_string()
             }
+            // any published version can be reviewed, the source code is downloaded if it is not cached
             "wt_edit_or_new" => {
                 if self.yanked {
                     "".to_string()
-                } else if self.is_src_cached.unwrap_or(false) {
-                    if self.my_review.is_some() {
-                        "Edit".to_string()
-                    } else {
-                        "New".to_string()
-                    }
+                } else if self.my_review.is_some() {
+                    "Edit".to_string()
                 } else {
-                    "".to_string()
+                    "New".to_string()
                 }
             }
             "wt_crate_yanked_or_cached" => {
@@ -159,6 +156,7 @@ pub fn routing_version_list(param2: &str) {
         crate_name: param2.to_string(),
         crate_version: None,
         old_crate_version: None,
+        source: String::new(),
     };
     srv_methods::srv_version_list(request_data);
 }
@@ -176,12 +174,13 @@ pub fn cln_version_list(srv_response: RpcResponse) {
 
     inject_into_html(&html_after_process);
 
+    on_click!("button_version_diff", button_version_diff_on_click);
     // on_click for every row of the list
     for (row_number, item) in VERSION_LIST_DATA.lock().unwrap().list_of_version.iter().enumerate() {
         // button New or Edit
         if item.my_review.is_some() {
             row_on_click!("button_review_edit_or_new", row_number, request_review_edit_or_new);
-        } else if item.is_src_cached == Some(true) {
+        } else if !item.yanked {
             row_on_click!("button_review_edit_or_new", row_number, request_review_edit_or_new);
         }
         // sub-menu
@@ -190,11 +189,33 @@ pub fn cln_version_list(srv_response: RpcResponse) {
             row_on_click!("button_open_crates_io", row_number, button_open_crates_io_onclick);
             row_on_click!("button_open_lib_rs", row_number, button_open_lib_rs_onclick);
             row_on_click!("button_open_source_code", row_number, button_open_source_code_onclick);
+            row_on_click!("button_browse_source", row_number, button_browse_source_onclick);
             row_on_click!("button_review_delete", row_number, modal_delete);
         }
     }
 }
 
+/// open the diff of the two checked versions in a new tab
+#[named]
+fn button_version_diff_on_click(_element_id: &str) {
+    log::info!("{}", function_name!());
+    let data = VERSION_LIST_DATA.lock().unwrap();
+    let mut checked_versions = vec![];
+    for (row_number, item) in data.list_of_version.iter().enumerate() {
+        let element_id = if row_number > 0 { format!("checkbox_diff({})", row_number) } else { "checkbox_diff".to_string() };
+        if w::get_input_html_element_by_id(&element_id).checked() {
+            checked_versions.push(item.crate_version.clone());
+        }
+    }
+    if checked_versions.len() != 2 {
+        show_modal_message("Check exactly two versions to compare.");
+        return;
+    }
+    // the list is in descending order
+    let url = format!("index.html#version_diff/{}/{}..{}", data.list_of_version[0].crate_name, checked_versions[1], checked_versions[0]);
+    unwrap!(w::window().open_with_url(&url));
+}
+
 #[named]
 fn request_review_edit_or_new(_element_id: &str, row_number: usize) {
     log::info!("{}", function_name!());
@@ -240,10 +261,19 @@ fn button_open_source_code_onclick(_element_id: &str, row_number: usize) {
         crate_name: item.crate_name.clone(),
         crate_version: Some(item.crate_version.clone()),
         old_crate_version: None,
+        source: String::new(),
     };
     srv_methods::srv_review_open_source_code(request_data);
 }
 
+#[named]
+fn button_browse_source_onclick(_element_id: &str, row_number: usize) {
+    log::info!("{}", function_name!());
+    // from list get crate name and version
+    let item = &VERSION_LIST_DATA.lock().unwrap().list_of_version[row_number];
+    crate::cln_methods_mod::cln_source_mod::open_source_browser(&item.crate_name, &item.crate_version);
+}
+
 #[named]
 pub fn modal_delete(_element_id: &str, row_number: usize) {
     log::info!("{}", function_name!());
@@ -271,12 +301,12 @@ fn request_review_delete(_element_id: &str, row_number: usize) {
     use crate::cln_methods_mod::cln_utils_mod::modal_close_on_click;
     modal_close_on_click("");
 
-    // from list get crate name and version
+    // from list get crate name, version and source
     let item = &VERSION_LIST_DATA.lock().unwrap().list_of_version[row_number];
-    let request_data = ReviewFilterData {
+    let request_data = ReviewDeleteData {
         crate_name: item.crate_name.clone(),
-        crate_version: Some(item.crate_version.clone()),
-        old_crate_version: None,
+        crate_version: item.crate_version.clone(),
+        source: item.my_review.as_ref().map(|x| x.source.clone()).unwrap_or_default(),
     };
     srv_methods::srv_review_delete(request_data);
 }
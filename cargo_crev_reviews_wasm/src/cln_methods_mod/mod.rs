@@ -4,9 +4,14 @@
 
 pub mod cln_cargo_tree_mod;
 pub mod cln_config_mod;
+pub mod cln_jobs_mod;
+pub mod cln_proof_problem_mod;
 pub mod cln_publisher_item_mod;
 pub mod cln_publisher_list_mod;
 pub mod cln_review_item_mod;
 pub mod cln_review_list_mod;
+pub mod cln_risk_scan_mod;
+pub mod cln_source_mod;
 pub mod cln_utils_mod;
+pub mod cln_version_diff_mod;
 pub mod cln_version_mod;
@@ -2,14 +2,30 @@
 
 //! generic code to process html
 
+use lazy_static::lazy_static;
+use std::sync::Mutex;
 use unwrap::unwrap;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
 
 use crate::auto_generated_mod::common_structs_mod::*;
 use crate::web_sys_mod as w;
 use dev_bestia_string_utils::*;
 
+/// the live log shows the last lines
+const MAX_PROGRESS_LOG: usize = 200;
+
+lazy_static! {
+    /// the live log of the progress modal
+    static ref PROGRESS_LOG: Mutex<Vec<String>> = Mutex::new(vec![]);
+}
+
+thread_local! {
+    /// the open event stream of the progress modal, the js object cannot be in a Mutex
+    static PROGRESS_EVENT_SOURCE: std::cell::RefCell<Option<web_sys::EventSource>> = std::cell::RefCell::new(None);
+}
+
 pub fn post_request_await_run_response_method<T>(request_method: &str, request_data: T)
 where
     T: serde::Serialize,
@@ -18,18 +34,45 @@ where
     let rpc = RpcRequest {
         request_method: request_method.to_string(),
         request_data: data,
+        token: session_token(),
     };
     let json_string = unwrap!(serde_json::to_string(&rpc));
     let rpc_request = JsValue::from_str(&json_string);
 
     spawn_local(async move {
         let rpc_request = Some(&rpc_request);
-        let resp_body_text = w::fetch_post_response("submit", rpc_request).await;
-        let srv_response: RpcResponse = unwrap!(serde_json::from_str(&resp_body_text));
+        let resp_body_text = w::fetch_post_response(&server_url("submit"), rpc_request).await;
+        let srv_response: Result<RpcResponse, _> = serde_json::from_str(&resp_body_text);
+        // the response or the error ends the progress, only the pending long method continues
+        if !matches!(&srv_response, Ok(x) if x.response_method == "cln_rpc_pending") {
+            close_progress();
+        }
+        let srv_response = unwrap!(srv_response);
         crate::auto_generated_mod::match_response_method_and_call_function(srv_response).await;
     });
 }
 
+/// the url on the same host and port that opened this page
+pub fn server_url(path: &str) -> String {
+    let origin = unwrap!(w::window().location().origin());
+    format!("{}/{}/{}", origin, crate::SERVER_FIRST_SUBDIRECTORY.as_str(), path)
+}
+
+/// the server opens `index.html?token=...&progress_port=...`, the tabs opened later get them from localStorage
+/// Other origins cannot read the localStorage of this host and port.
+pub fn store_session_token() {
+    let search = w::window().location().search().unwrap_or_default();
+    for (param, key) in [("token=", "cargo_crev_reviews_token"), ("progress_port=", "cargo_crev_reviews_progress_port")] {
+        if let Some(value) = search.trim_start_matches('?').split('&').find_map(|x| x.strip_prefix(param)) {
+            w::local_storage_set_item(key, value);
+        }
+    }
+}
+
+fn session_token() -> String {
+    w::local_storage_get_item("cargo_crev_reviews_token").unwrap_or_default()
+}
+
 // extract only the html inside the <body> </body>
 pub fn extract_html(response: &RpcResponse) -> String {
     extract_body_inner(&response.response_html)
@@ -56,6 +99,97 @@ pub fn show_modal_message(msg: &str) {
     w::set_inner_html("div_for_modal", &html);
 }
 
+/// show the progress bar and the live log in the modal until the operation is finished
+/// The server-sent events come from the progress listener on another port of the same host.
+pub fn show_modal_progress(operation: &str, title: &str) {
+    close_progress();
+    PROGRESS_LOG.lock().unwrap().clear();
+    render_progress(title, &ProgressEventData::default());
+    let progress_port = match w::local_storage_get_item("cargo_crev_reviews_progress_port") {
+        Some(progress_port) => progress_port,
+        None => return,
+    };
+    let location = w::window().location();
+    let url = format!(
+        "{}//{}:{}/{}/progress?token={}",
+        unwrap!(location.protocol()),
+        unwrap!(location.hostname()),
+        progress_port,
+        crate::SERVER_FIRST_SUBDIRECTORY.as_str(),
+        session_token()
+    );
+    let event_source = match web_sys::EventSource::new(&url) {
+        Ok(event_source) => event_source,
+        Err(_err) => return,
+    };
+    let event_source_clone = event_source.clone();
+    let operation = operation.to_string();
+    let title = title.to_string();
+    let on_message = Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+        let data = event.data().as_string().unwrap_or_default();
+        let progress: ProgressEventData = match serde_json::from_str(&data) {
+            Ok(progress) if progress.operation == operation => progress,
+            _ => return,
+        };
+        if progress.finished {
+            event_source_clone.close();
+            return;
+        }
+        {
+            let mut log = PROGRESS_LOG.lock().unwrap();
+            if !progress.error.is_empty() {
+                log.push(format!("error {}: {}", progress.current, progress.error));
+            } else if progress.m > 0 {
+                log.push(format!("{} of {}: {}", progress.n, progress.m, progress.current));
+            } else {
+                log.push(progress.current.clone());
+            }
+            let len = log.len();
+            if len > MAX_PROGRESS_LOG {
+                log.drain(..len - MAX_PROGRESS_LOG);
+            }
+        }
+        // the rpc response can replace the modal before the last events
+        if w::element_exists("modal_progress") {
+            render_progress(&title, &progress);
+        }
+    }) as Box<dyn FnMut(web_sys::MessageEvent)>);
+    event_source.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    on_message.forget();
+    PROGRESS_EVENT_SOURCE.with(|x| *x.borrow_mut() = Some(event_source));
+}
+
+/// the browser would reconnect the event stream forever
+fn close_progress() {
+    if let Some(event_source) = PROGRESS_EVENT_SOURCE.with(|x| x.borrow_mut().take()) {
+        event_source.close();
+    }
+}
+
+fn render_progress(title: &str, progress: &ProgressEventData) {
+    // without m the progress bar is indeterminate
+    let progress_bar = if progress.m > 0 {
+        format!(r#"<progress value="{}" max="{}" style="width:100%;"></progress>"#, progress.n, progress.m)
+    } else {
+        r#"<progress style="width:100%;"></progress>"#.to_string()
+    };
+    let log = PROGRESS_LOG.lock().unwrap().iter().rev().map(|x| encode_5_xml_control_characters(x)).collect::<Vec<String>>().join("\n");
+    let html = format!(
+        r#"
+<div id="modal_progress" class="w3_modal">
+    <div class="w3_modal_content">
+        <div class="pre-line">{}</div>
+        {}
+        <pre class="small" style="max-height:300px;overflow:auto;">{}</pre>
+    </div>
+</div>"#,
+        encode_5_xml_control_characters(title),
+        progress_bar,
+        log
+    );
+    w::set_inner_html("div_for_modal", &html);
+}
+
 pub fn show_modal_html(html: &str) {
     w::set_inner_html("div_for_modal", &html);
 }
@@ -40,11 +40,8 @@ pub use dev_bestia_url_utf8::*;
 use crate::web_sys_mod as w;
 
 lazy_static! {
-    /// 127.0.0.1
-    static ref SERVER_HOST: String=String::from("127.0.0.1");
-    /// 8182
-    static ref SERVER_PORT: String=String::from("8182");
     // first subdirectory /cargo_crev_reviews/
+    // the host and port come from window.location, because the server can listen on any port
     static ref SERVER_FIRST_SUBDIRECTORY: String=String::from("cargo_crev_reviews");
 }
 
@@ -58,6 +55,7 @@ pub fn wasm_bindgen_start() -> Result<(), JsValue> {
     // write the app version just for debug purposes
     log::info!("{} v{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
 
+    html_mod::store_session_token();
     routing_local_hash_mod::router_boilerplate();
 
     // return
@@ -14,8 +14,12 @@ fn router_for_local_hash_routing(param1: &str, param2: &str, param3: &str) {
     match param1 {
         "edit_or_new" => cln_methods_mod::cln_review_list_mod::routing_edit_or_new(param2, param3),
         "version_list" => cln_methods_mod::cln_version_mod::routing_version_list(param2),
+        "version_diff" => cln_methods_mod::cln_version_diff_mod::routing_version_diff(param2, param3),
+        "source_tree" => cln_methods_mod::cln_source_mod::routing_source_tree(param2, param3),
         "publisher_list" => cln_methods_mod::cln_publisher_list_mod::routing_publisher_list(),
         "config_edit" => cln_methods_mod::cln_config_mod::routing_config_edit(),
+        "proof_problem_list" => cln_methods_mod::cln_proof_problem_mod::routing_proof_problem_list(),
+        "jobs_list" => cln_methods_mod::cln_jobs_mod::routing_jobs_list(),
         _ => log::info!("unrecognized hash routing method: {}", param1),
     }
 }
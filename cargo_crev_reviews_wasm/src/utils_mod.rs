@@ -15,3 +15,12 @@ pub fn crate_version_split(crate_name_version: &str) -> (String, String) {
 pub fn crate_version_join(crate_name: &str, crate_version: &str) -> String {
     format!("{} {}", crate_name, crate_version)
 }
+
+/// the same id as the templating gives to rows: name, name(1), name(2),...
+pub fn row_element_id(element_prefix: &str, row_number: usize) -> String {
+    if row_number > 0 {
+        format!("{}({})", element_prefix, row_number)
+    } else {
+        element_prefix.to_string()
+    }
+}
@@ -114,12 +114,29 @@ pub fn set_text(element_id: &str, text: &str) {
     div.set_inner_text(text);
 }
 
+/// the element is in the document
+pub fn element_exists(element_id: &str) -> bool {
+    unwrap!(window().document()).get_element_by_id(element_id).is_some()
+}
+
 /// set inner html to element_id
 pub fn set_inner_html(element_id: &str, inner_html: &str) {
     let div = get_element_by_id(element_id);
     div.set_inner_html(inner_html);
 }
 
+/// get item from the localStorage of this origin
+pub fn local_storage_get_item(key: &str) -> Option<String> {
+    window().local_storage().ok().flatten().and_then(|storage| storage.get_item(key).ok().flatten())
+}
+
+/// set item into the localStorage of this origin
+pub fn local_storage_set_item(key: &str, value: &str) {
+    if let Some(storage) = window().local_storage().ok().flatten() {
+        unwrap!(storage.set_item(key, value));
+    }
+}
+
 /// fetch in Rust with async await for executor spawn_local()
 /// return the response as String. Any error will panic.
 pub async fn fetch_response(url: &str) -> String {
@@ -180,6 +197,12 @@ pub fn get_input_element_value_string_by_id(element_id: &str) -> String {
     input_html_element.value()
 }
 
+/// set input element value string by id
+pub fn set_input_element_value_string_by_id(element_id: &str, value: &str) {
+    let input_html_element = get_input_html_element_by_id(element_id);
+    input_html_element.set_value(value);
+}
+
 /// get elements by name for example radio group
 pub fn get_value_of_radio_group_by_name(form_element_name: &str) -> String {
     let document = unwrap!(window().document());
@@ -192,6 +215,16 @@ pub fn get_value_of_radio_group_by_name(form_element_name: &str) -> String {
     radio_node_list.value()
 }
 
+/// value of the checked radio button in the whole document, also outside of the first form (modal)
+/// returns an empty string if nothing is checked
+pub fn get_value_of_checked_radio_by_name(name: &str) -> String {
+    let document = unwrap!(window().document());
+    match unwrap!(document.query_selector(&format!("input[name='{}']:checked", name))) {
+        None => String::new(),
+        Some(element) => unwrap!(element.dyn_into::<web_sys::HtmlInputElement>()).value(),
+    }
+}
+
 /// get input element value string by id
 pub fn get_text_area_element_value_string_by_id(element_id: &str) -> String {
     let element = get_element_by_id(element_id);
@@ -219,6 +252,15 @@ pub fn show_snackbar() {
     closure.forget();
 }
 
+/// run the function once after the timeout in milliseconds
+pub fn set_timeout(timeout_ms: i32, function: impl FnOnce() + 'static) {
+    let closure = wasm_bindgen::prelude::Closure::once(function);
+    window()
+        .set_timeout_with_callback_and_timeout_and_arguments_0(closure.as_ref().unchecked_ref(), timeout_ms)
+        .unwrap();
+    closure.forget();
+}
+
 /// close the windows/tab in the browser. It works only if it was opened in script wit window.open().
 pub fn close_tab() {
     unwrap!(window().close());